@@ -12,4 +12,8 @@ function fibonacci(n) {
     return fibonacci(n - 1) + fibonacci(n - 2);
 }
 
-module.exports = { multiply, reverse, fibonacci };
+function range(n) {
+    return Array.from({ length: n }, (_, i) => i + 1);
+}
+
+module.exports = { multiply, reverse, fibonacci, range };
@@ -0,0 +1,242 @@
+use crate::models::{AssertionResult, ExecutionResult, Expectation};
+use crate::values;
+use regex::Regex;
+use serde_json::Value;
+
+/// Checks every expectation of a step against its result.
+pub fn check_all(expectations: &[Expectation], result: &ExecutionResult) -> Vec<AssertionResult> {
+    expectations.iter().map(|e| check(e, result)).collect()
+}
+
+pub fn check(expectation: &Expectation, result: &ExecutionResult) -> AssertionResult {
+    let actual = result.output_value();
+    let outcome: Result<(), String> = match expectation {
+        Expectation::Equals { value } => {
+            if values_equal(&actual, value) {
+                Ok(())
+            } else {
+                Err(format!("got {}", values::display(&actual)))
+            }
+        }
+        Expectation::Contains { value } => {
+            let found = match &actual {
+                Value::Array(items) => items
+                    .iter()
+                    .any(|item| values_equal(item, &values::parse_arg(value))),
+                _ => result.output.contains(value.as_str()),
+            };
+            if found {
+                Ok(())
+            } else {
+                Err(format!("got {}", result.output))
+            }
+        }
+        Expectation::Regex { pattern } => match Regex::new(pattern) {
+            Ok(re) if re.is_match(&result.output) => Ok(()),
+            Ok(_) => Err(format!("got {}", result.output)),
+            Err(e) => Err(format!("invalid pattern: {}", e)),
+        },
+        Expectation::JsonPath { path, value } => match json_path(&actual, path) {
+            Some(found) if values_equal(found, value) => Ok(()),
+            Some(found) => Err(format!("{} is {}", path, values::display(found))),
+            None => Err(format!("{} not found", path)),
+        },
+        Expectation::MaxDuration { ms } => {
+            if result.duration_ms <= *ms {
+                Ok(())
+            } else {
+                Err(format!("took {}ms", result.duration_ms))
+            }
+        }
+    };
+
+    let (passed, message) = match outcome {
+        Ok(()) => (true, String::new()),
+        Err(message) => (false, message),
+    };
+
+    AssertionResult {
+        expectation: describe(expectation),
+        passed,
+        message,
+    }
+}
+
+/// Compares two values, treating integers and floats with the same value as
+/// equal since scripting languages disagree on which one they return.
+pub fn values_equal(a: &Value, b: &Value) -> bool {
+    match (a, b) {
+        (Value::Number(x), Value::Number(y)) => x.as_f64() == y.as_f64(),
+        (Value::Array(x), Value::Array(y)) => {
+            x.len() == y.len() && x.iter().zip(y).all(|(a, b)| values_equal(a, b))
+        }
+        (Value::Object(x), Value::Object(y)) => {
+            x.len() == y.len()
+                && x.iter()
+                    .all(|(k, v)| y.get(k).is_some_and(|w| values_equal(v, w)))
+        }
+        _ => a == b,
+    }
+}
+
+/// Looks up a dotted path such as `$.user.tags[0]` or `items.2.name`.
+pub fn json_path<'a>(value: &'a Value, path: &str) -> Option<&'a Value> {
+    let path = path.trim();
+    let path = path.strip_prefix('$').unwrap_or(path);
+
+    let mut current = value;
+    for segment in path.split('.').filter(|s| !s.is_empty()) {
+        let (key, indices) = match segment.find('[') {
+            Some(i) => (&segment[..i], &segment[i..]),
+            None => (segment, ""),
+        };
+
+        if !key.is_empty() {
+            current = match current {
+                Value::Object(map) => map.get(key)?,
+                Value::Array(items) => items.get(key.parse::<usize>().ok()?)?,
+                _ => return None,
+            };
+        }
+
+        for index in indices.split('[').filter(|s| !s.is_empty()) {
+            let index = index.strip_suffix(']')?.parse::<usize>().ok()?;
+            current = current.as_array()?.get(index)?;
+        }
+    }
+
+    Some(current)
+}
+
+/// Renders an expectation in the syntax accepted by [`parse_expectations`].
+pub fn describe(expectation: &Expectation) -> String {
+    match expectation {
+        Expectation::Equals { value } => format!("= {}", value),
+        Expectation::Contains { value } => format!("contains {}", value),
+        Expectation::Regex { pattern } => format!("matches {}", pattern),
+        Expectation::JsonPath { path, value } => format!("{} = {}", path, value),
+        Expectation::MaxDuration { ms } => format!("<= {}ms", ms),
+    }
+}
+
+pub fn format_expectations(expectations: &[Expectation]) -> String {
+    expectations
+        .iter()
+        .map(describe)
+        .collect::<Vec<_>>()
+        .join("; ")
+}
+
+/// Parses the compact expectation syntax used by the step editor, one
+/// expectation per `;` separated clause:
+///
+/// - `= 120` equals (the value is parsed like an argument)
+/// - `contains Hello` output contains the text, or an array contains the item
+/// - `matches ^Hello.*!$` output matches the regex
+/// - `$.items[0].id = 3` the JSON path equals the value
+/// - `<= 50ms` duration budget
+pub fn parse_expectations(input: &str) -> Result<Vec<Expectation>, String> {
+    input
+        .split(';')
+        .map(str::trim)
+        .filter(|clause| !clause.is_empty())
+        .map(parse_expectation)
+        .collect()
+}
+
+fn parse_expectation(clause: &str) -> Result<Expectation, String> {
+    if let Some(value) = clause.strip_prefix('=') {
+        return Ok(Expectation::Equals {
+            value: values::parse_arg(value.trim()),
+        });
+    }
+
+    if let Some(value) = clause.strip_prefix("contains ") {
+        return Ok(Expectation::Contains {
+            value: value.trim().to_string(),
+        });
+    }
+
+    if let Some(pattern) = clause.strip_prefix("matches ") {
+        let pattern = pattern.trim();
+        Regex::new(pattern).map_err(|e| format!("Invalid regex '{}': {}", pattern, e))?;
+        return Ok(Expectation::Regex {
+            pattern: pattern.to_string(),
+        });
+    }
+
+    if let Some(budget) = clause.strip_prefix("<=") {
+        let budget = budget.trim();
+        let ms = budget
+            .strip_suffix("ms")
+            .unwrap_or(budget)
+            .trim()
+            .parse::<u64>()
+            .map_err(|_| format!("Invalid duration budget '{}'", budget))?;
+        return Ok(Expectation::MaxDuration { ms });
+    }
+
+    if clause.starts_with('$')
+        && let Some((path, value)) = clause.split_once('=')
+    {
+        return Ok(Expectation::JsonPath {
+            path: path.trim().to_string(),
+            value: values::parse_arg(value.trim()),
+        });
+    }
+
+    Err(format!("Unrecognised expectation '{}'", clause))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn parses_and_describes_expectations() {
+        let input = "= 120; contains Hello; matches ^H.*; $.items[0] = 1; <= 50ms";
+        let expectations = parse_expectations(input).unwrap();
+        assert_eq!(
+            expectations,
+            [
+                Expectation::Equals { value: json!(120) },
+                Expectation::Contains {
+                    value: "Hello".to_string()
+                },
+                Expectation::Regex {
+                    pattern: "^H.*".to_string()
+                },
+                Expectation::JsonPath {
+                    path: "$.items[0]".to_string(),
+                    value: json!(1)
+                },
+                Expectation::MaxDuration { ms: 50 },
+            ]
+        );
+        assert_eq!(format_expectations(&expectations), input);
+        assert!(parse_expectations(" ; ").unwrap().is_empty());
+        assert!(parse_expectations("matches (").is_err());
+        assert!(parse_expectations("<= soon").is_err());
+        assert!(parse_expectations("roughly 3").is_err());
+    }
+
+    #[test]
+    fn looks_up_json_paths_and_compares_numbers_loosely() {
+        let value = json!({"user": {"tags": ["a", "b"]}, "items": [{"name": "x"}]});
+        assert_eq!(json_path(&value, "$.user.tags[1]"), Some(&json!("b")));
+        assert_eq!(json_path(&value, "items.0.name"), Some(&json!("x")));
+        assert_eq!(json_path(&value, "$"), Some(&value));
+        assert_eq!(json_path(&value, "$.user.tags[2]"), None);
+        assert_eq!(json_path(&value, "$.missing"), None);
+
+        assert!(values_equal(&json!(3), &json!(3.0)));
+        assert!(values_equal(
+            &json!({"a": [1, 2.0]}),
+            &json!({"a": [1.0, 2]})
+        ));
+        assert!(!values_equal(&json!([1]), &json!([1, 2])));
+        assert!(!values_equal(&json!({"a": 1}), &json!({"b": 1})));
+        assert!(!values_equal(&json!("1"), &json!(1)));
+    }
+}
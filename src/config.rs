@@ -0,0 +1,122 @@
+use crate::time::{self, Timestamp};
+use serde::{Deserialize, Serialize};
+use std::{collections::BTreeMap, fs, io, path::Path};
+
+/// Directory under the scripts directory where project settings and state
+/// are kept.
+pub const DATA_DIR: &str = ".polyglot";
+
+const CONFIG_FILE: &str = "config.json";
+
+/// Project settings read from `.polyglot/config.json`. Every field is
+/// optional and falls back to its default.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Config {
+    pub history: HistoryConfig,
+    pub display: DisplayConfig,
+    pub logs: LogsConfig,
+    pub theme: ThemeConfig,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct HistoryConfig {
+    /// Number of results kept in the history file; 0 turns history off.
+    pub retention: usize,
+}
+
+impl Default for HistoryConfig {
+    fn default() -> Self {
+        Self { retention: 1000 }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DisplayConfig {
+    /// How results, runs and logs show their time: `time`, `datetime`,
+    /// `rfc3339`, `relative` or a strftime pattern.
+    pub timestamps: String,
+    /// Show times in UTC instead of the local timezone.
+    pub utc: bool,
+}
+
+impl Default for DisplayConfig {
+    fn default() -> Self {
+        Self {
+            timestamps: "time".to_string(),
+            utc: false,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct LogsConfig {
+    /// Number of log entries kept in memory for the Logs view.
+    pub buffer: usize,
+    /// Also write the log to `.polyglot/polyglot.log`.
+    pub file: bool,
+    /// Size in KiB past which the log file is rotated; 0 never rotates it.
+    pub max_file_kb: u64,
+    /// Number of rotated log files kept, as `polyglot.log.1` and up.
+    pub max_files: usize,
+}
+
+impl Default for LogsConfig {
+    fn default() -> Self {
+        Self {
+            buffer: 1000,
+            file: true,
+            max_file_kb: 1024,
+            max_files: 3,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ThemeConfig {
+    /// `dark`, `light` or `high-contrast`.
+    pub name: String,
+    /// Draw icons and arrows as ASCII.
+    pub ascii: bool,
+    /// Colours replacing those of the theme, by role.
+    pub colors: BTreeMap<String, String>,
+}
+
+impl Default for ThemeConfig {
+    fn default() -> Self {
+        Self {
+            name: "dark".to_string(),
+            ascii: false,
+            colors: BTreeMap::new(),
+        }
+    }
+}
+
+impl DisplayConfig {
+    pub fn time(&self, timestamp: &Timestamp) -> String {
+        time::format(timestamp, &self.timestamps, self.utc)
+    }
+}
+
+impl Config {
+    /// Reads the settings of the project at `root_dir`. A missing file gives
+    /// the defaults.
+    pub fn load(root_dir: &Path) -> Result<Self, String> {
+        let path = root_dir.join(DATA_DIR).join(CONFIG_FILE);
+        match fs::read_to_string(&path) {
+            Ok(content) => {
+                let config: Self = serde_json::from_str(&content)
+                    .map_err(|e| format!("Invalid settings in {}: {}", path.display(), e))?;
+                time::validate_format(&config.display.timestamps)
+                    .map_err(|e| format!("{} in {}", e, path.display()))?;
+                Ok(config)
+            }
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(e) => Err(format!("Failed to read {}: {}", path.display(), e)),
+        }
+    }
+}
@@ -0,0 +1,102 @@
+use crate::assertions::values_equal;
+use crate::models::ExecutionResult;
+use serde_json::Value;
+
+/// One difference between two JSON values, at a path in the syntax of
+/// `assertions::json_path`. `old` is `None` for added values and `new` for
+/// removed ones.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Change {
+    pub path: String,
+    pub old: Option<Value>,
+    pub new: Option<Value>,
+}
+
+/// Structural differences from `old` to `new`. Object members are matched by
+/// key and array items by index; numbers are compared by value.
+pub fn diff(old: &Value, new: &Value) -> Vec<Change> {
+    let mut changes = Vec::new();
+    walk("$", old, new, &mut changes);
+    changes
+}
+
+/// Whether two results of the same call disagree: one failed and the other
+/// did not, or they returned different values.
+pub fn outputs_differ(a: &ExecutionResult, b: &ExecutionResult) -> bool {
+    a.success != b.success || !values_equal(&a.output_value(), &b.output_value())
+}
+
+fn walk(path: &str, old: &Value, new: &Value, changes: &mut Vec<Change>) {
+    match (old, new) {
+        (Value::Object(a), Value::Object(b)) => {
+            for (key, value) in a {
+                let path = member_path(path, key);
+                match b.get(key) {
+                    Some(other) => walk(&path, value, other, changes),
+                    None => changes.push(Change {
+                        path,
+                        old: Some(value.clone()),
+                        new: None,
+                    }),
+                }
+            }
+            for (key, value) in b.iter().filter(|(key, _)| !a.contains_key(*key)) {
+                changes.push(Change {
+                    path: member_path(path, key),
+                    old: None,
+                    new: Some(value.clone()),
+                });
+            }
+        }
+        (Value::Array(a), Value::Array(b)) => {
+            for i in 0..a.len().max(b.len()) {
+                let path = format!("{}[{}]", path, i);
+                match (a.get(i), b.get(i)) {
+                    (Some(x), Some(y)) => walk(&path, x, y, changes),
+                    (x, y) => changes.push(Change {
+                        path,
+                        old: x.cloned(),
+                        new: y.cloned(),
+                    }),
+                }
+            }
+        }
+        _ if values_equal(old, new) => {}
+        _ => changes.push(Change {
+            path: path.to_string(),
+            old: Some(old.clone()),
+            new: Some(new.clone()),
+        }),
+    }
+}
+
+fn member_path(parent: &str, key: &str) -> String {
+    format!("{}.{}", parent, key)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn reports_changed_added_and_removed_members() {
+        let old = json!({"total": 3, "items": [1, 2], "tags": {"a": true}, "gone": "x"});
+        let new = json!({"total": 3.0, "items": [1, 5, 6], "tags": {"a": false}, "extra": null});
+
+        let changes = diff(&old, &new);
+        let paths: Vec<&str> = changes.iter().map(|c| c.path.as_str()).collect();
+        assert_eq!(
+            paths,
+            ["$.gone", "$.items[1]", "$.items[2]", "$.tags.a", "$.extra"]
+        );
+        assert_eq!(changes[0].new, None);
+        assert_eq!(changes[1].old, Some(json!(2)));
+        assert_eq!(changes[1].new, Some(json!(5)));
+        assert_eq!(changes[2].old, None);
+        assert_eq!(changes[4].new, Some(Value::Null));
+
+        assert!(diff(&json!("same"), &json!("same")).is_empty());
+        assert_eq!(diff(&json!(1), &json!("1"))[0].path, "$");
+    }
+}
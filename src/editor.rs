@@ -0,0 +1,255 @@
+use crate::theme::Theme;
+use ratatui::{
+    style::{Modifier, Style},
+    widgets::{Block, Borders},
+};
+use serde_json::Value;
+use tui_textarea::TextArea;
+
+/// The argument editor of the Function Tester. Each line is one argument,
+/// except that a JSON object or array continues over the following lines
+/// until its brackets are closed.
+pub struct ArgEditor {
+    textarea: TextArea<'static>,
+    /// Argument lists offered by history recall, newest first.
+    recall: Vec<Vec<String>>,
+    /// Position in `recall` of the list being shown, if any.
+    recalled: Option<usize>,
+    /// The text typed before history recall replaced it.
+    draft: Vec<String>,
+    block: Block<'static>,
+    placeholder: Style,
+}
+
+impl ArgEditor {
+    pub fn new(args: &[String], recall: Vec<Vec<String>>, theme: &Theme) -> Self {
+        let mut editor = Self {
+            textarea: TextArea::default(),
+            recall,
+            recalled: None,
+            draft: Vec::new(),
+            block: Block::default()
+                .title(theme.icons("⚙️  Arguments - one per line").into_owned())
+                .borders(Borders::ALL)
+                .border_style(Style::default().fg(theme.highlight)),
+            placeholder: Style::default().fg(theme.subtle),
+        };
+        editor.set_args(args);
+        editor
+    }
+
+    pub fn textarea(&self) -> &TextArea<'static> {
+        &self.textarea
+    }
+
+    pub fn textarea_mut(&mut self) -> &mut TextArea<'static> {
+        &mut self.textarea
+    }
+
+    /// The arguments as typed.
+    pub fn args(&self) -> Vec<String> {
+        split_args(self.textarea.lines())
+    }
+
+    /// Replaces the text with an older argument list from the history, or a
+    /// newer one, ending with the text typed before. Returns false when there
+    /// is nothing further in that direction.
+    pub fn recall(&mut self, older: bool) -> bool {
+        let next = match (self.recalled, older) {
+            (None, true) if !self.recall.is_empty() => Some(0),
+            (Some(i), true) if i + 1 < self.recall.len() => Some(i + 1),
+            (Some(0), false) => None,
+            (Some(i), false) => Some(i - 1),
+            _ => return false,
+        };
+        if self.recalled.is_none() {
+            self.draft = self.textarea.lines().to_vec();
+        }
+        self.recalled = next;
+        match next {
+            Some(i) => {
+                let args = self.recall[i].clone();
+                self.set_args(&args);
+            }
+            None => self.set_lines(self.draft.clone()),
+        }
+        true
+    }
+
+    /// The position of the recalled list and the number of lists, for
+    /// display.
+    pub fn recall_position(&self) -> (Option<usize>, usize) {
+        (self.recalled, self.recall.len())
+    }
+
+    /// Shows an argument list, one argument per line. Multi-line JSON keeps
+    /// its lines; other text that would not split back into the same
+    /// argument, such as a string with line breaks, is written as a JSON
+    /// string.
+    fn set_args(&mut self, args: &[String]) {
+        let lines = args
+            .iter()
+            .flat_map(|arg| {
+                let ambiguous = arg.contains('\n') || arg.trim_start().starts_with(['[', '{']);
+                if ambiguous && serde_json::from_str::<Value>(arg.trim()).is_err() {
+                    vec![Value::String(arg.clone()).to_string()]
+                } else {
+                    arg.lines().map(str::to_string).collect()
+                }
+            })
+            .collect();
+        self.set_lines(lines);
+    }
+
+    fn set_lines(&mut self, lines: Vec<String>) {
+        let mut textarea = TextArea::new(lines);
+        textarea.set_block(self.block.clone());
+        textarea.set_cursor_line_style(Style::default());
+        textarea.set_cursor_style(Style::default().add_modifier(Modifier::REVERSED));
+        textarea.set_placeholder_text("e.g. 42, \"text\" or {\"key\": [1, 2]}");
+        textarea.set_placeholder_style(self.placeholder);
+        textarea.move_cursor(tui_textarea::CursorMove::Bottom);
+        textarea.move_cursor(tui_textarea::CursorMove::End);
+        self.textarea = textarea;
+    }
+}
+
+/// Splits editor lines into arguments: one per non-blank line, except that
+/// a line opening a JSON object or array is joined with the following ones
+/// until its brackets balance, if the joined text parses as JSON.
+pub fn split_args(lines: &[String]) -> Vec<String> {
+    let mut args = Vec::new();
+    let mut i = 0;
+    while i < lines.len() {
+        let line = &lines[i];
+        if line.trim().is_empty() {
+            i += 1;
+        } else if line.trim_start().starts_with(['[', '{'])
+            && let Some(end) = json_end(lines, i)
+        {
+            args.push(lines[i..end].join("\n"));
+            i = end;
+        } else {
+            args.push(line.clone());
+            i += 1;
+        }
+    }
+    args
+}
+
+/// The index after the line where the brackets opened at `start` balance,
+/// when the lines up to there parse as JSON.
+fn json_end(lines: &[String], start: usize) -> Option<usize> {
+    let mut depth = 0;
+    for (i, line) in lines.iter().enumerate().skip(start) {
+        depth += bracket_depth(line);
+        if depth <= 0 {
+            let joined = lines[start..=i].join("\n");
+            return serde_json::from_str::<Value>(&joined)
+                .is_ok()
+                .then_some(i + 1);
+        }
+    }
+    None
+}
+
+/// Opening minus closing brackets in a line, ignoring those in JSON strings.
+fn bracket_depth(line: &str) -> i32 {
+    let mut depth = 0;
+    let mut in_string = false;
+    let mut escaped = false;
+    for c in line.chars() {
+        match c {
+            _ if escaped => escaped = false,
+            '\\' if in_string => escaped = true,
+            '"' => in_string = !in_string,
+            '[' | '{' if !in_string => depth += 1,
+            ']' | '}' if !in_string => depth -= 1,
+            _ => {}
+        }
+    }
+    depth
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lines(text: &str) -> Vec<String> {
+        text.lines().map(str::to_string).collect()
+    }
+
+    #[test]
+    fn joins_multi_line_json_values() {
+        let args = split_args(&lines(
+            "42\n\nhello world\n{\n  \"items\": [1, 2],\n  \"note\": \"}\"\n}\n[1,\n 2]\n\"x\"",
+        ));
+        assert_eq!(
+            args,
+            [
+                "42",
+                "hello world",
+                "{\n  \"items\": [1, 2],\n  \"note\": \"}\"\n}",
+                "[1,\n 2]",
+                "\"x\"",
+            ]
+        );
+        assert_eq!(
+            crate::values::parse_arg(&args[2])["items"],
+            serde_json::json!([1, 2])
+        );
+        assert_eq!(split_args(&lines("{\"open\": 1")), ["{\"open\": 1"]);
+    }
+
+    #[test]
+    fn keeps_lines_that_only_look_like_json_apart() {
+        assert_eq!(
+            split_args(&lines("[not json\nnext\n]\n{\"a\": 1}")),
+            ["[not json", "next", "]", "{\"a\": 1}"]
+        );
+    }
+
+    #[test]
+    fn recalls_multi_line_and_bracketed_strings_as_one_argument() {
+        let recalled = vec![
+            "line one\nline two".to_string(),
+            "[".to_string(),
+            "1]".to_string(),
+            "{\n  \"a\": 1\n}".to_string(),
+        ];
+        let mut editor = ArgEditor::new(&[], vec![recalled.clone()], &Theme::default());
+        assert!(editor.recall(true));
+
+        let args = editor.args();
+        assert_eq!(args.len(), 4);
+        let values: Vec<Value> = args.iter().map(|a| crate::values::parse_arg(a)).collect();
+        assert_eq!(
+            values,
+            [
+                Value::from("line one\nline two"),
+                Value::from("["),
+                Value::from("1]"),
+                serde_json::json!({"a": 1}),
+            ]
+        );
+    }
+
+    #[test]
+    fn recalls_older_argument_lists_and_the_draft() {
+        let recall = vec![
+            vec!["2".to_string()],
+            vec!["1".to_string(), "x".to_string()],
+        ];
+        let mut editor = ArgEditor::new(&["draft".to_string()], recall, &Theme::default());
+        assert!(!editor.recall(false));
+        assert!(editor.recall(true));
+        assert_eq!(editor.args(), ["2"]);
+        assert!(editor.recall(true));
+        assert_eq!(editor.args(), ["1", "x"]);
+        assert!(!editor.recall(true));
+        assert!(editor.recall(false));
+        assert!(editor.recall(false));
+        assert_eq!(editor.args(), ["draft"]);
+        assert_eq!(editor.recall_position(), (None, 2));
+    }
+}
@@ -0,0 +1,382 @@
+use super::{ExportSource, comment, references, script_location};
+use crate::models::{ParamType, PipelineParam, StepKind};
+use crate::{params, values};
+use serde_json::Value;
+use std::{collections::BTreeSet, fmt::Write as _, path::Path};
+
+/// A shell script that replays the pipeline in `metacallcli`. The CLI has no
+/// variables, so `${step}` references are replaced by the step's last
+/// observed output and map steps are unrolled over it. Parameters and
+/// environment variables are expanded by the shell.
+pub fn script(source: &ExportSource, dir: &Path, relative_paths: bool) -> String {
+    let mut notes = Vec::new();
+    let mut commands = String::new();
+    let mut escaped = BTreeSet::new();
+
+    let mut loaded = BTreeSet::new();
+    for step in source.steps {
+        if !loaded.insert(step.script.as_str()) {
+            continue;
+        }
+        let Some(script) = source.scripts.iter().find(|s| s.name == step.script) else {
+            notes.push(format!(
+                "{} was not found when the pipeline was exported",
+                step.script
+            ));
+            continue;
+        };
+        let (absolute, relative) = script_location(&script.path, dir);
+        let path = if relative_paths {
+            format!("$HERE/{}", heredoc_escape(&relative))
+        } else {
+            heredoc_escape(&absolute)
+        };
+        let _ = writeln!(commands, "load {} {}", script.runtime, path);
+    }
+
+    for step in source.steps {
+        for name in step.args.iter().flat_map(|arg| references(arg)) {
+            if is_unrecorded(source, name) {
+                notes.push(format!(
+                    "{} uses ${{{}}}, which has no recorded value and is passed as null",
+                    step.id, name
+                ));
+            }
+        }
+
+        let function = heredoc_escape(&step.function);
+        let args: Vec<String> = step
+            .args
+            .iter()
+            .map(|arg| arg_text(source, arg, &mut escaped))
+            .collect();
+        match &step.kind {
+            StepKind::Call => {
+                let _ = writeln!(commands, "call {}({})", function, args.join(", "));
+            }
+            StepKind::Map { over } => {
+                let items = match over_items(source, over) {
+                    Some(items) => items,
+                    None => {
+                        notes.push(format!(
+                            "{} maps over {}, which has no recorded array value and is skipped",
+                            step.id,
+                            over.trim()
+                        ));
+                        continue;
+                    }
+                };
+                for item in items {
+                    let call_args: Vec<String> = std::iter::once(heredoc_escape(&item.to_string()))
+                        .chain(args.iter().cloned())
+                        .collect();
+                    let _ = writeln!(commands, "call {}({})", function, call_args.join(", "));
+                }
+            }
+        }
+    }
+    commands.push_str("exit\n");
+
+    for param in source.params.iter().filter(|p| !is_shell_name(&p.name)) {
+        notes.push(format!(
+            "parameter {} is not a valid shell variable name and always uses its default",
+            param.name
+        ));
+    }
+
+    let mut out = String::new();
+    let _ = writeln!(
+        out,
+        "#!/bin/sh\n# Generated MetaCall Pipeline\n# Export Date: {}\n#",
+        source.timestamp
+    );
+    out.push_str("# Replays the pipeline in metacallcli. Parameters are read from environment\n");
+    out.push_str("# variables of the same name, e.g. `name=Ada ./pipeline.sh`.\n");
+    for note in &notes {
+        let _ = writeln!(out, "#\n# Note: {}", comment(note));
+    }
+    out.push_str("\nset -e\n");
+    if relative_paths {
+        out.push_str("HERE=\"$(cd \"$(dirname \"$0\")\" && pwd)\"\n");
+    }
+
+    let shell_params: Vec<&PipelineParam> = source
+        .params
+        .iter()
+        .filter(|p| is_shell_name(&p.name))
+        .collect();
+    if !shell_params.is_empty() {
+        out.push('\n');
+    }
+    for param in shell_params {
+        let default = param
+            .default
+            .as_ref()
+            .and_then(|d| params::coerce(param, d).ok())
+            .map(|value| param_text(param, &value));
+        let _ = match default {
+            Some(default) => writeln!(
+                out,
+                ": \"${{{}={}}}\"",
+                param.name,
+                double_quote_escape(&default)
+            ),
+            None => writeln!(
+                out,
+                ": \"${{{}:?Missing value for parameter {}}}\"",
+                param.name, param.name
+            ),
+        };
+    }
+
+    // Text from the environment is escaped before it is spliced into JSON.
+    if !escaped.is_empty() {
+        out.push_str(JSON_STRING);
+    }
+    for var in &escaped {
+        let check = if source.params.iter().any(|p| &p.name == var) {
+            String::new()
+        } else {
+            format!("?Environment variable {} is not set", var)
+        };
+        let _ = writeln!(out, "{}_json=$(json_string \"${{{}{}}}\")", var, var, check);
+    }
+
+    out.push_str("\nmetacallcli <<METACALL\n");
+    out.push_str(&commands);
+    out.push_str("METACALL\n");
+
+    out
+}
+
+const JSON_STRING: &str = r#"
+json_string() {
+    printf '%s' "$1" | sed -e ':a' -e '$!N' -e '$!ba' -e 's/\\/\\\\/g' -e 's/"/\\"/g' -e 's/\n/\\n/g'
+}
+"#;
+
+/// Whether a parameter can be read from an environment variable.
+fn is_shell_name(name: &str) -> bool {
+    name.starts_with(|c: char| c.is_ascii_alphabetic() || c == '_')
+        && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+/// Whether a reference points at a step without a recorded output.
+fn is_unrecorded(source: &ExportSource, name: &str) -> bool {
+    !name.starts_with("params.") && !name.starts_with("env.") && !source.observed.contains_key(name)
+}
+
+/// An argument as a `metacallcli` call argument, escaped for an unquoted
+/// heredoc so `${param}` references still expand. Variables whose text needs
+/// JSON escaping are added to `escaped`.
+fn arg_text(source: &ExportSource, arg: &str, escaped: &mut BTreeSet<String>) -> String {
+    let trimmed = arg.trim();
+    if let Some(name) = trimmed
+        .strip_prefix("${")
+        .and_then(|rest| rest.strip_suffix('}'))
+        && !name.contains(['{', '}'])
+    {
+        return reference_text(source, name, true, escaped);
+    }
+
+    if references(arg).is_empty() {
+        return heredoc_escape(&values::parse_arg(arg).to_string());
+    }
+
+    // Text with embedded references is passed as a JSON string.
+    let mut text = String::new();
+    let mut rest = arg;
+    while let Some(start) = rest.find("${") {
+        let Some(end) = rest[start..].find('}') else {
+            break;
+        };
+        text.push_str(&string_body(&rest[..start]));
+        text.push_str(&reference_text(
+            source,
+            &rest[start + 2..start + end],
+            false,
+            escaped,
+        ));
+        rest = &rest[start + end + 1..];
+    }
+    text.push_str(&string_body(rest));
+    format!("\"{}\"", text)
+}
+
+/// A reference as shell text. Whole arguments keep their JSON form, while
+/// references inside a string are spliced in as text.
+fn reference_text(
+    source: &ExportSource,
+    name: &str,
+    whole: bool,
+    escaped: &mut BTreeSet<String>,
+) -> String {
+    let mut escaped_var = |var: &str| {
+        escaped.insert(var.to_string());
+        if whole {
+            format!("\"${{{}_json}}\"", var)
+        } else {
+            format!("${{{}_json}}", var)
+        }
+    };
+
+    if let Some(var) = name.strip_prefix("env.") {
+        if is_shell_name(var) {
+            return escaped_var(var);
+        }
+        return if whole {
+            "null".to_string()
+        } else {
+            String::new()
+        };
+    }
+
+    let value = match name
+        .strip_prefix("params.")
+        .and_then(|p| source.params.iter().find(|param| param.name == p))
+    {
+        Some(param) if is_shell_name(&param.name) => {
+            return match param.kind {
+                ParamType::String => escaped_var(&param.name),
+                ParamType::Json if !whole => escaped_var(&param.name),
+                _ => format!("${{{}}}", param.name),
+            };
+        }
+        Some(param) => param
+            .default
+            .as_ref()
+            .and_then(|d| params::coerce(param, d).ok())
+            .unwrap_or(Value::Null),
+        None => source.observed.get(name).cloned().unwrap_or(Value::Null),
+    };
+    if whole {
+        heredoc_escape(&value.to_string())
+    } else {
+        string_body(&values::display(&value))
+    }
+}
+
+/// The items a map step runs over, when they are known at export time.
+fn over_items(source: &ExportSource, over: &str) -> Option<Vec<Value>> {
+    let trimmed = over.trim();
+    let value = match trimmed
+        .strip_prefix("${")
+        .and_then(|rest| rest.strip_suffix('}'))
+    {
+        Some(name) => match name.strip_prefix("params.") {
+            Some(param) => source
+                .params
+                .iter()
+                .find(|p| p.name == param)
+                .and_then(|p| p.default.as_ref().and_then(|d| params::coerce(p, d).ok()))
+                .or_else(|| source.last_params.get(param).cloned())?,
+            None => source.observed.get(name)?.clone(),
+        },
+        None => values::parse_arg(trimmed),
+    };
+
+    match value {
+        Value::Array(items) => Some(items),
+        _ => None,
+    }
+}
+
+/// How a parameter default is spelled in the shell: strings as raw text,
+/// other types in their JSON form.
+fn param_text(param: &PipelineParam, value: &Value) -> String {
+    match param.kind {
+        ParamType::String => values::display(value),
+        _ => value.to_string(),
+    }
+}
+
+/// Text escaped to sit inside a JSON string in an unquoted heredoc.
+fn string_body(text: &str) -> String {
+    let json = Value::String(text.to_string()).to_string();
+    heredoc_escape(&json[1..json.len() - 1])
+}
+
+/// Escapes the characters an unquoted heredoc would interpret.
+fn heredoc_escape(text: &str) -> String {
+    text.replace('\\', "\\\\")
+        .replace('$', "\\$")
+        .replace('`', "\\`")
+}
+
+/// Escapes text for a double quoted shell string.
+fn double_quote_escape(text: &str) -> String {
+    text.replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('$', "\\$")
+        .replace('`', "\\`")
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use crate::export::tests::Fixture;
+    use std::os::unix::fs::PermissionsExt;
+    use std::process::Command;
+
+    /// Runs the script with a `metacallcli` that echoes its input, and checks
+    /// that the arguments of every call are valid JSON after expansion.
+    #[test]
+    fn script_expands_to_valid_calls() {
+        let root = std::env::temp_dir().join(format!("polyglot-cli-{}", std::process::id()));
+        let bin = root.join("bin");
+        std::fs::create_dir_all(&bin).unwrap();
+        let fixture = Fixture::new(&root.join("scripts"));
+
+        let cli = bin.join("metacallcli");
+        std::fs::write(&cli, "#!/bin/sh\ncat\n").unwrap();
+        std::fs::set_permissions(&cli, std::fs::Permissions::from_mode(0o755)).unwrap();
+        let script = root.join("pipeline.sh");
+        std::fs::write(&script, super::script(&fixture.source(), &root, true)).unwrap();
+
+        let path = format!(
+            "{}:{}",
+            bin.display(),
+            std::env::var("PATH").unwrap_or_default()
+        );
+        let output = match Command::new("sh")
+            .arg(&script)
+            .env("PATH", path)
+            .env("HOME", "/home/\"quoted\" \\ user")
+            .env("name", "line one\nline \"two\" \\ $x")
+            .env("required", "`tick`")
+            .output()
+        {
+            Ok(output) => output,
+            Err(_) => {
+                eprintln!("sh is not available, skipping");
+                return;
+            }
+        };
+        let _ = std::fs::remove_dir_all(&root);
+        assert!(
+            output.status.success(),
+            "script failed:\n{}",
+            String::from_utf8_lossy(&output.stderr)
+        );
+
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        let calls: Vec<Vec<Value>> = stdout
+            .lines()
+            .filter_map(|line| line.strip_prefix("call "))
+            .map(|call| {
+                let args = &call[call.find('(').unwrap() + 1..call.len() - 1];
+                serde_json::from_str(&format!("[{}]", args))
+                    .unwrap_or_else(|e| panic!("invalid arguments in {}: {}", call, e))
+            })
+            .collect();
+
+        assert_eq!(calls.len(), 8);
+        assert_eq!(calls[1][0], "line one\nline \"two\" \\ $x");
+        assert_eq!(
+            calls[6][0],
+            "[1.0,4.0,9.0] from /home/\"quoted\" \\ user: \"line one\nline \"two\" \\ $x\" {x}"
+        );
+        assert_eq!(calls[7][3], "`tick`");
+        assert_eq!(calls[7][6], "42");
+    }
+}
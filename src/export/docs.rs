@@ -0,0 +1,160 @@
+use super::{ExportSource, identifier, pipeline_file, references, truncate};
+use crate::assertions;
+use crate::models::StepKind;
+use crate::values;
+use std::{collections::BTreeSet, fmt::Write as _};
+
+/// Documents the pipeline: its parameters, a table of the steps with their
+/// last recorded outputs, the flow diagram and the pipeline JSON.
+pub fn markdown(source: &ExportSource) -> String {
+    let mut out = String::new();
+    let _ = writeln!(
+        out,
+        "# Pipeline\n\nExported on {} with {} steps.\n",
+        source.timestamp,
+        source.steps.len()
+    );
+
+    if !source.params.is_empty() {
+        out.push_str("## Parameters\n\n| Name | Type | Default |\n| --- | --- | --- |\n");
+        for param in source.params {
+            let _ = writeln!(
+                out,
+                "| {} | {} | {} |",
+                cell(&param.name),
+                param.kind.name(),
+                param.default.as_deref().map(code).unwrap_or_default()
+            );
+        }
+        out.push('\n');
+    }
+
+    out.push_str("## Steps\n\n");
+    out.push_str("| # | Id | Call | Script | Arguments | Expectations | Last output |\n");
+    out.push_str("| --- | --- | --- | --- | --- | --- | --- |\n");
+    for (i, step) in source.steps.iter().enumerate() {
+        let call = match &step.kind {
+            StepKind::Call => code(&step.function),
+            StepKind::Map { over } => {
+                format!("{} over {}", code(&step.function), code(over.trim()))
+            }
+        };
+        let args: Vec<String> = step.args.iter().map(|a| code(a)).collect();
+        let output = source
+            .observed
+            .get(&step.id)
+            .map(|value| code(&truncate(&values::display(value), 60)))
+            .unwrap_or_default();
+
+        let _ = writeln!(
+            out,
+            "| {} | {} | {} | {} | {} | {} | {} |",
+            i + 1,
+            cell(&step.id),
+            call,
+            cell(&step.script),
+            args.join(", "),
+            cell(&assertions::format_expectations(&step.expect)),
+            output
+        );
+    }
+
+    out.push_str("\n## Flow\n\n```mermaid\n");
+    out.push_str(&mermaid(source));
+    out.push_str("```\n\n## Pipeline JSON\n\n```json\n");
+    out.push_str(&serde_json::to_string_pretty(&pipeline_file(source)).unwrap_or_default());
+    out.push_str("\n```\n");
+
+    out
+}
+
+/// A flowchart of the steps in order, with dotted edges for the values that
+/// flow through `${...}` references.
+pub fn mermaid(source: &ExportSource) -> String {
+    let mut out = String::from("flowchart TD\n");
+
+    for param in source.params {
+        let _ = writeln!(
+            out,
+            "    param_{}([\"{}: {}\"])",
+            identifier(&param.name),
+            label(&param.name),
+            param.kind.name()
+        );
+    }
+
+    for (i, step) in source.steps.iter().enumerate() {
+        let text = format!(
+            "{}<br/>{} · {}",
+            label(&step.id),
+            label(&step.function),
+            label(&step.script)
+        );
+        let _ = match step.kind {
+            StepKind::Call => writeln!(out, "    step{}[\"{}\"]", i + 1, text),
+            StepKind::Map { .. } => writeln!(out, "    step{}[[\"map {}\"]]", i + 1, text),
+        };
+    }
+
+    for i in 1..source.steps.len() {
+        let _ = writeln!(out, "    step{} --> step{}", i, i + 1);
+    }
+
+    for (i, step) in source.steps.iter().enumerate() {
+        let over = match &step.kind {
+            StepKind::Map { over } => Some(over.as_str()),
+            StepKind::Call => None,
+        };
+        let names: BTreeSet<&str> = step
+            .args
+            .iter()
+            .map(String::as_str)
+            .chain(over)
+            .flat_map(references)
+            .collect();
+
+        for name in names {
+            let from = match name.strip_prefix("params.") {
+                Some(param) if source.params.iter().any(|p| p.name == param) => {
+                    format!("param_{}", identifier(param))
+                }
+                Some(_) => continue,
+                None => match source.steps[..i].iter().position(|s| s.id == name) {
+                    Some(j) => format!("step{}", j + 1),
+                    None => continue,
+                },
+            };
+            let _ = writeln!(out, "    {} -.-> step{}", from, i + 1);
+        }
+    }
+
+    out
+}
+
+/// Text for a Markdown table cell.
+fn cell(text: &str) -> String {
+    text.replace('|', "\\|").replace(['\n', '\r'], " ")
+}
+
+/// An inline code span, with a double fence when the text has backticks.
+fn code(text: &str) -> String {
+    let text = cell(text);
+    if text.is_empty() {
+        return String::new();
+    }
+    if text.contains('`') {
+        format!("`` {} ``", text)
+    } else {
+        format!("`{}`", text)
+    }
+}
+
+/// Text for a quoted Mermaid label, using its entity codes for characters
+/// that would end the label or be read as HTML.
+fn label(text: &str) -> String {
+    text.replace('&', "#amp;")
+        .replace('"', "#quot;")
+        .replace('<', "#lt;")
+        .replace('>', "#gt;")
+        .replace(['\n', '\r'], " ")
+}
@@ -0,0 +1,563 @@
+use super::{
+    ExportFile, ExportSource, comment, identifier, package_name, pipeline_json, script_location,
+};
+use crate::models::{ParamType, StepKind};
+use crate::{params, values};
+use serde_json::{Value, json};
+use std::{
+    collections::{BTreeSet, HashMap},
+    fmt::Write as _,
+    path::Path,
+};
+
+/// A dynamically typed language with a MetaCall port, used to host the
+/// pipeline instead of Rust.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Host {
+    Python,
+    Node,
+}
+
+impl Host {
+    pub fn program_name(self) -> &'static str {
+        match self {
+            Host::Python => "main.py",
+            Host::Node => "index.js",
+        }
+    }
+
+    fn literal(self, value: &Value) -> String {
+        match (self, value) {
+            // Integers a JavaScript number cannot hold exactly become BigInts.
+            (Host::Node, Value::Number(n))
+                if !n.is_f64()
+                    && n.as_i64()
+                        .is_none_or(|i| i.unsigned_abs() > MAX_SAFE_INTEGER) =>
+            {
+                format!("{}n", n)
+            }
+            (Host::Node, Value::Array(items)) => format!(
+                "[{}]",
+                items
+                    .iter()
+                    .map(|v| self.literal(v))
+                    .collect::<Vec<_>>()
+                    .join(",")
+            ),
+            (Host::Node, Value::Object(map)) => format!(
+                "{{{}}}",
+                map.iter()
+                    .map(|(k, v)| format!("{}:{}", self.string(k), self.literal(v)))
+                    .collect::<Vec<_>>()
+                    .join(",")
+            ),
+            // JSON is valid JavaScript, and JSON numbers and strings are valid Python.
+            (Host::Node, _) => json_text(value),
+            (Host::Python, Value::Null) => "None".to_string(),
+            (Host::Python, Value::Bool(true)) => "True".to_string(),
+            (Host::Python, Value::Bool(false)) => "False".to_string(),
+            (Host::Python, Value::Array(items)) => format!(
+                "[{}]",
+                items
+                    .iter()
+                    .map(|v| self.literal(v))
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ),
+            (Host::Python, Value::Object(map)) => format!(
+                "{{{}}}",
+                map.iter()
+                    .map(|(k, v)| format!("{}: {}", self.string(k), self.literal(v)))
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ),
+            (Host::Python, other) => json_text(other),
+        }
+    }
+
+    fn string(self, text: &str) -> String {
+        json_text(&Value::String(text.to_string()))
+    }
+
+    fn statement(self, var: &str, expr: &str) -> String {
+        match self {
+            Host::Python => format!("    {} = {}\n", var, expr),
+            Host::Node => format!("    const {} = {};\n", var, expr),
+        }
+    }
+
+    fn line_comment(self, text: &str) -> String {
+        match self {
+            Host::Python => format!("# {}", comment(text)),
+            Host::Node => format!("// {}", comment(text)),
+        }
+    }
+}
+
+/// `Number.MAX_SAFE_INTEGER`, the largest integer JavaScript numbers hold
+/// exactly.
+const MAX_SAFE_INTEGER: u64 = (1 << 53) - 1;
+
+/// Compact JSON with the line and paragraph separators escaped, which older
+/// JavaScript engines do not accept inside string literals.
+fn json_text(value: &Value) -> String {
+    value
+        .to_string()
+        .replace('\u{2028}', "\\u2028")
+        .replace('\u{2029}', "\\u2029")
+}
+
+/// A Python or Node program in `dir` that runs the pipeline through the
+/// language's MetaCall port, with the manifest that installs the port.
+pub fn project(
+    source: &ExportSource,
+    host: Host,
+    dir: &Path,
+    relative_paths: bool,
+) -> Vec<ExportFile> {
+    let manifest = match host {
+        Host::Python => ExportFile {
+            path: dir.join("requirements.txt"),
+            contents: "metacall\n".to_string(),
+        },
+        Host::Node => {
+            let package = json!({
+                "name": package_name(dir),
+                "version": "0.1.0",
+                "private": true,
+                "main": "index.js",
+                "scripts": { "start": "node index.js" },
+                "dependencies": { "metacall": "*" }
+            });
+            ExportFile {
+                path: dir.join("package.json"),
+                contents: format!(
+                    "{}\n",
+                    serde_json::to_string_pretty(&package).unwrap_or_default()
+                ),
+            }
+        }
+    };
+
+    vec![
+        manifest,
+        ExportFile {
+            path: dir.join(host.program_name()),
+            contents: program(source, host, dir, relative_paths),
+        },
+    ]
+}
+
+/// The program of [`project`]. Steps run in order and print their results;
+/// `${...}` references become variables like in the pipeline runner.
+pub fn program(source: &ExportSource, host: Host, dir: &Path, relative_paths: bool) -> String {
+    let mut helpers = BTreeSet::new();
+    let mut body = String::new();
+    let mut bindings: HashMap<String, String> = HashMap::new();
+
+    for param in source.params {
+        let var = format!("param_{}", identifier(&param.name));
+        let default = param
+            .default
+            .as_ref()
+            .and_then(|d| params::coerce(param, d).ok())
+            .or_else(|| {
+                (param.kind == ParamType::Json)
+                    .then(|| source.last_params.get(&param.name).cloned())
+                    .flatten()
+            });
+        let read = match default {
+            Some(value) => format!(
+                "param({}, {})",
+                host.string(&param.name),
+                host.string(&values::display(&value))
+            ),
+            None => format!("param({})", host.string(&param.name)),
+        };
+
+        let expr = match (host, param.kind) {
+            (_, ParamType::String) => read,
+            (Host::Python, ParamType::Int) => format!("int({})", read),
+            (Host::Python, ParamType::Float) => format!("float({})", read),
+            (_, ParamType::Bool) => {
+                helpers.insert("boolean");
+                format!("boolean({}, {})", host.string(&param.name), read)
+            }
+            (Host::Python, _) => format!("json.loads({})", read),
+            (Host::Node, ParamType::Int | ParamType::Float) => {
+                helpers.insert("number");
+                format!(
+                    "number({}, {}, {})",
+                    host.string(&param.name),
+                    host.string(param.kind.name()),
+                    read
+                )
+            }
+            (Host::Node, _) => format!("JSON.parse({})", read),
+        };
+        helpers.insert("param");
+        body.push_str(&host.statement(&var, &expr));
+        bindings.insert(format!("params.{}", param.name), var);
+    }
+    if !source.params.is_empty() {
+        body.push('\n');
+    }
+
+    let mut loaded = BTreeSet::new();
+    for step in source.steps {
+        if !loaded.insert(step.script.as_str()) {
+            continue;
+        }
+        let Some(script) = source.scripts.iter().find(|s| s.name == step.script) else {
+            let _ = writeln!(
+                body,
+                "    {}",
+                host.line_comment(&format!(
+                    "{} was not found when the pipeline was exported",
+                    step.script
+                ))
+            );
+            continue;
+        };
+
+        let (absolute, relative) = script_location(&script.path, dir);
+        let path = if relative_paths {
+            helpers.insert("here");
+            match host {
+                Host::Python => format!("os.path.join(HERE, {})", host.string(&relative)),
+                Host::Node => format!("path.join(__dirname, {})", host.string(&relative)),
+            }
+        } else {
+            host.string(&absolute)
+        };
+        let _ = writeln!(
+            body,
+            "    metacall_load_from_file({}, [{}]){}",
+            host.string(&script.runtime),
+            path,
+            if host == Host::Node { ";" } else { "" }
+        );
+    }
+
+    for (i, step) in source.steps.iter().enumerate() {
+        let var = format!("step_{}", i + 1);
+        let _ = writeln!(
+            body,
+            "\n    {}",
+            host.line_comment(&format!("Step {}: {}", i + 1, step.description))
+        );
+
+        let args: Vec<String> = step
+            .args
+            .iter()
+            .map(|arg| arg_expr(host, arg, &bindings, &mut helpers))
+            .collect();
+        let call = |first: Option<&str>| {
+            let args: Vec<&str> = first
+                .into_iter()
+                .chain(args.iter().map(String::as_str))
+                .collect();
+            let mut call = format!("metacall({}", host.string(&step.function));
+            for arg in args {
+                call.push_str(", ");
+                call.push_str(arg);
+            }
+            call.push(')');
+            call
+        };
+
+        let expr = match &step.kind {
+            StepKind::Call => call(None),
+            StepKind::Map { over } => {
+                let items = arg_expr(host, over, &bindings, &mut helpers);
+                match host {
+                    Host::Python => format!("[{} for item in {}]", call(Some("item")), items),
+                    Host::Node => format!("{}.map((item) => {})", items, call(Some("item"))),
+                }
+            }
+        };
+        body.push_str(&host.statement(&var, &expr));
+
+        helpers.insert("display");
+        let label = host.string(&format!("{}: ", step.id));
+        let _ = writeln!(
+            body,
+            "{}",
+            match host {
+                Host::Python => format!("    print({} + display({}))", label, var),
+                Host::Node => format!("    console.log({} + display({}));", label, var),
+            }
+        );
+        bindings.insert(step.id.clone(), var);
+    }
+
+    let mut out = String::new();
+    let heading = [
+        "Generated MetaCall Pipeline".to_string(),
+        format!("Export Date: {}", source.timestamp),
+        String::new(),
+        match host {
+            Host::Python => "Requires MetaCall and its Python port: pip install metacall",
+            Host::Node => "Requires MetaCall and its Node.js port: npm install metacall",
+        }
+        .to_string(),
+    ];
+    for line in heading {
+        let _ = writeln!(out, "{}", host.line_comment(&line).trim_end());
+    }
+    out.push('\n');
+
+    match host {
+        Host::Python => {
+            let mut modules = vec!["json"];
+            if helpers.contains("here") || helpers.contains("env") {
+                modules.push("os");
+            }
+            if helpers.contains("param") {
+                modules.push("sys");
+            }
+            for module in modules {
+                let _ = writeln!(out, "import {}", module);
+            }
+            out.push_str("\nfrom metacall import metacall, metacall_load_from_file\n\n");
+            if helpers.contains("here") {
+                out.push_str("HERE = os.path.dirname(os.path.abspath(__file__))\n\n");
+            }
+            out.push_str(PYTHON_DISPLAY);
+            if helpers.contains("param") {
+                out.push_str(PYTHON_PARAM);
+            }
+            if helpers.contains("boolean") {
+                out.push_str(PYTHON_BOOLEAN);
+            }
+            if helpers.contains("env") {
+                out.push_str(PYTHON_ENV);
+            }
+            out.push_str("\n\ndef main():\n");
+            out.push_str(&body);
+            out.push_str("\n\nif __name__ == \"__main__\":\n    main()\n");
+
+            out.push_str("\n# === JSON Configuration ===\n");
+            for line in pipeline_json(source).lines() {
+                let _ = writeln!(out, "# {}", line);
+            }
+        }
+        Host::Node => {
+            if helpers.contains("here") {
+                out.push_str("const path = require('path');\n");
+            }
+            out.push_str("const { metacall, metacall_load_from_file } = require('metacall');\n\n");
+            out.push_str(NODE_DISPLAY);
+            if helpers.contains("param") {
+                out.push_str(NODE_PARAM);
+            }
+            if helpers.contains("number") {
+                out.push_str(NODE_NUMBER);
+            }
+            if helpers.contains("boolean") {
+                out.push_str(NODE_BOOLEAN);
+            }
+            if helpers.contains("env") {
+                out.push_str(NODE_ENV);
+            }
+            out.push_str("\nfunction main() {\n");
+            out.push_str(&body);
+            out.push_str("}\n\nmain();\n");
+
+            out.push_str("\n// === JSON Configuration ===\n/*\n");
+            out.push_str(&pipeline_json(source));
+            out.push_str("\n*/\n");
+        }
+    }
+
+    out
+}
+
+/// Turns a step argument into an expression, resolving `${...}` references
+/// the way the pipeline runner does. Text with embedded references becomes
+/// a string concatenation.
+fn arg_expr(
+    host: Host,
+    arg: &str,
+    bindings: &HashMap<String, String>,
+    helpers: &mut BTreeSet<&'static str>,
+) -> String {
+    let mut reference = |name: &str| -> Option<String> {
+        if let Some(var) = name.strip_prefix("env.") {
+            helpers.insert("env");
+            return Some(format!("env({})", host.string(var)));
+        }
+        bindings.get(name).cloned()
+    };
+
+    let trimmed = arg.trim();
+    if let Some(name) = trimmed
+        .strip_prefix("${")
+        .and_then(|rest| rest.strip_suffix('}'))
+        && !name.contains(['{', '}'])
+        && let Some(expr) = reference(name)
+    {
+        return expr;
+    }
+
+    let mut parts = Vec::new();
+    let mut text = String::new();
+    let mut rest = arg;
+    while let Some(start) = rest.find("${") {
+        let Some(end) = rest[start..].find('}') else {
+            break;
+        };
+        text.push_str(&rest[..start]);
+        match reference(&rest[start + 2..start + end]) {
+            Some(expr) => {
+                if !text.is_empty() {
+                    parts.push(host.string(&std::mem::take(&mut text)));
+                }
+                parts.push(format!("display({})", expr));
+            }
+            None => text.push_str(&rest[start..=start + end]),
+        }
+        rest = &rest[start + end + 1..];
+    }
+
+    if parts.is_empty() {
+        return host.literal(&values::parse_arg(arg));
+    }
+
+    helpers.insert("display");
+    text.push_str(rest);
+    if !text.is_empty() {
+        parts.push(host.string(&text));
+    }
+    parts.join(" + ")
+}
+
+const PYTHON_DISPLAY: &str = r#"
+def display(value):
+    """Strings verbatim, anything else as compact JSON."""
+    if isinstance(value, str):
+        return value
+    return json.dumps(value, separators=(",", ":"), ensure_ascii=False)
+"#;
+
+const PYTHON_PARAM: &str = r#"
+
+def param(name, default=None):
+    """Reads a `name=value` command line argument, falling back to the default."""
+    for arg in sys.argv[1:]:
+        if arg.startswith(name + "="):
+            return arg[len(name) + 1 :]
+    if default is None:
+        raise SystemExit("Missing value for parameter " + name)
+    return default
+"#;
+
+const PYTHON_BOOLEAN: &str = r#"
+
+def boolean(name, text):
+    """Parses a bool parameter, accepting only `true` or `false`."""
+    if text.strip() not in ("true", "false"):
+        raise SystemExit(name + " expects bool")
+    return text.strip() == "true"
+"#;
+
+const PYTHON_ENV: &str = r#"
+
+def env(name):
+    if name not in os.environ:
+        raise SystemExit("Environment variable " + name + " is not set")
+    return os.environ[name]
+"#;
+
+const NODE_DISPLAY: &str = r#"// Strings verbatim, anything else as compact JSON. BigInts are written as
+// numbers where JSON.rawJSON is available, and as strings otherwise.
+function display(value) {
+    if (typeof value === 'string') {
+        return value;
+    }
+    return JSON.stringify(value, (_, v) =>
+        typeof v === 'bigint' ? (JSON.rawJSON ? JSON.rawJSON(v.toString()) : v.toString()) : v
+    );
+}
+"#;
+
+const NODE_PARAM: &str = r#"
+// Reads a `name=value` command line argument, falling back to the default.
+function param(name, fallback) {
+    const arg = process.argv.slice(2).find((a) => a.startsWith(name + '='));
+    if (arg !== undefined) {
+        return arg.slice(name.length + 1);
+    }
+    if (fallback === undefined) {
+        throw new Error('Missing value for parameter ' + name);
+    }
+    return fallback;
+}
+"#;
+
+const NODE_NUMBER: &str = r#"
+// Parses an int or float parameter, rejecting text that is not one.
+function number(name, type, text) {
+    const value = Number(text);
+    if (text.trim() === '' || !Number.isFinite(value) || (type === 'int' && !Number.isInteger(value))) {
+        throw new Error(name + ' expects ' + type);
+    }
+    return value;
+}
+"#;
+
+const NODE_BOOLEAN: &str = r#"
+// Parses a bool parameter, accepting only `true` or `false`.
+function boolean(name, text) {
+    if (text.trim() !== 'true' && text.trim() !== 'false') {
+        throw new Error(name + ' expects bool');
+    }
+    return text.trim() === 'true';
+}
+"#;
+
+const NODE_ENV: &str = r#"
+function env(name) {
+    if (!(name in process.env)) {
+        throw new Error('Environment variable ' + name + ' is not set');
+    }
+    return process.env[name];
+}
+"#;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::export::tests::Fixture;
+    use std::process::Command;
+
+    /// Checks the syntax of both programs with their interpreters, when they
+    /// are installed.
+    #[test]
+    fn generated_programs_parse() {
+        let root = std::env::temp_dir().join(format!("polyglot-host-{}", std::process::id()));
+        let fixture = Fixture::new(&root.join("scripts"));
+        let source = fixture.source();
+
+        for (host, command, args) in [
+            (Host::Python, "python3", &["-m", "py_compile"][..]),
+            (Host::Node, "node", &["--check"][..]),
+        ] {
+            let dir = root.join(format!("{:?}", host));
+            std::fs::create_dir_all(&dir).unwrap();
+            let path = dir.join(host.program_name());
+            std::fs::write(&path, program(&source, host, &dir, true)).unwrap();
+
+            match Command::new(command).args(args).arg(&path).output() {
+                Ok(output) => assert!(
+                    output.status.success(),
+                    "generated {:?} program does not parse:\n{}",
+                    host,
+                    String::from_utf8_lossy(&output.stderr)
+                ),
+                Err(_) => eprintln!("{} is not installed, skipping", command),
+            }
+        }
+        let _ = std::fs::remove_dir_all(&root);
+    }
+}
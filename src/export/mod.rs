@@ -0,0 +1,527 @@
+use crate::models::{
+    ExecutionResult, ExportFormat, PipelineFile, PipelineParam, PipelineStep, ResultsFormat, Script,
+};
+use serde_json::Value;
+use std::{
+    collections::{BTreeMap, HashMap},
+    fs, io,
+    path::{Component, Path, PathBuf},
+};
+
+mod cli;
+mod docs;
+mod host;
+mod results;
+mod rust;
+
+use host::Host;
+
+/// Everything the generators need to know about a pipeline.
+pub struct ExportSource<'a> {
+    pub scripts: &'a [Script],
+    pub params: &'a [PipelineParam],
+    pub steps: &'a [PipelineStep],
+    /// The output of each step in the last successful run, keyed by step id.
+    /// It decides the return types of the generated calls.
+    pub observed: HashMap<String, Value>,
+    /// Parameter values of the last successful run, used for JSON parameters
+    /// without a default.
+    pub last_params: BTreeMap<String, Value>,
+    pub timestamp: String,
+}
+
+pub struct ExportFile {
+    pub path: PathBuf,
+    pub contents: String,
+}
+
+/// The files written when exporting `name` into `output_dir`: a project
+/// directory for the program formats, a single file for the others. With
+/// `relative_paths` scripts are found relative to the exported files,
+/// otherwise through their absolute paths.
+pub fn files(
+    source: &ExportSource,
+    format: ExportFormat,
+    output_dir: &Path,
+    name: &str,
+    relative_paths: bool,
+) -> Vec<ExportFile> {
+    let dir = output_dir.join(name);
+    match format {
+        ExportFormat::Rust => rust::project(source, &dir, relative_paths),
+        ExportFormat::RustTests => rust::test_project(source, &dir, relative_paths),
+        ExportFormat::Python => host::project(source, Host::Python, &dir, relative_paths),
+        ExportFormat::Node => host::project(source, Host::Node, &dir, relative_paths),
+        _ => {
+            let (path, contents) = document(source, format, output_dir, name, relative_paths);
+            vec![ExportFile { path, contents }]
+        }
+    }
+}
+
+/// The main file of an export, shown as its preview: the program of project
+/// formats, or the single exported file.
+pub fn preview(
+    source: &ExportSource,
+    format: ExportFormat,
+    output_dir: &Path,
+    name: &str,
+    relative_paths: bool,
+) -> (PathBuf, String) {
+    let dir = output_dir.join(name);
+    match format {
+        ExportFormat::Rust => (
+            dir.join("src").join("main.rs"),
+            rust::main_rs(source, &dir, relative_paths),
+        ),
+        ExportFormat::RustTests => (
+            dir.join("tests").join("pipeline.rs"),
+            rust::tests_rs(source, &dir, relative_paths),
+        ),
+        ExportFormat::Python => (
+            dir.join(Host::Python.program_name()),
+            host::program(source, Host::Python, &dir, relative_paths),
+        ),
+        ExportFormat::Node => (
+            dir.join(Host::Node.program_name()),
+            host::program(source, Host::Node, &dir, relative_paths),
+        ),
+        _ => document(source, format, output_dir, name, relative_paths),
+    }
+}
+
+fn document(
+    source: &ExportSource,
+    format: ExportFormat,
+    output_dir: &Path,
+    name: &str,
+    relative_paths: bool,
+) -> (PathBuf, String) {
+    let path = output_dir.join(format!("{}.{}", name, format.extension().unwrap_or("txt")));
+    let contents = match format {
+        ExportFormat::Json => format!(
+            "{}\n",
+            serde_json::to_string_pretty(&pipeline_file(source)).unwrap_or_default()
+        ),
+        ExportFormat::Yaml => serde_yaml::to_string(&pipeline_file(source))
+            .unwrap_or_else(|e| format!("# Failed to serialize the pipeline: {}\n", e)),
+        ExportFormat::Shell => cli::script(source, output_dir, relative_paths),
+        ExportFormat::Markdown => docs::markdown(source),
+        _ => docs::mermaid(source),
+    };
+    (path, contents)
+}
+
+/// A report of execution results, given oldest first, saved as `name` in
+/// `output_dir`.
+pub fn results_file(
+    results: &[&ExecutionResult],
+    format: ResultsFormat,
+    output_dir: &Path,
+    name: &str,
+) -> ExportFile {
+    ExportFile {
+        path: output_dir.join(format!("{}.{}", name, format.extension())),
+        contents: results::render(results, format),
+    }
+}
+
+/// Writes the files, creating missing directories.
+pub fn write(files: &[ExportFile]) -> io::Result<()> {
+    for file in files {
+        if let Some(parent) = file.path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(&file.path, &file.contents)?;
+    }
+    Ok(())
+}
+
+fn pipeline_file(source: &ExportSource) -> PipelineFile {
+    PipelineFile {
+        params: source.params.to_vec(),
+        steps: source.steps.to_vec(),
+    }
+}
+
+/// The pipeline as pretty printed JSON, safe to embed in a block comment.
+fn pipeline_json(source: &ExportSource) -> String {
+    let json = serde_json::to_string_pretty(&pipeline_file(source)).unwrap_or_default();
+
+    // Stars only occur inside JSON strings, where `\u002a` spells them too.
+    // Escaping those next to a `/` keeps the comment from being closed early,
+    // or nested in Rust.
+    let mut out = String::with_capacity(json.len());
+    let mut prev = None;
+    let mut chars = json.chars().peekable();
+    while let Some(c) = chars.next() {
+        if c == '*' && (prev == Some('/') || chars.peek() == Some(&'/')) {
+            out.push_str("\\u002a");
+        } else {
+            out.push(c);
+        }
+        prev = Some(c);
+    }
+    out
+}
+
+/// Keeps user text on a single comment line. JavaScript also ends lines at
+/// the Unicode line and paragraph separators.
+fn comment(text: &str) -> String {
+    text.chars()
+        .map(|c| {
+            if c.is_control() || matches!(c, '\u{2028}' | '\u{2029}') {
+                ' '
+            } else {
+                c
+            }
+        })
+        .collect()
+}
+
+/// Shortens text to `max` characters, marking the cut with an ellipsis.
+fn truncate(text: &str, max: usize) -> String {
+    if text.chars().count() <= max {
+        text.to_string()
+    } else {
+        format!("{}…", text.chars().take(max).collect::<String>())
+    }
+}
+
+/// An ASCII identifier for a parameter name, which may contain any
+/// alphanumeric character.
+fn identifier(name: &str) -> String {
+    name.chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '_' {
+                c.to_string()
+            } else {
+                format!("_{:x}", c as u32)
+            }
+        })
+        .collect()
+}
+
+/// The names referenced as `${name}` in a step argument.
+fn references(arg: &str) -> Vec<&str> {
+    let mut names = Vec::new();
+    let mut rest = arg;
+    while let Some(start) = rest.find("${") {
+        let Some(end) = rest[start..].find('}') else {
+            break;
+        };
+        names.push(&rest[start + 2..start + end]);
+        rest = &rest[start + end + 1..];
+    }
+    names
+}
+
+/// The absolute path of a script, and its path relative to `dir` with `/`
+/// separators.
+fn script_location(path: &Path, dir: &Path) -> (String, String) {
+    let script = std::path::absolute(path).unwrap_or_else(|_| path.to_path_buf());
+    let dir = std::path::absolute(dir).unwrap_or_else(|_| dir.to_path_buf());
+    (
+        script.to_string_lossy().into_owned(),
+        relative_path(&dir, &script),
+    )
+}
+
+/// The path of `to` relative to the directory `from`, with `/` separators.
+fn relative_path(from: &Path, to: &Path) -> String {
+    let from: Vec<Component> = from.components().collect();
+    let to: Vec<Component> = to.components().collect();
+    let common = from.iter().zip(&to).take_while(|(a, b)| a == b).count();
+
+    std::iter::repeat_n("..".to_string(), from.len() - common)
+        .chain(
+            to[common..]
+                .iter()
+                .map(|c| c.as_os_str().to_string_lossy().into_owned()),
+        )
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+/// A valid Cargo package name derived from the project directory.
+fn package_name(dir: &Path) -> String {
+    let name: String = dir
+        .file_name()
+        .map(|n| n.to_string_lossy().to_lowercase())
+        .unwrap_or_default()
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '-' })
+        .collect();
+    let name = name.trim_matches('-');
+
+    if name.starts_with(|c: char| c.is_ascii_alphabetic()) {
+        name.to_string()
+    } else {
+        format!("pipeline-{}", name)
+            .trim_end_matches('-')
+            .to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{AssertionResult, StepKind};
+    use crate::params;
+    use serde_json::json;
+
+    /// A pipeline with the inputs code generation gets wrong most easily:
+    /// quotes, backslashes, newlines, braces, comment delimiters, shell
+    /// metacharacters, non-ASCII text and values of every JSON type.
+    pub(super) struct Fixture {
+        pub scripts: Vec<Script>,
+        pub params: Vec<PipelineParam>,
+        pub steps: Vec<PipelineStep>,
+        pub observed: HashMap<String, Value>,
+    }
+
+    impl Fixture {
+        pub fn new(scripts_dir: &Path) -> Fixture {
+            let scripts = vec![Script {
+                path: scripts_dir.join("calc.py"),
+                name: "calc.py".to_string(),
+                language: "Python".to_string(),
+                runtime: "py".to_string(),
+                functions: Vec::new(),
+                loaded: true,
+                error: None,
+            }];
+            let params = params::parse_declarations(
+                "name:string=Wo\"rld \\ $x `y`, n:int=5, ratio:float=1.5, verbose:bool=true, \
+                 items:json=[1\\, \"a\"], größe:int=3, required",
+            )
+            .unwrap();
+
+            let step = |id: &str, function: &str, args: &[&str], kind: StepKind| PipelineStep {
+                id: id.to_string(),
+                script: "calc.py".to_string(),
+                function: function.to_string(),
+                args: args.iter().map(|a| a.to_string()).collect(),
+                description: format!("Call {} */ /* with\n\"quotes\"", function),
+                kind,
+                expect: Vec::new(),
+            };
+            let steps = vec![
+                step("product", "multiply", &["6", "7.5", "-0.0"], StepKind::Call),
+                step(
+                    "hello",
+                    "greet",
+                    &[
+                        "${params.name}",
+                        "a \"quoted\" \\ back\\slash {braces} $HOME `tick`\nnew line\ttab",
+                        "*/ /* closing",
+                        "unicode é ✓ \u{2028} end",
+                    ],
+                    StepKind::Call,
+                ),
+                step("nums", "range", &["${params.n}"], StepKind::Call),
+                step(
+                    "squares",
+                    "square",
+                    &["${params.ratio}"],
+                    StepKind::Map {
+                        over: "${nums}".to_string(),
+                    },
+                ),
+                step(
+                    "summary",
+                    "describe",
+                    &[
+                        "${squares} from ${env.HOME}: \"${params.name}\" {x}",
+                        "[1, \"a|b\", null, true, 2.5]",
+                        "{\"k\": {\"n\": -1.25e-7}}",
+                        "1e300",
+                        "9223372036854775807",
+                    ],
+                    StepKind::Call,
+                ),
+                step(
+                    "flags",
+                    "mixed",
+                    &[
+                        "${params.verbose}",
+                        "${params.items}",
+                        "${params.größe}",
+                        "${params.required}",
+                        "true",
+                        "null",
+                        "\"42\"",
+                        "items: ${params.items}",
+                    ],
+                    StepKind::Call,
+                ),
+            ];
+            let observed = HashMap::from([
+                ("product".to_string(), json!(-0.0)),
+                ("hello".to_string(), json!("Hello, \"World\"!\n")),
+                ("nums".to_string(), json!([1, 2, 3])),
+                ("squares".to_string(), json!([1.0, 4.0, 9.0])),
+                ("summary".to_string(), json!({"a": [1, 2], "b": [3]})),
+                ("flags".to_string(), json!([1, "two", null])),
+            ]);
+
+            Fixture {
+                scripts,
+                params,
+                steps,
+                observed,
+            }
+        }
+
+        pub fn source(&self) -> ExportSource<'_> {
+            ExportSource {
+                scripts: &self.scripts,
+                params: &self.params,
+                steps: &self.steps,
+                observed: self.observed.clone(),
+                last_params: BTreeMap::from([("required".to_string(), json!("from the last run"))]),
+                timestamp: "2024-01-01 12:00:00".to_string(),
+            }
+        }
+    }
+
+    /// Compares generated output with `src/export/golden/<name>`. Set
+    /// `UPDATE_GOLDEN=1` to rewrite the golden files instead.
+    fn assert_golden(name: &str, actual: &str) {
+        let path = Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("src/export/golden")
+            .join(name);
+        if std::env::var_os("UPDATE_GOLDEN").is_some() {
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(&path, actual).unwrap();
+            return;
+        }
+        let expected = fs::read_to_string(&path).unwrap_or_default();
+        assert!(
+            expected == actual,
+            "{} differs from its golden file, rerun with UPDATE_GOLDEN=1 if the change is intended\n{}",
+            name,
+            actual
+        );
+    }
+
+    #[test]
+    fn exports_match_golden_files() {
+        let fixture = Fixture::new(Path::new("/pipelines/scripts"));
+        let source = fixture.source();
+        let output_dir = Path::new("/pipelines/export");
+
+        for (format, golden) in [
+            (ExportFormat::Rust, "main.rs.golden"),
+            (ExportFormat::RustTests, "pipeline.rs.golden"),
+            (ExportFormat::Python, "main.py.golden"),
+            (ExportFormat::Node, "index.js.golden"),
+            (ExportFormat::Json, "pipeline.json.golden"),
+            (ExportFormat::Yaml, "pipeline.yaml.golden"),
+            (ExportFormat::Shell, "pipeline.sh.golden"),
+            (ExportFormat::Markdown, "pipeline.md.golden"),
+            (ExportFormat::Mermaid, "pipeline.mmd.golden"),
+        ] {
+            let (_, contents) = preview(&source, format, output_dir, "pipeline", true);
+            assert_golden(golden, &contents);
+        }
+    }
+
+    #[test]
+    fn results_match_golden_files() {
+        let result =
+            |function: &str, args: &[&str], output: &str, value, success, run_id| ExecutionResult {
+                function: function.to_string(),
+                args: args.iter().map(|a| a.to_string()).collect(),
+                output: output.to_string(),
+                value,
+                duration_ms: 1234,
+                success,
+                timestamp: crate::time::parse("2024-01-01T12:00:00.250+02:00").unwrap(),
+                run_id,
+                changed: false,
+                mapped: false,
+                replay_of: None,
+                assertions: Vec::new(),
+            };
+        let mut results = [
+            result(
+                "greet",
+                &["World"],
+                "Hello, World!",
+                Some(json!("Hello, World!")),
+                true,
+                None,
+            ),
+            result(
+                "describe",
+                &["a, \"quoted\"", "<tag> & 'apos'"],
+                "{\"a\":[1,2]}",
+                Some(json!({"a": [1, 2]})),
+                true,
+                Some(3),
+            ),
+            result(
+                "fail",
+                &["line\nbreak"],
+                "Error: boom\n  at <stack>\u{1}",
+                None,
+                false,
+                Some(3),
+            ),
+        ];
+        results[1].assertions = vec![
+            AssertionResult {
+                expectation: "contains a".to_string(),
+                passed: true,
+                message: String::new(),
+            },
+            AssertionResult {
+                expectation: "$.a[0] = 2".to_string(),
+                passed: false,
+                message: "got 1".to_string(),
+            },
+        ];
+        let results: Vec<&ExecutionResult> = results.iter().collect();
+
+        for format in ResultsFormat::ALL {
+            let file = results_file(&results, format, Path::new("/reports"), "results");
+            assert_eq!(
+                file.path,
+                Path::new("/reports").join(format!("results.{}", format.extension()))
+            );
+            assert_golden(
+                &format!("results.{}.golden", format.extension()),
+                &file.contents,
+            );
+        }
+    }
+
+    #[test]
+    fn exports_import_back_intact() {
+        let fixture = Fixture::new(Path::new("/pipelines/scripts"));
+        let source = fixture.source();
+        let output_dir = Path::new("/pipelines/export");
+        let original = serde_json::to_value(pipeline_file(&source)).unwrap();
+
+        for format in [
+            ExportFormat::Rust,
+            ExportFormat::RustTests,
+            ExportFormat::Python,
+            ExportFormat::Node,
+            ExportFormat::Json,
+            ExportFormat::Yaml,
+            ExportFormat::Markdown,
+        ] {
+            let (path, contents) = preview(&source, format, output_dir, "pipeline", true);
+            let imported = crate::import::parse_pipeline(&contents, &path)
+                .unwrap_or_else(|e| panic!("{} does not import: {}", path.display(), e));
+            assert_eq!(
+                serde_json::to_value(imported).unwrap(),
+                original,
+                "{}",
+                path.display()
+            );
+        }
+    }
+}
@@ -0,0 +1,192 @@
+use crate::models::{ExecutionResult, ResultsFormat};
+use crate::{time, values};
+use std::fmt::Write as _;
+
+/// Renders results, oldest first, in one of the report formats.
+pub fn render(results: &[&ExecutionResult], format: ResultsFormat) -> String {
+    match format {
+        ResultsFormat::JsonLines => json_lines(results),
+        ResultsFormat::Csv => csv(results),
+        ResultsFormat::JUnit => junit(results),
+    }
+}
+
+/// One serialized `ExecutionResult` per line.
+fn json_lines(results: &[&ExecutionResult]) -> String {
+    let mut out = String::new();
+    for result in results {
+        if let Ok(line) = serde_json::to_string(result) {
+            out.push_str(&line);
+            out.push('\n');
+        }
+    }
+    out
+}
+
+/// RFC 4180 CSV with a header row. Arguments, values and assertion results
+/// are JSON encoded.
+fn csv(results: &[&ExecutionResult]) -> String {
+    let mut out = String::from(
+        "timestamp,run_id,function,args,success,duration_ms,output,value,assertions\r\n",
+    );
+    for result in results {
+        let fields = [
+            time::rfc3339(&result.timestamp),
+            result.run_id.map(|id| id.to_string()).unwrap_or_default(),
+            result.function.clone(),
+            serde_json::to_string(&result.args).unwrap_or_default(),
+            result.success.to_string(),
+            result.duration_ms.to_string(),
+            result.output.clone(),
+            result
+                .value
+                .as_ref()
+                .map(|value| value.to_string())
+                .unwrap_or_default(),
+            serde_json::to_string(&result.assertions).unwrap_or_default(),
+        ];
+        let row: Vec<String> = fields.iter().map(|field| csv_field(field)).collect();
+        out.push_str(&row.join(","));
+        out.push_str("\r\n");
+    }
+    out
+}
+
+fn csv_field(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+/// A JUnit XML report with a test suite per pipeline run, and one for the
+/// calls made from the Function Tester. Every result is a test case that
+/// fails when the call failed or one of its step's expectations did not
+/// hold. Suites are stamped with their first call.
+fn junit(results: &[&ExecutionResult]) -> String {
+    let mut suites: Vec<(Option<u64>, Vec<&ExecutionResult>)> = Vec::new();
+    for result in results {
+        match suites
+            .iter_mut()
+            .find(|(run_id, _)| *run_id == result.run_id)
+        {
+            Some((_, cases)) => cases.push(result),
+            None => suites.push((result.run_id, vec![result])),
+        }
+    }
+
+    let failed = |r: &&&ExecutionResult| !r.passed();
+    let failures = results.iter().filter(failed).count();
+    let mut out = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    let _ = writeln!(
+        out,
+        "<testsuites name=\"polyglot-devops-thing\" tests=\"{}\" failures=\"{}\" time=\"{}\">",
+        results.len(),
+        failures,
+        seconds(results.iter().map(|r| r.duration_ms).sum())
+    );
+
+    for (run_id, cases) in suites {
+        let (name, classname) = match run_id {
+            Some(id) => (
+                format!("Pipeline run #{}", id),
+                format!("pipeline.run{}", id),
+            ),
+            None => ("Function Tester".to_string(), "function_tester".to_string()),
+        };
+        let _ = writeln!(
+            out,
+            "  <testsuite name=\"{}\" timestamp=\"{}\" tests=\"{}\" failures=\"{}\" time=\"{}\">",
+            xml_attr(&name),
+            time::rfc3339(&cases[0].timestamp),
+            cases.len(),
+            cases.iter().filter(failed).count(),
+            seconds(cases.iter().map(|r| r.duration_ms).sum())
+        );
+
+        for case in cases {
+            let _ = writeln!(
+                out,
+                "    <testcase name=\"{}\" classname=\"{}\" time=\"{}\">",
+                xml_attr(&format!("{}({})", case.function, case.args.join(", "))),
+                classname,
+                seconds(case.duration_ms)
+            );
+            let output = case
+                .value
+                .as_ref()
+                .map(values::display)
+                .unwrap_or_else(|| case.output.clone());
+            if case.success {
+                let failed: Vec<String> = case
+                    .assertions
+                    .iter()
+                    .filter(|a| !a.passed)
+                    .map(|a| format!("expected {}: {}", a.expectation, a.message))
+                    .collect();
+                if let Some(first) = failed.first() {
+                    let _ = writeln!(
+                        out,
+                        "      <failure message=\"{}\" type=\"assertion\">{}</failure>",
+                        xml_attr(first),
+                        xml_escape(&failed.join("\n"))
+                    );
+                }
+                let _ = writeln!(
+                    out,
+                    "      <system-out>{}</system-out>",
+                    xml_escape(&output)
+                );
+            } else {
+                let message = output.lines().next().unwrap_or_default();
+                let _ = writeln!(
+                    out,
+                    "      <failure message=\"{}\">{}</failure>",
+                    xml_attr(message),
+                    xml_escape(&output)
+                );
+            }
+            out.push_str("    </testcase>\n");
+        }
+        out.push_str("  </testsuite>\n");
+    }
+    out.push_str("</testsuites>\n");
+    out
+}
+
+fn seconds(ms: u64) -> String {
+    format!("{}.{:03}", ms / 1000, ms % 1000)
+}
+
+/// Escapes text for XML content, dropping the control characters XML 1.0
+/// cannot represent.
+fn xml_escape(text: &str) -> String {
+    escape(text, false)
+}
+
+/// Escapes text for an XML attribute, where line breaks and tabs are kept
+/// as character references.
+fn xml_attr(text: &str) -> String {
+    escape(text, true)
+}
+
+fn escape(text: &str, attribute: bool) -> String {
+    let mut out = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            '\'' => out.push_str("&apos;"),
+            '\t' | '\n' | '\r' if attribute => {
+                let _ = write!(out, "&#{};", c as u32);
+            }
+            '\t' | '\n' | '\r' => out.push(c),
+            c if c.is_control() && c < '\u{80}' => {}
+            c => out.push(c),
+        }
+    }
+    out
+}
@@ -0,0 +1,957 @@
+use super::{
+    ExportFile, ExportSource, comment, identifier, package_name, pipeline_json, references,
+    script_location, truncate,
+};
+use crate::models::{ParamType, PipelineParam, PipelineStep, StepKind};
+use crate::{params, values};
+use serde_json::Value;
+use std::{
+    collections::{BTreeSet, HashMap},
+    fmt::Write as _,
+    path::Path,
+};
+
+/// A standalone Cargo project: the manifest, a `build.rs` that links MetaCall
+/// through `metacall-sys`, and the program.
+pub fn project(source: &ExportSource, dir: &Path, relative_paths: bool) -> Vec<ExportFile> {
+    let mut files = scaffold(dir, "dependencies");
+    files.push(ExportFile {
+        path: dir.join("src").join("main.rs"),
+        contents: main_rs(source, dir, relative_paths),
+    });
+    files
+}
+
+/// A Cargo project holding the pipeline as integration tests, runnable with
+/// `cargo test`. The test file can also be copied into the `tests` directory
+/// of another crate that depends on `metacall` and `serde_json`.
+pub fn test_project(source: &ExportSource, dir: &Path, relative_paths: bool) -> Vec<ExportFile> {
+    let mut files = scaffold(dir, "dev-dependencies");
+    files.push(ExportFile {
+        path: dir.join("src").join("lib.rs"),
+        contents: "//! The pipeline tests are in `tests/pipeline.rs`.\n".to_string(),
+    });
+    files.push(ExportFile {
+        path: dir.join("tests").join("pipeline.rs"),
+        contents: tests_rs(source, dir, relative_paths),
+    });
+    files
+}
+
+/// The manifest, `build.rs` and `.gitignore` shared by the Rust projects,
+/// with `metacall` and `serde_json` listed in the `section` table.
+fn scaffold(dir: &Path, section: &str) -> Vec<ExportFile> {
+    let cargo_toml = format!(
+        "[package]\n\
+         name = \"{}\"\n\
+         version = \"0.1.0\"\n\
+         edition = \"2024\"\n\
+         \n\
+         [{}]\n\
+         metacall = \"0.5.2\"\n\
+         serde_json = \"1.0\"\n\
+         \n\
+         [build-dependencies]\n\
+         metacall-sys = \"0.1.2\"\n\
+         \n\
+         # Keep the project standalone even when exported inside another workspace.\n\
+         [workspace]\n",
+        package_name(dir),
+        section
+    );
+
+    vec![
+        ExportFile {
+            path: dir.join("Cargo.toml"),
+            contents: cargo_toml,
+        },
+        ExportFile {
+            path: dir.join("build.rs"),
+            contents: "fn main() {\n    metacall_sys::build();\n}\n".to_string(),
+        },
+        ExportFile {
+            path: dir.join(".gitignore"),
+            contents: "/target\n".to_string(),
+        },
+    ]
+}
+
+/// The `src/main.rs` of [`project`], ending with the pipeline JSON in a
+/// comment block.
+pub fn main_rs(source: &ExportSource, dir: &Path, relative_paths: bool) -> String {
+    let mut imports = BTreeSet::new();
+    let mut helpers = BTreeSet::new();
+    let mut body = String::new();
+    let mut bindings: HashMap<String, (String, RustType)> = HashMap::new();
+
+    for param in source.params {
+        let var = format!("param_{}", identifier(&param.name));
+        let ty = emit_param(&mut body, &mut imports, param, source, &var);
+        bindings.insert(format!("params.{}", param.name), (var, ty));
+    }
+    if !source.params.is_empty() {
+        body.push('\n');
+    }
+
+    emit_loads(&mut body, &mut imports, source, dir, relative_paths, "    ");
+
+    for (i, step) in source.steps.iter().enumerate() {
+        let var = format!("step_{}", i + 1);
+        let _ = writeln!(
+            body,
+            "\n    // Step {}: {}",
+            i + 1,
+            comment(&step.description)
+        );
+
+        let observed = source.observed.get(&step.id);
+        let Some(ty) = emit_step(
+            &mut body,
+            &mut imports,
+            &mut helpers,
+            &bindings,
+            i,
+            step,
+            observed,
+        ) else {
+            break;
+        };
+
+        let _ = writeln!(
+            body,
+            "    println!({:?}, {});",
+            format!("{}: {{}}", format_escape(&step.id)),
+            ty.display(&var, &mut imports, &mut helpers)
+        );
+        bindings.insert(step.id.clone(), (var, ty));
+    }
+
+    let mut out = String::new();
+    let _ = writeln!(
+        out,
+        "// Generated MetaCall Pipeline\n// Export Date: {}\n",
+        source.timestamp
+    );
+    if !imports.is_empty() {
+        let imports: Vec<&str> = imports.into_iter().collect();
+        let _ = writeln!(out, "use metacall::{{{}}};\n", imports.join(", "));
+    }
+
+    out.push_str(
+        "fn main() {\n    if let Err(e) = run() {\n        eprintln!(\"{}\", e);\n        std::process::exit(1);\n    }\n}\n\n",
+    );
+    out.push_str("fn run() -> Result<(), String> {\n");
+    out.push_str("    let _metacall = metacall::initialize().map_err(|e| e.to_string())?;\n\n");
+    out.push_str(&body);
+    out.push_str("\n    Ok(())\n}\n");
+    emit_helpers(&mut out, &helpers);
+
+    if !source.params.is_empty() {
+        out.push_str(
+            "\n/// Reads a `name=value` command line argument, falling back to the default.\n\
+             fn param(name: &str, default: Option<&str>) -> Result<String, String> {\n    \
+                 std::env::args()\n        \
+                     .skip(1)\n        \
+                     .find_map(|arg| {\n            \
+                         arg.strip_prefix(name)\n                \
+                             .and_then(|rest| rest.strip_prefix('='))\n                \
+                             .map(str::to_string)\n        \
+                     })\n        \
+                     .or(default.map(str::to_string))\n        \
+                     .ok_or_else(|| format!(\"Missing value for parameter {}\", name))\n\
+             }\n",
+        );
+    }
+
+    out.push_str("\n// === JSON Configuration ===\n/*\n");
+    out.push_str(&pipeline_json(source));
+    out.push_str("\n*/\n");
+
+    out
+}
+
+/// The `tests/pipeline.rs` of [`test_project`]. Every step with a recorded
+/// output becomes a test that calls it with the inputs of the last
+/// successful run and compares the result with the recorded output.
+pub fn tests_rs(source: &ExportSource, dir: &Path, relative_paths: bool) -> String {
+    let mut imports = BTreeSet::new();
+    let mut helpers = BTreeSet::new();
+    let mut setup = String::new();
+    emit_loads(
+        &mut setup,
+        &mut imports,
+        source,
+        dir,
+        relative_paths,
+        "        ",
+    );
+
+    let mut tests = String::new();
+    for (i, step) in source.steps.iter().enumerate() {
+        let Some(expected) = source.observed.get(&step.id) else {
+            let _ = writeln!(
+                tests,
+                "\n// Step {} ({}) has no recorded output, so it is not tested.",
+                i + 1,
+                comment(&step.id)
+            );
+            continue;
+        };
+
+        // References are fixed to the values of the last run, so every test
+        // runs on its own.
+        let mut body = String::new();
+        let mut bindings = HashMap::new();
+        let over = match &step.kind {
+            StepKind::Map { over } => Some(over.as_str()),
+            StepKind::Call => None,
+        };
+        let mut missing = None;
+        for name in step
+            .args
+            .iter()
+            .map(String::as_str)
+            .chain(over)
+            .flat_map(references)
+        {
+            if name.starts_with("env.") || bindings.contains_key(name) {
+                continue;
+            }
+            let (var, value, note) = match name.strip_prefix("params.") {
+                Some(param_name) => {
+                    let Some(param) = source.params.iter().find(|p| p.name == param_name) else {
+                        continue;
+                    };
+                    let value = source.last_params.get(param_name).cloned().or_else(|| {
+                        param
+                            .default
+                            .as_ref()
+                            .and_then(|d| params::coerce(param, d).ok())
+                    });
+                    (
+                        format!("param_{}", identifier(param_name)),
+                        value,
+                        format!("Value of params.{} in the last run", param_name),
+                    )
+                }
+                None => {
+                    let Some(j) = source.steps[..i].iter().position(|s| s.id == name) else {
+                        continue;
+                    };
+                    (
+                        format!("step_{}", j + 1),
+                        source.observed.get(name).cloned(),
+                        format!("Recorded output of {}", name),
+                    )
+                }
+            };
+            let Some(value) = value else {
+                missing = Some(name);
+                break;
+            };
+            let (expr, ty) = literal(&value, &mut imports);
+            let _ = writeln!(
+                body,
+                "    // {}\n    let {} = {};",
+                comment(&note),
+                var,
+                expr
+            );
+            bindings.insert(name.to_string(), (var, ty));
+        }
+        if let Some(name) = missing {
+            let _ = writeln!(
+                tests,
+                "\n// Step {} ({}) uses ${{{}}}, which has no recorded value, so it is not tested.",
+                i + 1,
+                comment(&step.id),
+                comment(name)
+            );
+            continue;
+        }
+
+        let Some(ty) = emit_step(
+            &mut body,
+            &mut imports,
+            &mut helpers,
+            &bindings,
+            i,
+            step,
+            Some(expected),
+        ) else {
+            let _ = writeln!(
+                tests,
+                "\n// Step {} ({}) maps over a value that is not an array, so it is not tested.",
+                i + 1,
+                comment(&step.id)
+            );
+            continue;
+        };
+        if ty.comparable() {
+            let (expr, _) = literal(expected, &mut imports);
+            let _ = writeln!(body, "    assert_eq!(step_{}, {});", i + 1, expr);
+        } else {
+            let _ = writeln!(
+                body,
+                "    // The recorded output {} cannot be compared with assert_eq!, so only\n    // the call is checked.\n    let _ = step_{};",
+                comment(&truncate(&values::display(expected), 40)),
+                i + 1
+            );
+        }
+
+        let _ = writeln!(
+            tests,
+            "\n// Step {}: {}\n#[test]\nfn {}() -> Result<(), String> {{\n    let _metacall = metacall_setup();\n\n{}    Ok(())\n}}",
+            i + 1,
+            comment(&step.description),
+            test_name(i, &step.id),
+            body
+        );
+    }
+
+    let mut out = String::new();
+    let _ = writeln!(
+        out,
+        "// Generated MetaCall Pipeline Tests\n\
+         // Export Date: {}\n\
+         //\n\
+         // Each test calls one step with the inputs of the last successful run and\n\
+         // checks that it still returns the recorded output.\n",
+        source.timestamp
+    );
+    if !imports.is_empty() {
+        let imports: Vec<&str> = imports.into_iter().collect();
+        let _ = writeln!(out, "use metacall::{{{}}};", imports.join(", "));
+    }
+    out.push_str("use std::sync::{Mutex, MutexGuard, OnceLock};\n\n");
+
+    out.push_str(
+        "/// Initializes MetaCall and loads the scripts once for the whole test\n\
+         /// binary. The returned guard runs the tests one at a time.\n\
+         fn metacall_setup() -> MutexGuard<'static, ()> {\n    \
+             static LOCK: Mutex<()> = Mutex::new(());\n    \
+             static SETUP: OnceLock<Result<(), String>> = OnceLock::new();\n\n    \
+             let guard = LOCK.lock().unwrap_or_else(|e| e.into_inner());\n    \
+             let setup = SETUP.get_or_init(|| {\n        \
+                 // MetaCall stays initialized until the test binary exits.\n        \
+                 std::mem::forget(metacall::initialize().map_err(|e| e.to_string())?);\n",
+    );
+    out.push_str(&setup);
+    out.push_str(
+        "        Ok(())\n    \
+             });\n    \
+             if let Err(e) = setup {\n        \
+                 panic!(\"{}\", e);\n    \
+             }\n    \
+             guard\n\
+         }\n",
+    );
+    out.push_str(&tests);
+    emit_helpers(&mut out, &helpers);
+
+    out.push_str("\n// === JSON Configuration ===\n/*\n");
+    out.push_str(&pipeline_json(source));
+    out.push_str("\n*/\n");
+
+    out
+}
+
+/// A snake case test name, unique through the step's position.
+fn test_name(i: usize, id: &str) -> String {
+    let mut name = format!("step_{}", i + 1);
+    for word in id
+        .split(|c: char| !c.is_ascii_alphanumeric())
+        .filter(|w| !w.is_empty())
+    {
+        name.push('_');
+        name.push_str(&word.to_ascii_lowercase());
+    }
+    name
+}
+
+/// Emits a `load::from_single_file` for every script the steps use, indented
+/// by `indent`.
+fn emit_loads(
+    body: &mut String,
+    imports: &mut BTreeSet<&'static str>,
+    source: &ExportSource,
+    dir: &Path,
+    relative_paths: bool,
+    indent: &str,
+) {
+    let mut loaded = BTreeSet::new();
+    for step in source.steps {
+        if !loaded.insert(step.script.as_str()) {
+            continue;
+        }
+        match source.scripts.iter().find(|s| s.name == step.script) {
+            Some(script) => {
+                imports.insert("load");
+                let _ = writeln!(
+                    body,
+                    "{indent}load::from_single_file({:?}, {})\n{indent}    .map_err(|e| format!({}, e))?;",
+                    script.runtime,
+                    script_path(&script.path, dir, relative_paths),
+                    error_format(&format!("Failed to load {}", script.name))
+                );
+            }
+            None => {
+                let _ = writeln!(
+                    body,
+                    "{indent}// {} was not found when the pipeline was exported",
+                    comment(&step.script)
+                );
+            }
+        }
+    }
+}
+
+/// Emits the call of the `i`th step into `step_<i + 1>`, typed by its
+/// observed output. Returns the type of the variable, or `None` when a map
+/// step runs over a value that is not an array, in which case the emitted
+/// code returns an error instead.
+fn emit_step(
+    body: &mut String,
+    imports: &mut BTreeSet<&'static str>,
+    helpers: &mut BTreeSet<&'static str>,
+    bindings: &HashMap<String, (String, RustType)>,
+    i: usize,
+    step: &PipelineStep,
+    observed: Option<&Value>,
+) -> Option<RustType> {
+    let var = format!("step_{}", i + 1);
+    let args: Vec<(String, RustType)> = step
+        .args
+        .iter()
+        .map(|arg| arg_expr(arg, bindings, imports, helpers))
+        .collect();
+    let ty = observed.map(RustType::of).unwrap_or(RustType::Any);
+
+    match &step.kind {
+        StepKind::Call => {
+            let ty = ty.castable();
+            let args: Vec<String> = args.into_iter().map(|(expr, _)| expr).collect();
+            let call = call_expr(&step.function, &ty, None, &args, imports);
+            let _ = writeln!(
+                body,
+                "    let {}: {} = {}\n        .map_err(|e| format!({}, e))?;",
+                var,
+                ty.name(),
+                call,
+                error_format(&format!("Step {} failed", step.id))
+            );
+            Some(ty)
+        }
+        StepKind::Map { over } => {
+            let (items, items_ty) = arg_expr(over, bindings, imports, helpers);
+            let RustType::Vec(over_item) = items_ty else {
+                let _ = writeln!(
+                    body,
+                    "    // Run the pipeline once before exporting so the items of {} can be typed.\n    return Err({:?}.to_string());",
+                    comment(over.trim()),
+                    format!(
+                        "Step {} maps over {}, which is not an array",
+                        step.id,
+                        over.trim()
+                    )
+                );
+                return None;
+            };
+            let item_ty = match ty {
+                RustType::Vec(item) => item.castable(),
+                _ => RustType::Any,
+            };
+            // Arguments are evaluated once, outside the closure, so `?`
+            // keeps returning from the enclosing function.
+            let args: Vec<String> = args
+                .iter()
+                .enumerate()
+                .map(|(j, (expr, ty))| {
+                    let var = format!("arg_{}_{}", i + 1, j + 1);
+                    let _ = writeln!(body, "    let {} = {};", var, expr);
+                    ty.owned(&var)
+                })
+                .collect();
+            let item = match *over_item {
+                RustType::Bool | RustType::Int | RustType::Float => "*item".to_string(),
+                _ => "item.clone()".to_string(),
+            };
+            let call = call_expr(&step.function, &item_ty, Some(&item), &args, imports);
+            let _ = writeln!(
+                body,
+                "    let items_{} = {};\n    let {}: Vec<{}> = items_{}\n        .iter()\n        .map(|item| {})\n        .collect::<Result<_, _>>()\n        .map_err(|e| format!({}, e))?;",
+                i + 1,
+                items,
+                var,
+                item_ty.name(),
+                i + 1,
+                call,
+                error_format(&format!("Step {} failed", step.id))
+            );
+            Some(RustType::Vec(Box::new(item_ty)))
+        }
+    }
+}
+
+/// The Rust type a MetaCall value is received as.
+#[derive(Debug, Clone, PartialEq)]
+enum RustType {
+    Bool,
+    Int,
+    Float,
+    Str,
+    Null,
+    Vec(Box<RustType>),
+    Map(Box<RustType>),
+    /// Received untyped, as `Box<dyn MetaCallValue>`.
+    Any,
+}
+
+impl RustType {
+    fn of(value: &Value) -> RustType {
+        match value {
+            Value::Null => RustType::Null,
+            Value::Bool(_) => RustType::Bool,
+            Value::Number(n) if n.is_i64() => RustType::Int,
+            Value::Number(_) => RustType::Float,
+            Value::String(_) => RustType::Str,
+            Value::Array(items) => RustType::Vec(Box::new(common_type(items.iter()))),
+            Value::Object(map) => RustType::Map(Box::new(common_type(map.values()))),
+        }
+    }
+
+    /// The type to request from `metacall::<T>`. Containers of mixed values
+    /// cannot be cast, so they are received untyped.
+    fn castable(&self) -> RustType {
+        match self {
+            RustType::Vec(item) | RustType::Map(item) if item.castable() == RustType::Any => {
+                RustType::Any
+            }
+            other => other.clone(),
+        }
+    }
+
+    fn name(&self) -> String {
+        match self {
+            RustType::Bool => "bool".to_string(),
+            RustType::Int => "i64".to_string(),
+            RustType::Float => "f64".to_string(),
+            RustType::Str => "String".to_string(),
+            RustType::Null => "MetaCallNull".to_string(),
+            RustType::Vec(item) => format!("Vec<{}>", item.name()),
+            RustType::Map(item) => format!("std::collections::HashMap<String, {}>", item.name()),
+            RustType::Any => "Box<dyn MetaCallValue>".to_string(),
+        }
+    }
+
+    /// An owned copy of the variable `var` of this type.
+    fn owned(&self, var: &str) -> String {
+        match self {
+            RustType::Bool | RustType::Int | RustType::Float => var.to_string(),
+            _ => format!("{}.clone()", var),
+        }
+    }
+
+    /// Whether received values can be compared with `assert_eq!`.
+    fn comparable(&self) -> bool {
+        match self {
+            RustType::Bool | RustType::Int | RustType::Float | RustType::Str => true,
+            RustType::Vec(item) | RustType::Map(item) => item.comparable(),
+            RustType::Null | RustType::Any => false,
+        }
+    }
+
+    /// Whether serde_json can serialize values of this type.
+    fn serializable(&self) -> bool {
+        match self {
+            RustType::Bool | RustType::Int | RustType::Float | RustType::Str => true,
+            RustType::Vec(item) | RustType::Map(item) => item.serializable(),
+            RustType::Null | RustType::Any => false,
+        }
+    }
+
+    /// An expression showing the variable `var` of this type the way the
+    /// pipeline runner splices and prints values: strings verbatim, anything
+    /// else as compact JSON.
+    fn display(
+        &self,
+        var: &str,
+        imports: &mut BTreeSet<&'static str>,
+        helpers: &mut BTreeSet<&'static str>,
+    ) -> String {
+        match self {
+            RustType::Str => var.to_string(),
+            ty if ty.serializable() => {
+                format!("serde_json::to_string(&{}).unwrap_or_default()", var)
+            }
+            _ => {
+                imports.insert("MetaCallNull");
+                imports.insert("MetaCallValue");
+                helpers.insert("display");
+                format!("display(&{})", var)
+            }
+        }
+    }
+
+    fn uses(&self, imports: &mut BTreeSet<&'static str>) {
+        match self {
+            RustType::Null => {
+                imports.insert("MetaCallNull");
+            }
+            RustType::Any => {
+                imports.insert("MetaCallValue");
+            }
+            RustType::Vec(item) | RustType::Map(item) => item.uses(imports),
+            _ => {}
+        }
+    }
+}
+
+fn common_type<'a>(mut values: impl Iterator<Item = &'a Value>) -> RustType {
+    let Some(first) = values.next().map(RustType::of) else {
+        return RustType::Any;
+    };
+    if values.all(|v| RustType::of(v) == first) {
+        first
+    } else {
+        RustType::Any
+    }
+}
+
+fn emit_param(
+    body: &mut String,
+    imports: &mut BTreeSet<&'static str>,
+    param: &PipelineParam,
+    source: &ExportSource,
+    var: &str,
+) -> RustType {
+    // Defaults are normalised through the same coercion as the TUI, so e.g. a
+    // ` 5 ` int default becomes `5`.
+    let default = param
+        .default
+        .as_ref()
+        .and_then(|d| params::coerce(param, d).ok());
+
+    if param.kind == ParamType::Json {
+        let value = default
+            .or_else(|| source.last_params.get(&param.name).cloned())
+            .unwrap_or(Value::Null);
+        let (expr, ty) = literal(&value, imports);
+        let _ = writeln!(
+            body,
+            "    // JSON parameters are fixed when the pipeline is exported.\n    let {} = {};",
+            var, expr
+        );
+        return ty;
+    }
+
+    let default = match default {
+        Some(value) => format!("Some({:?})", values::display(&value)),
+        None => "None".to_string(),
+    };
+    let read = format!("param({:?}, {})?", param.name, default);
+
+    let (ty, expr) = match param.kind {
+        ParamType::String => (RustType::Str, read),
+        ParamType::Int => (RustType::Int, parse_param(&read, param, "i64")),
+        ParamType::Float => (RustType::Float, parse_param(&read, param, "f64")),
+        ParamType::Bool => (RustType::Bool, parse_param(&read, param, "bool")),
+        ParamType::Json => unreachable!(),
+    };
+    let _ = writeln!(body, "    let {}: {} = {};", var, ty.name(), expr);
+    ty
+}
+
+fn parse_param(read: &str, param: &PipelineParam, ty: &str) -> String {
+    format!(
+        "{}\n        .trim()\n        .parse::<{}>()\n        .map_err(|_| {:?}.to_string())?",
+        read,
+        ty,
+        format!("{} expects {}", param.name, param.kind.name())
+    )
+}
+
+fn call_expr(
+    function: &str,
+    ty: &RustType,
+    item: Option<&str>,
+    args: &[String],
+    imports: &mut BTreeSet<&'static str>,
+) -> String {
+    ty.uses(imports);
+
+    let args: Vec<String> = item
+        .map(str::to_string)
+        .into_iter()
+        .chain(args.iter().cloned())
+        .map(|arg| format!("metacall_box({})", arg))
+        .collect();
+    if !args.is_empty() {
+        imports.insert("metacall_box");
+    }
+
+    let (typed, untyped) = if args.is_empty() {
+        ("metacall_no_arg", "metacall_untyped_no_arg")
+    } else {
+        ("metacall", "metacall_untyped")
+    };
+    let call = if *ty == RustType::Any {
+        imports.insert(untyped);
+        format!("{}({:?}", untyped, function)
+    } else {
+        imports.insert(typed);
+        format!("{}::<{}>({:?}", typed, ty.name(), function)
+    };
+
+    if args.is_empty() {
+        format!("{})", call)
+    } else {
+        format!("{}, [{}])", call, args.join(", "))
+    }
+}
+
+/// Turns a step argument into a Rust expression, resolving `${...}`
+/// references the way the pipeline runner does.
+fn arg_expr(
+    arg: &str,
+    bindings: &HashMap<String, (String, RustType)>,
+    imports: &mut BTreeSet<&'static str>,
+    helpers: &mut BTreeSet<&'static str>,
+) -> (String, RustType) {
+    let env = |name: &str| {
+        name.strip_prefix("env.").map(|var| {
+            format!(
+                "std::env::var({:?}).map_err(|_| {:?}.to_string())?",
+                var,
+                format!("Environment variable {} is not set", var)
+            )
+        })
+    };
+
+    let trimmed = arg.trim();
+    if let Some(name) = trimmed
+        .strip_prefix("${")
+        .and_then(|rest| rest.strip_suffix('}'))
+        && !name.contains(['{', '}'])
+    {
+        if let Some(expr) = env(name) {
+            return (expr, RustType::Str);
+        }
+        if let Some((var, ty)) = bindings.get(name) {
+            return (ty.owned(var), ty.clone());
+        }
+    }
+
+    if !arg.contains("${") {
+        return literal(&values::parse_arg(arg), imports);
+    }
+
+    let mut template = String::new();
+    let mut exprs = Vec::new();
+    let mut rest = arg;
+    while let Some(start) = rest.find("${") {
+        let Some(end) = rest[start..].find('}') else {
+            break;
+        };
+        let name = &rest[start + 2..start + end];
+        template.push_str(&format_escape(&rest[..start]));
+        let expr = match (env(name), bindings.get(name)) {
+            (Some(expr), _) => Some(expr),
+            (None, Some((var, ty))) => Some(ty.display(var, imports, helpers)),
+            (None, None) => None,
+        };
+        match expr {
+            Some(expr) => {
+                template.push_str("{}");
+                exprs.push(expr);
+            }
+            None => template.push_str(&format_escape(&rest[start..=start + end])),
+        }
+        rest = &rest[start + end + 1..];
+    }
+    template.push_str(&format_escape(rest));
+
+    if exprs.is_empty() {
+        return literal(&values::parse_arg(arg), imports);
+    }
+    (
+        format!("format!({:?}, {})", template, exprs.join(", ")),
+        RustType::Str,
+    )
+}
+
+/// Renders a value as a Rust expression of a `MetaCallValue` type.
+fn literal(value: &Value, imports: &mut BTreeSet<&'static str>) -> (String, RustType) {
+    let ty = RustType::of(value);
+    let expr = match value {
+        Value::Null => {
+            imports.insert("MetaCallNull");
+            "MetaCallNull()".to_string()
+        }
+        Value::Bool(b) => b.to_string(),
+        Value::Number(n) => match n.as_i64() {
+            Some(i) => format!("{}_i64", i),
+            None => format!("{:?}_f64", n.as_f64().unwrap_or_default()),
+        },
+        Value::String(s) => format!("String::from({:?})", s),
+        Value::Array(items) => {
+            let items = container_items(items.iter(), &ty, imports);
+            if items.is_empty() {
+                imports.insert("MetaCallNull");
+                "Vec::<MetaCallNull>::new()".to_string()
+            } else {
+                format!("vec![{}]", items.join(", "))
+            }
+        }
+        Value::Object(map) => {
+            let items = container_items(map.values(), &ty, imports);
+            if items.is_empty() {
+                imports.insert("MetaCallNull");
+                "std::collections::HashMap::<String, MetaCallNull>::new()".to_string()
+            } else {
+                let entries: Vec<String> = map
+                    .keys()
+                    .zip(items)
+                    .map(|(key, item)| format!("(String::from({:?}), {})", key, item))
+                    .collect();
+                format!("std::collections::HashMap::from([{}])", entries.join(", "))
+            }
+        }
+    };
+    (expr, ty)
+}
+
+/// Appends the helper functions the generated code calls.
+fn emit_helpers(out: &mut String, helpers: &BTreeSet<&'static str>) {
+    if helpers.contains("display") {
+        out.push_str(RUST_DISPLAY);
+    }
+}
+
+const RUST_DISPLAY: &str = r#"
+/// Shows a value the way the pipeline runner does: strings verbatim,
+/// anything else as compact JSON.
+fn display(value: &dyn MetaCallValue) -> String {
+    match json(value) {
+        serde_json::Value::String(s) => s,
+        other => other.to_string(),
+    }
+}
+
+fn json(value: &dyn MetaCallValue) -> serde_json::Value {
+    use std::collections::HashMap;
+
+    if let Some(v) = value.downcast_ref::<Box<dyn MetaCallValue>>() {
+        json(&**v)
+    } else if let Some(v) = value.downcast_ref::<Vec<Box<dyn MetaCallValue>>>() {
+        v.iter().map(|v| json(&**v)).collect()
+    } else if let Some(v) = value.downcast_ref::<HashMap<String, Box<dyn MetaCallValue>>>() {
+        v.iter().map(|(k, v)| (k.clone(), json(&**v))).collect()
+    } else if let Some(v) = value.downcast_ref::<Vec<MetaCallNull>>() {
+        vec![serde_json::Value::Null; v.len()].into()
+    } else if let Some(v) = value.downcast_ref::<String>() {
+        v.clone().into()
+    } else if let Some(v) = value.downcast_ref::<bool>() {
+        (*v).into()
+    } else if let Some(v) = value.downcast_ref::<i16>() {
+        (*v).into()
+    } else if let Some(v) = value.downcast_ref::<i32>() {
+        (*v).into()
+    } else if let Some(v) = value.downcast_ref::<i64>() {
+        (*v).into()
+    } else if let Some(v) = value.downcast_ref::<f32>() {
+        (*v).into()
+    } else if let Some(v) = value.downcast_ref::<f64>() {
+        (*v).into()
+    } else if let Some(v) = value.downcast_ref::<char>() {
+        v.to_string().into()
+    } else if value.is::<MetaCallNull>() {
+        serde_json::Value::Null
+    } else {
+        format!("{:?}", value).into()
+    }
+}
+"#;
+
+/// Item expressions of an array or object literal. Items of mixed types are
+/// boxed so the container stays homogeneous.
+fn container_items<'a>(
+    items: impl Iterator<Item = &'a Value>,
+    ty: &RustType,
+    imports: &mut BTreeSet<&'static str>,
+) -> Vec<String> {
+    let mixed = matches!(ty, RustType::Vec(item) | RustType::Map(item) if **item == RustType::Any);
+    items
+        .map(|item| {
+            let (expr, _) = literal(item, imports);
+            if mixed {
+                imports.insert("metacall_box");
+                format!("metacall_box({})", expr)
+            } else {
+                expr
+            }
+        })
+        .collect()
+}
+
+/// Escapes braces so text can be used inside a `format!` string.
+fn format_escape(text: &str) -> String {
+    text.replace('{', "{{").replace('}', "}}")
+}
+
+/// A format string literal of `prefix` followed by the debug printed error.
+fn error_format(prefix: &str) -> String {
+    format!("{:?}", format!("{}: {{:?}}", format_escape(prefix)))
+}
+
+fn script_path(path: &Path, dir: &Path, relative: bool) -> String {
+    let (absolute, relative_to_dir) = script_location(path, dir);
+    if relative {
+        format!(
+            "concat!(env!(\"CARGO_MANIFEST_DIR\"), {:?})",
+            format!("/{}", relative_to_dir)
+        )
+    } else {
+        format!("{:?}", absolute)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::export::{tests::Fixture, write};
+    use std::fs;
+    use std::process::Command;
+
+    #[test]
+    fn generated_projects_compile() {
+        let root = std::env::temp_dir().join(format!("polyglot-export-{}", std::process::id()));
+        let fixture = Fixture::new(&root.join("scripts"));
+        let source = fixture.source();
+
+        let program = root.join("exported pipeline");
+        let tests = root.join("exported tests");
+        write(&project(&source, &program, true)).unwrap();
+        write(&test_project(&source, &tests, true)).unwrap();
+
+        for dir in [program, tests] {
+            let output =
+                Command::new(std::env::var("CARGO").unwrap_or_else(|_| "cargo".to_string()))
+                    .args(["check", "--offline", "--quiet", "--all-targets"])
+                    .current_dir(&dir)
+                    .env(
+                        "CARGO_TARGET_DIR",
+                        std::env::temp_dir().join("polyglot-export-target"),
+                    )
+                    .output()
+                    .unwrap();
+            assert!(
+                output.status.success(),
+                "{} does not compile:\n{}",
+                dir.display(),
+                String::from_utf8_lossy(&output.stderr)
+            );
+        }
+        let _ = fs::remove_dir_all(&root);
+    }
+}
@@ -0,0 +1,126 @@
+use crate::App;
+use crate::models::PipelineRun;
+use std::{collections::HashMap, fs, io, path::PathBuf};
+
+const USAGE: &str = "Usage: polyglot-devops-thing run <pipeline file> [--dir <scripts dir>] [--param name=value]... [--json]";
+
+/// Runs a pipeline file without the TUI, prints its test report and returns
+/// the process exit code: 0 when every step and assertion passed, 1 when
+/// something failed and 2 for usage errors.
+pub fn run(args: &[String]) -> io::Result<i32> {
+    let mut pipeline_file = None;
+    let mut root_dir = None;
+    let mut json = false;
+    let mut inputs = HashMap::new();
+
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--dir" => match args.next() {
+                Some(dir) => root_dir = Some(PathBuf::from(dir)),
+                None => return usage_error("--dir needs a value"),
+            },
+            "--param" => match args.next().and_then(|p| p.split_once('=')) {
+                Some((name, value)) => {
+                    inputs.insert(name.trim().to_string(), value.to_string());
+                }
+                None => return usage_error("--param expects name=value"),
+            },
+            "--json" => json = true,
+            _ if pipeline_file.is_none() && !arg.starts_with("--") => {
+                pipeline_file = Some(PathBuf::from(arg))
+            }
+            _ => return usage_error(&format!("Unexpected argument '{}'", arg)),
+        }
+    }
+
+    let Some(pipeline_file) = pipeline_file else {
+        return usage_error("Missing pipeline file");
+    };
+
+    let content = fs::read_to_string(&pipeline_file)?;
+    let pipeline = crate::import::parse_pipeline(&content, &pipeline_file).map_err(|e| {
+        io::Error::other(format!(
+            "Invalid pipeline file {}: {}",
+            pipeline_file.display(),
+            e
+        ))
+    })?;
+
+    let root_dir = match root_dir {
+        Some(dir) => dir,
+        None => std::env::current_dir()?,
+    };
+
+    let mut app = App::new(root_dir);
+    app.pipeline = pipeline.steps;
+    app.params = pipeline.params;
+
+    if let Err(e) = crate::params::resolve_inputs(&app.params, &inputs) {
+        return usage_error(&e);
+    }
+    let _ = app.execute_pipeline(&inputs);
+
+    let Some(run) = app.runs.last() else {
+        eprintln!("Pipeline is empty");
+        return Ok(1);
+    };
+
+    if json {
+        println!("{}", serde_json::to_string_pretty(run).unwrap_or_default());
+    } else {
+        print!("{}", format_report(run));
+    }
+
+    Ok(if run.success() { 0 } else { 1 })
+}
+
+fn usage_error(message: &str) -> io::Result<i32> {
+    eprintln!("{}\n{}", message, USAGE);
+    Ok(2)
+}
+
+pub fn format_report(run: &PipelineRun) -> String {
+    let mut out = String::new();
+
+    for step_run in &run.steps {
+        let result = &step_run.result;
+        let status = if result.success { "ok" } else { "FAILED" };
+        out.push_str(&format!(
+            "{} {} ({}ms) {}\n",
+            step_run.step.id, result.function, result.duration_ms, status
+        ));
+
+        if !result.success {
+            out.push_str(&format!("    error: {}\n", result.output));
+        }
+
+        for assertion in &step_run.assertions {
+            if assertion.passed {
+                out.push_str(&format!("    ✓ {}\n", assertion.expectation));
+            } else {
+                out.push_str(&format!(
+                    "    ✗ {} — {}\n",
+                    assertion.expectation, assertion.message
+                ));
+            }
+        }
+    }
+
+    for skipped in run.steps.len()..run.total_steps {
+        out.push_str(&format!("step #{} skipped\n", skipped + 1));
+    }
+
+    let (passed, failed) = run.assertion_counts();
+    out.push_str(&format!(
+        "\n{}: {}/{} steps, {} assertions passed, {} failed in {}ms\n",
+        if run.success() { "PASS" } else { "FAIL" },
+        run.steps.iter().filter(|s| s.result.success).count(),
+        run.total_steps,
+        passed,
+        failed,
+        run.duration_ms
+    ));
+
+    out
+}
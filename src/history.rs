@@ -0,0 +1,252 @@
+use crate::config::DATA_DIR;
+use crate::models::ExecutionResult;
+use std::{
+    fs::{self, OpenOptions},
+    io::{self, Write},
+    path::{Path, PathBuf},
+};
+
+const HISTORY_FILE: &str = "history.jsonl";
+const BASELINES_FILE: &str = "baselines.json";
+
+/// Execution results kept across sessions as JSON Lines, oldest first, in
+/// `.polyglot/history.jsonl` under the scripts directory.
+pub struct History {
+    path: PathBuf,
+    retention: usize,
+}
+
+/// What was read back from the history file.
+pub struct Loaded {
+    pub results: Vec<ExecutionResult>,
+    /// Lines that could not be read as a result and were dropped.
+    pub skipped: usize,
+}
+
+impl History {
+    pub fn new(root_dir: &Path, retention: usize) -> Self {
+        Self {
+            path: root_dir.join(DATA_DIR).join(HISTORY_FILE),
+            retention,
+        }
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub fn retention(&self) -> usize {
+        self.retention
+    }
+
+    /// Reads the newest `retention` results. The file is rewritten when
+    /// older results or unreadable lines had to be dropped, so it does not
+    /// grow past the retention between sessions.
+    pub fn load(&self) -> Result<Loaded, String> {
+        let content = match fs::read_to_string(&self.path) {
+            Ok(content) => content,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => {
+                return Ok(Loaded {
+                    results: Vec::new(),
+                    skipped: 0,
+                });
+            }
+            Err(e) => return Err(format!("Failed to read {}: {}", self.path.display(), e)),
+        };
+
+        let mut results = Vec::new();
+        let mut skipped = 0;
+        for line in content.lines().filter(|line| !line.trim().is_empty()) {
+            match serde_json::from_str::<ExecutionResult>(line) {
+                Ok(result) => results.push(result),
+                Err(_) => skipped += 1,
+            }
+        }
+
+        let excess = results.len().saturating_sub(self.retention);
+        results.drain(..excess);
+        if excess > 0 || skipped > 0 {
+            self.rewrite(&results)?;
+        }
+        Ok(Loaded { results, skipped })
+    }
+
+    pub fn append(&self, result: &ExecutionResult) -> Result<(), String> {
+        let line = serde_json::to_string(result).map_err(|e| e.to_string())?;
+        self.create_dir()?;
+        OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)
+            .and_then(|mut file| writeln!(file, "{}", line))
+            .map_err(|e| format!("Failed to write {}: {}", self.path.display(), e))
+    }
+
+    /// Deletes every stored result.
+    pub fn clear(&self) -> Result<(), String> {
+        match fs::remove_file(&self.path) {
+            Err(e) if e.kind() != io::ErrorKind::NotFound => {
+                Err(format!("Failed to remove {}: {}", self.path.display(), e))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    fn rewrite(&self, results: &[ExecutionResult]) -> Result<(), String> {
+        let mut content = String::new();
+        for result in results {
+            content.push_str(&serde_json::to_string(result).map_err(|e| e.to_string())?);
+            content.push('\n');
+        }
+        self.create_dir()?;
+        fs::write(&self.path, content)
+            .map_err(|e| format!("Failed to write {}: {}", self.path.display(), e))
+    }
+
+    fn create_dir(&self) -> Result<(), String> {
+        match self.path.parent() {
+            Some(dir) => fs::create_dir_all(dir)
+                .map_err(|e| format!("Failed to create {}: {}", dir.display(), e)),
+            None => Ok(()),
+        }
+    }
+}
+
+/// Results saved as the expected output of a call, at most one per function
+/// and argument list, in `.polyglot/baselines.json`.
+pub struct Baselines {
+    path: PathBuf,
+    results: Vec<ExecutionResult>,
+}
+
+impl Baselines {
+    pub fn new(root_dir: &Path) -> Self {
+        Self {
+            path: root_dir.join(DATA_DIR).join(BASELINES_FILE),
+            results: Vec::new(),
+        }
+    }
+
+    /// Reads the stored baselines, replacing the ones in memory, and returns
+    /// how many there are.
+    pub fn load(&mut self) -> Result<usize, String> {
+        self.results = match fs::read_to_string(&self.path) {
+            Ok(content) => serde_json::from_str(&content)
+                .map_err(|e| format!("Invalid baselines in {}: {}", self.path.display(), e))?,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Vec::new(),
+            Err(e) => return Err(format!("Failed to read {}: {}", self.path.display(), e)),
+        };
+        Ok(self.results.len())
+    }
+
+    pub fn get(&self, function: &str, args: &[String]) -> Option<&ExecutionResult> {
+        self.results
+            .iter()
+            .find(|r| r.function == function && r.args == args)
+    }
+
+    /// Makes `result` the baseline of its call, replacing any earlier one.
+    pub fn set(&mut self, result: &ExecutionResult) -> Result<(), String> {
+        self.results
+            .retain(|r| r.function != result.function || r.args != result.args);
+        self.results.push(result.clone());
+        self.save()
+    }
+
+    /// Removes the baseline of a call, returning whether there was one.
+    pub fn remove(&mut self, function: &str, args: &[String]) -> Result<bool, String> {
+        let before = self.results.len();
+        self.results
+            .retain(|r| r.function != function || r.args != args);
+        if self.results.len() == before {
+            return Ok(false);
+        }
+        self.save().map(|_| true)
+    }
+
+    fn save(&self) -> Result<(), String> {
+        let content = serde_json::to_string_pretty(&self.results).map_err(|e| e.to_string())?;
+        if let Some(dir) = self.path.parent() {
+            fs::create_dir_all(dir)
+                .map_err(|e| format!("Failed to create {}: {}", dir.display(), e))?;
+        }
+        fs::write(&self.path, content)
+            .map_err(|e| format!("Failed to write {}: {}", self.path.display(), e))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn result(function: &str, success: bool) -> ExecutionResult {
+        ExecutionResult {
+            function: function.to_string(),
+            args: vec!["1".to_string()],
+            output: "2".to_string(),
+            value: Some(serde_json::json!(2)),
+            duration_ms: 3,
+            success,
+            timestamp: crate::time::now(),
+            run_id: None,
+            changed: false,
+            mapped: false,
+            replay_of: None,
+            assertions: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn keeps_the_newest_results_within_retention() {
+        let dir = std::env::temp_dir().join(format!("polyglot-history-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        let history = History::new(&dir, 2);
+
+        assert!(history.load().unwrap().results.is_empty());
+        for name in ["a", "b", "c"] {
+            history.append(&result(name, name != "b")).unwrap();
+        }
+        let mut file = OpenOptions::new()
+            .append(true)
+            .open(history.path())
+            .unwrap();
+        writeln!(file, "{{not a result").unwrap();
+
+        let loaded = history.load().unwrap();
+        let names: Vec<&str> = loaded.results.iter().map(|r| r.function.as_str()).collect();
+        assert_eq!(names, ["b", "c"]);
+        assert_eq!(loaded.skipped, 1);
+        assert!(!loaded.results[0].success);
+
+        let content = fs::read_to_string(history.path()).unwrap();
+        assert_eq!(content.lines().count(), 2);
+
+        history.clear().unwrap();
+        assert!(history.load().unwrap().results.is_empty());
+        history.clear().unwrap();
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn keeps_one_baseline_per_call() {
+        let dir = std::env::temp_dir().join(format!("polyglot-baselines-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        let mut baselines = Baselines::new(&dir);
+        assert_eq!(baselines.load().unwrap(), 0);
+
+        let mut expected = result("calc.py::add", true);
+        baselines.set(&expected).unwrap();
+        expected.output = "3".to_string();
+        baselines.set(&expected).unwrap();
+
+        let mut reloaded = Baselines::new(&dir);
+        assert_eq!(reloaded.load().unwrap(), 1);
+        let args = vec!["1".to_string()];
+        assert_eq!(reloaded.get("calc.py::add", &args).unwrap().output, "3");
+        assert!(reloaded.get("calc.py::add", &[]).is_none());
+
+        assert!(reloaded.remove("calc.py::add", &args).unwrap());
+        assert!(!reloaded.remove("calc.py::add", &args).unwrap());
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,94 @@
+use crate::models::PipelineFile;
+use std::path::Path;
+
+const MARKER: &str = "=== JSON Configuration ===";
+
+/// Reads a pipeline from pipeline JSON or YAML, or from the configuration
+/// block embedded in an exported program or Markdown document. The format
+/// is picked by extension, and files with other extensions are tried as
+/// JSON, then YAML.
+pub fn parse_pipeline(content: &str, path: &Path) -> Result<PipelineFile, String> {
+    let extension = path
+        .extension()
+        .map(|e| e.to_string_lossy().to_lowercase())
+        .unwrap_or_default();
+
+    match extension.as_str() {
+        "json" => from_json(content),
+        "yaml" | "yml" => from_yaml(content),
+        "rs" | "js" | "py" | "md" => {
+            let block = embedded_block(content).ok_or_else(|| {
+                format!("{} has no embedded pipeline configuration", path.display())
+            })?;
+            from_json(&block)
+        }
+        _ => from_json(content)
+            .or_else(|_| from_yaml(content))
+            .or_else(|e| match embedded_block(content) {
+                Some(block) => from_json(&block),
+                None => Err(e),
+            }),
+    }
+}
+
+fn from_json(content: &str) -> Result<PipelineFile, String> {
+    serde_json::from_str(content).map_err(|e| format!("Invalid pipeline JSON: {}", e))
+}
+
+fn from_yaml(content: &str) -> Result<PipelineFile, String> {
+    serde_yaml::from_str(content).map_err(|e| format!("Invalid pipeline YAML: {}", e))
+}
+
+/// The pipeline JSON of an export: a `/* ... */` block or `#` comment lines
+/// after the configuration marker, or the `json` fence of a Markdown
+/// document.
+fn embedded_block(content: &str) -> Option<String> {
+    if let Some(start) = content.rfind(MARKER) {
+        let mut lines = content[start + MARKER.len()..].lines().skip(1);
+        let first = lines.find(|line| !line.trim().is_empty())?;
+
+        if first.trim() == "/*" {
+            let json: Vec<&str> = lines.take_while(|line| line.trim() != "*/").collect();
+            return Some(json.join("\n"));
+        }
+        if first.starts_with('#') {
+            let json: Vec<&str> = std::iter::once(first)
+                .chain(lines)
+                .map_while(|line| line.strip_prefix('#'))
+                .map(|line| line.strip_prefix(' ').unwrap_or(line))
+                .collect();
+            return Some(json.join("\n"));
+        }
+        return None;
+    }
+
+    let section = &content[content.find("## Pipeline JSON")?..];
+    let fence = &section[section.find("```json")?..];
+    let json: Vec<&str> = fence
+        .lines()
+        .skip(1)
+        .take_while(|line| !line.starts_with("```"))
+        .collect();
+    Some(json.join("\n"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_the_step_list_of_older_exports() {
+        let exported = "fn main() {}\n\n// === JSON Configuration ===\n/*\n[\n  {\n    \"id\": \"step1\",\n    \"script\": \"calc.py\",\n    \"function\": \"add\",\n    \"args\": [\"1\", \"2\"],\n    \"description\": \"Call add\"\n  }\n]\n*/\n";
+        let pipeline = parse_pipeline(exported, Path::new("pipeline.rs")).unwrap();
+        assert!(pipeline.params.is_empty());
+        assert_eq!(pipeline.steps.len(), 1);
+        assert_eq!(pipeline.steps[0].function, "add");
+    }
+
+    #[test]
+    fn reports_files_without_a_pipeline() {
+        let error = parse_pipeline("fn main() {}\n", Path::new("main.rs")).unwrap_err();
+        assert!(error.contains("no embedded pipeline"));
+        assert!(parse_pipeline("not: [a pipeline", Path::new("notes.txt")).is_err());
+    }
+}
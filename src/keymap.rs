@@ -0,0 +1,1031 @@
+use crate::config::DATA_DIR;
+use crate::models::View;
+use crossterm::event::{KeyCode, KeyModifiers};
+use serde::Deserialize;
+use std::{collections::HashMap, fs, io, path::Path};
+
+const KEYMAP_FILE: &str = "keymap.json";
+
+/// Where a binding applies: everywhere, in one view, or in the output
+/// viewer. View bindings take precedence over global ones.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Scope {
+    Global,
+    Scripts,
+    Tester,
+    Pipeline,
+    Runs,
+    Results,
+    Stats,
+    Export,
+    Logs,
+    Viewer,
+}
+
+impl Scope {
+    pub fn of(view: &View) -> Self {
+        match view {
+            View::ScriptBrowser => Scope::Scripts,
+            View::FunctionTester => Scope::Tester,
+            View::PipelineBuilder => Scope::Pipeline,
+            View::RunHistory => Scope::Runs,
+            View::ResultsExplorer => Scope::Results,
+            View::Statistics => Scope::Stats,
+            View::Export => Scope::Export,
+            View::Logs => Scope::Logs,
+        }
+    }
+
+    /// The view whose keys the scope holds.
+    pub fn view(&self) -> Option<View> {
+        match self {
+            Scope::Scripts => Some(View::ScriptBrowser),
+            Scope::Tester => Some(View::FunctionTester),
+            Scope::Pipeline => Some(View::PipelineBuilder),
+            Scope::Runs => Some(View::RunHistory),
+            Scope::Results => Some(View::ResultsExplorer),
+            Scope::Stats => Some(View::Statistics),
+            Scope::Export => Some(View::Export),
+            Scope::Logs => Some(View::Logs),
+            Scope::Global | Scope::Viewer => None,
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Scope::Global => "Global",
+            Scope::Scripts => "Script Browser",
+            Scope::Tester => "Function Tester",
+            Scope::Pipeline => "Pipeline Builder",
+            Scope::Runs => "Run History",
+            Scope::Results => "Results Explorer",
+            Scope::Stats => "Statistics",
+            Scope::Export => "Export View",
+            Scope::Logs => "Logs",
+            Scope::Viewer => "Output Viewer",
+        }
+    }
+}
+
+/// Something a key can be bound to. Names in the keymap file are the
+/// snake_case variant names, e.g. `next_view`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Action {
+    Quit,
+    Help,
+    NextView,
+    PrevView,
+    CommandPalette,
+    Up,
+    Down,
+    Left,
+    Right,
+    PageUp,
+    PageDown,
+    Top,
+    Bottom,
+    LoadScript,
+    Rescan,
+    EditArgs,
+    ClearArgs,
+    Execute,
+    AddToPipeline,
+    RunPipeline,
+    EditStep,
+    InsertStep,
+    DuplicateStep,
+    MoveStepUp,
+    MoveStepDown,
+    EditParams,
+    ImportPipeline,
+    DeleteStep,
+    ClearPipeline,
+    Rerun,
+    RerunFailed,
+    Compare,
+    ClearCompare,
+    ViewOutput,
+    FilterStatus,
+    FilterScript,
+    FilterFunction,
+    FilterLanguage,
+    Search,
+    Sort,
+    ResetFilters,
+    Replay,
+    SendToTester,
+    Mark,
+    Baseline,
+    Diff,
+    ExportResults,
+    ClearHistory,
+    SaveExport,
+    OutputDir,
+    TogglePaths,
+    ToggleInfo,
+    ToggleSuccess,
+    ToggleWarning,
+    ToggleError,
+    ClearLogs,
+    Close,
+    ToggleWrap,
+    TogglePretty,
+    NextMatch,
+    PrevMatch,
+    Copy,
+}
+
+impl Action {
+    /// The name used in the keymap file, e.g. `next_view`.
+    pub fn name(&self) -> String {
+        let mut name = String::new();
+        for c in format!("{:?}", self).chars() {
+            if c.is_ascii_uppercase() && !name.is_empty() {
+                name.push('_');
+            }
+            name.push(c.to_ascii_lowercase());
+        }
+        name
+    }
+}
+
+/// Every action of every scope with its default keys and help text, in the
+/// order the help lists them.
+const DEFAULTS: &[(Scope, Action, &[&str], &str)] = &[
+    (Scope::Global, Action::NextView, &["tab"], "Next view"),
+    (
+        Scope::Global,
+        Action::PrevView,
+        &["shift+tab"],
+        "Previous view",
+    ),
+    (
+        Scope::Global,
+        Action::CommandPalette,
+        &["ctrl+p"],
+        "Search functions and actions",
+    ),
+    (Scope::Global, Action::Help, &["?"], "Toggle this help"),
+    (
+        Scope::Global,
+        Action::Quit,
+        &["q", "ctrl+q"],
+        "Quit application",
+    ),
+    (
+        Scope::Scripts,
+        Action::Up,
+        &["up"],
+        "Select the previous script",
+    ),
+    (
+        Scope::Scripts,
+        Action::Down,
+        &["down"],
+        "Select the next script",
+    ),
+    (
+        Scope::Scripts,
+        Action::LoadScript,
+        &["l"],
+        "Load or unload the selected script",
+    ),
+    (Scope::Scripts, Action::Rescan, &["r"], "Rescan directory"),
+    (
+        Scope::Tester,
+        Action::Up,
+        &["up"],
+        "Select the previous function",
+    ),
+    (
+        Scope::Tester,
+        Action::Down,
+        &["down"],
+        "Select the next function",
+    ),
+    (
+        Scope::Tester,
+        Action::EditArgs,
+        &["a"],
+        "Edit arguments, one per line",
+    ),
+    (Scope::Tester, Action::ClearArgs, &["c"], "Clear arguments"),
+    (
+        Scope::Tester,
+        Action::Execute,
+        &["enter"],
+        "Execute function",
+    ),
+    (
+        Scope::Tester,
+        Action::AddToPipeline,
+        &["p"],
+        "Add to pipeline",
+    ),
+    (
+        Scope::Pipeline,
+        Action::Up,
+        &["up"],
+        "Select the previous step",
+    ),
+    (
+        Scope::Pipeline,
+        Action::Down,
+        &["down"],
+        "Select the next step",
+    ),
+    (
+        Scope::Pipeline,
+        Action::RunPipeline,
+        &["x"],
+        "Execute pipeline",
+    ),
+    (
+        Scope::Pipeline,
+        Action::EditStep,
+        &["e", "enter"],
+        "Edit selected step",
+    ),
+    (
+        Scope::Pipeline,
+        Action::InsertStep,
+        &["i"],
+        "Insert a new step",
+    ),
+    (
+        Scope::Pipeline,
+        Action::DuplicateStep,
+        &["y"],
+        "Duplicate selected step",
+    ),
+    (Scope::Pipeline, Action::MoveStepUp, &["K"], "Move step up"),
+    (
+        Scope::Pipeline,
+        Action::MoveStepDown,
+        &["J"],
+        "Move step down",
+    ),
+    (
+        Scope::Pipeline,
+        Action::EditParams,
+        &["P"],
+        "Declare pipeline parameters",
+    ),
+    (
+        Scope::Pipeline,
+        Action::ImportPipeline,
+        &["I"],
+        "Import a pipeline file or exported program",
+    ),
+    (
+        Scope::Pipeline,
+        Action::DeleteStep,
+        &["d"],
+        "Delete selected step",
+    ),
+    (
+        Scope::Pipeline,
+        Action::ClearPipeline,
+        &["c"],
+        "Clear all steps",
+    ),
+    (Scope::Runs, Action::Up, &["up"], "Select the previous run"),
+    (Scope::Runs, Action::Down, &["down"], "Select the next run"),
+    (
+        Scope::Runs,
+        Action::Left,
+        &["left"],
+        "Select the previous step of the run",
+    ),
+    (
+        Scope::Runs,
+        Action::Right,
+        &["right"],
+        "Select the next step of the run",
+    ),
+    (
+        Scope::Runs,
+        Action::Rerun,
+        &["r"],
+        "Rerun from selected step",
+    ),
+    (
+        Scope::Runs,
+        Action::RerunFailed,
+        &["f"],
+        "Rerun from first failed step",
+    ),
+    (
+        Scope::Runs,
+        Action::Compare,
+        &["m"],
+        "Mark run to compare against",
+    ),
+    (
+        Scope::Runs,
+        Action::ClearCompare,
+        &["esc"],
+        "Stop comparing",
+    ),
+    (
+        Scope::Results,
+        Action::Up,
+        &["up"],
+        "Select the previous result",
+    ),
+    (
+        Scope::Results,
+        Action::Down,
+        &["down"],
+        "Select the next result",
+    ),
+    (
+        Scope::Results,
+        Action::ViewOutput,
+        &["enter", "v"],
+        "View the full output (scroll, search, copy)",
+    ),
+    (
+        Scope::Results,
+        Action::FilterStatus,
+        &["f"],
+        "Filter all / passed / failed results",
+    ),
+    (
+        Scope::Results,
+        Action::FilterScript,
+        &["s"],
+        "Filter by script",
+    ),
+    (
+        Scope::Results,
+        Action::FilterFunction,
+        &["n"],
+        "Filter by function",
+    ),
+    (
+        Scope::Results,
+        Action::FilterLanguage,
+        &["l"],
+        "Filter by language",
+    ),
+    (
+        Scope::Results,
+        Action::Search,
+        &["/"],
+        "Search args and output (Esc clears)",
+    ),
+    (
+        Scope::Results,
+        Action::Sort,
+        &["o"],
+        "Sort by time or duration",
+    ),
+    (
+        Scope::Results,
+        Action::ResetFilters,
+        &["x"],
+        "Reset the filters",
+    ),
+    (
+        Scope::Results,
+        Action::Replay,
+        &["r"],
+        "Replay the call with the same args",
+    ),
+    (
+        Scope::Results,
+        Action::SendToTester,
+        &["t"],
+        "Open the function and args in the Function Tester",
+    ),
+    (
+        Scope::Results,
+        Action::Mark,
+        &["m"],
+        "Mark the result to diff others against",
+    ),
+    (
+        Scope::Results,
+        Action::Baseline,
+        &["b"],
+        "Save / remove the result as its call's baseline",
+    ),
+    (
+        Scope::Results,
+        Action::Diff,
+        &["d"],
+        "Diff against the marked result, baseline or previous call",
+    ),
+    (
+        Scope::Results,
+        Action::ExportResults,
+        &["e"],
+        "Export results as JSON Lines, CSV or JUnit XML",
+    ),
+    (
+        Scope::Results,
+        Action::ClearHistory,
+        &["C"],
+        "Clear the results history",
+    ),
+    (
+        Scope::Stats,
+        Action::Up,
+        &["up"],
+        "Chart the previous function's latency",
+    ),
+    (
+        Scope::Stats,
+        Action::Down,
+        &["down"],
+        "Chart the next function's latency",
+    ),
+    (
+        Scope::Export,
+        Action::Up,
+        &["up"],
+        "Select the previous export format",
+    ),
+    (
+        Scope::Export,
+        Action::Down,
+        &["down"],
+        "Select the next export format",
+    ),
+    (
+        Scope::Export,
+        Action::PageUp,
+        &["pageup"],
+        "Scroll the preview up",
+    ),
+    (
+        Scope::Export,
+        Action::PageDown,
+        &["pagedown"],
+        "Scroll the preview down",
+    ),
+    (
+        Scope::Export,
+        Action::SaveExport,
+        &["s"],
+        "Save in the selected format",
+    ),
+    (
+        Scope::Export,
+        Action::OutputDir,
+        &["o"],
+        "Set the output directory",
+    ),
+    (
+        Scope::Export,
+        Action::TogglePaths,
+        &["p"],
+        "Toggle absolute / relative script paths",
+    ),
+    (
+        Scope::Logs,
+        Action::Up,
+        &["up"],
+        "Select the previous entry",
+    ),
+    (
+        Scope::Logs,
+        Action::Down,
+        &["down"],
+        "Select the next entry",
+    ),
+    (
+        Scope::Logs,
+        Action::PageUp,
+        &["pageup"],
+        "Scroll back a page",
+    ),
+    (
+        Scope::Logs,
+        Action::PageDown,
+        &["pagedown"],
+        "Scroll forward a page",
+    ),
+    (
+        Scope::Logs,
+        Action::Top,
+        &["home"],
+        "Go to the oldest entry",
+    ),
+    (
+        Scope::Logs,
+        Action::Bottom,
+        &["end"],
+        "Follow the newest entry",
+    ),
+    (
+        Scope::Logs,
+        Action::ToggleInfo,
+        &["i"],
+        "Show or hide info entries",
+    ),
+    (
+        Scope::Logs,
+        Action::ToggleSuccess,
+        &["s"],
+        "Show or hide success entries",
+    ),
+    (
+        Scope::Logs,
+        Action::ToggleWarning,
+        &["w"],
+        "Show or hide warnings",
+    ),
+    (
+        Scope::Logs,
+        Action::ToggleError,
+        &["e"],
+        "Show or hide errors",
+    ),
+    (Scope::Logs, Action::Search, &["/"], "Search the messages"),
+    (
+        Scope::Logs,
+        Action::ResetFilters,
+        &["x"],
+        "Reset the filters",
+    ),
+    (
+        Scope::Logs,
+        Action::ClearLogs,
+        &["C"],
+        "Clear the log buffer",
+    ),
+    (
+        Scope::Viewer,
+        Action::Close,
+        &["esc", "q"],
+        "Close the viewer",
+    ),
+    (Scope::Viewer, Action::Up, &["up", "k"], "Scroll up"),
+    (Scope::Viewer, Action::Down, &["down", "j"], "Scroll down"),
+    (
+        Scope::Viewer,
+        Action::PageUp,
+        &["pageup"],
+        "Scroll up a page",
+    ),
+    (
+        Scope::Viewer,
+        Action::PageDown,
+        &["pagedown"],
+        "Scroll down a page",
+    ),
+    (Scope::Viewer, Action::Top, &["home"], "Go to the top"),
+    (Scope::Viewer, Action::Bottom, &["end"], "Go to the bottom"),
+    (
+        Scope::Viewer,
+        Action::Left,
+        &["left"],
+        "Scroll left when not wrapping",
+    ),
+    (
+        Scope::Viewer,
+        Action::Right,
+        &["right"],
+        "Scroll right when not wrapping",
+    ),
+    (
+        Scope::Viewer,
+        Action::ToggleWrap,
+        &["w"],
+        "Toggle line wrapping",
+    ),
+    (
+        Scope::Viewer,
+        Action::TogglePretty,
+        &["p"],
+        "Toggle pretty-printed JSON",
+    ),
+    (Scope::Viewer, Action::Search, &["/"], "Search the output"),
+    (Scope::Viewer, Action::NextMatch, &["n"], "Next match"),
+    (Scope::Viewer, Action::PrevMatch, &["N"], "Previous match"),
+    (
+        Scope::Viewer,
+        Action::Copy,
+        &["y"],
+        "Copy the output to the clipboard",
+    ),
+];
+
+/// Keys the vim preset adds to actions that have them, in scopes where the
+/// key is not already taken.
+const VIM_KEYS: &[(Action, &str)] = &[
+    (Action::Up, "k"),
+    (Action::Down, "j"),
+    (Action::Left, "h"),
+    (Action::Right, "l"),
+    (Action::Top, "g"),
+    (Action::Bottom, "G"),
+    (Action::PageUp, "ctrl+u"),
+    (Action::PageDown, "ctrl+d"),
+];
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Preset {
+    #[default]
+    Default,
+    Vim,
+}
+
+/// A key with the Ctrl and Alt modifiers. Shift is part of the character
+/// (`K`) rather than a modifier.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct KeyPress {
+    code: KeyCode,
+    modifiers: KeyModifiers,
+}
+
+impl KeyPress {
+    pub fn new(code: KeyCode, modifiers: KeyModifiers) -> Self {
+        let modifiers = modifiers & (KeyModifiers::CONTROL | KeyModifiers::ALT);
+        // Terminals report Ctrl+S and Ctrl+Shift+S alike.
+        let code = match code {
+            KeyCode::Char(c) if modifiers.contains(KeyModifiers::CONTROL) => {
+                KeyCode::Char(c.to_ascii_lowercase())
+            }
+            code => code,
+        };
+        Self { code, modifiers }
+    }
+
+    /// Reads a key such as `x`, `G`, `enter`, `pagedown`, `f5`, `ctrl+s` or
+    /// `shift+tab`.
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let invalid = || format!("Invalid key '{}'", text);
+        let (prefix, name) = match text.strip_suffix("++") {
+            Some(prefix) => (prefix.strip_suffix('+').unwrap_or(prefix), "+"),
+            None => match text.rsplit_once('+') {
+                Some((prefix, name)) if !name.is_empty() => (prefix, name),
+                _ => ("", text),
+            },
+        };
+
+        let mut modifiers = KeyModifiers::NONE;
+        for modifier in prefix.split('+').filter(|m| !m.is_empty()) {
+            modifiers |= match modifier.to_lowercase().as_str() {
+                "ctrl" | "control" => KeyModifiers::CONTROL,
+                "alt" => KeyModifiers::ALT,
+                "shift" => KeyModifiers::SHIFT,
+                _ => return Err(invalid()),
+            };
+        }
+        let shift = modifiers.contains(KeyModifiers::SHIFT);
+
+        let mut chars = name.chars();
+        let code = match (chars.next(), chars.next()) {
+            (Some(c), None) if shift => KeyCode::Char(c.to_ascii_uppercase()),
+            (Some(c), None) => KeyCode::Char(c),
+            _ => match name.to_lowercase().as_str() {
+                "up" => KeyCode::Up,
+                "down" => KeyCode::Down,
+                "left" => KeyCode::Left,
+                "right" => KeyCode::Right,
+                "pageup" | "pgup" => KeyCode::PageUp,
+                "pagedown" | "pgdn" => KeyCode::PageDown,
+                "home" => KeyCode::Home,
+                "end" => KeyCode::End,
+                "enter" | "return" => KeyCode::Enter,
+                "esc" | "escape" => KeyCode::Esc,
+                "tab" if shift => KeyCode::BackTab,
+                "tab" => KeyCode::Tab,
+                "backtab" => KeyCode::BackTab,
+                "backspace" => KeyCode::Backspace,
+                "delete" | "del" => KeyCode::Delete,
+                "insert" | "ins" => KeyCode::Insert,
+                "space" => KeyCode::Char(' '),
+                f => match f.strip_prefix('f').and_then(|n| n.parse().ok()) {
+                    Some(n @ 1..=24) => KeyCode::F(n),
+                    _ => return Err(invalid()),
+                },
+            },
+        };
+        Ok(Self::new(code, modifiers))
+    }
+
+    /// How the key is shown in the footer and help, e.g. `↑` or `Ctrl+S`.
+    pub fn label(&self) -> String {
+        let mut label = String::new();
+        if self.modifiers.contains(KeyModifiers::CONTROL) {
+            label.push_str("Ctrl+");
+        }
+        if self.modifiers.contains(KeyModifiers::ALT) {
+            label.push_str("Alt+");
+        }
+        match self.code {
+            KeyCode::Char(' ') => label.push_str("Space"),
+            KeyCode::Char(c) if !label.is_empty() => label.push(c.to_ascii_uppercase()),
+            KeyCode::Char(c) => label.push(c),
+            KeyCode::Up => label.push('↑'),
+            KeyCode::Down => label.push('↓'),
+            KeyCode::Left => label.push('←'),
+            KeyCode::Right => label.push('→'),
+            KeyCode::PageUp => label.push_str("PgUp"),
+            KeyCode::PageDown => label.push_str("PgDn"),
+            KeyCode::BackTab => label.push_str("Shift+Tab"),
+            KeyCode::F(n) => label.push_str(&format!("F{}", n)),
+            code => label.push_str(&format!("{:?}", code)),
+        }
+        label
+    }
+}
+
+/// One action of a scope and the keys bound to it.
+#[derive(Debug, Clone)]
+pub struct Binding {
+    pub scope: Scope,
+    pub action: Action,
+    pub keys: Vec<KeyPress>,
+    pub description: &'static str,
+}
+
+/// The contents of `.polyglot/keymap.json`.
+#[derive(Debug, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct KeymapFile {
+    preset: Preset,
+    confirm_quit: bool,
+    /// Keys per action per scope, replacing the preset's keys for that
+    /// action. An empty list unbinds it.
+    bindings: HashMap<Scope, HashMap<Action, Keys>>,
+}
+
+impl Default for KeymapFile {
+    fn default() -> Self {
+        Self {
+            preset: Preset::Default,
+            confirm_quit: true,
+            bindings: HashMap::new(),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum Keys {
+    One(String),
+    Many(Vec<String>),
+}
+
+/// The key bindings in use.
+#[derive(Debug, Clone)]
+pub struct Keymap {
+    bindings: Vec<Binding>,
+    pub preset: Preset,
+    /// Ask before quitting.
+    pub confirm_quit: bool,
+    /// Whether the bindings come from the keymap file.
+    pub customized: bool,
+}
+
+impl Default for Keymap {
+    fn default() -> Self {
+        Self::preset(Preset::Default)
+    }
+}
+
+impl Keymap {
+    pub fn preset(preset: Preset) -> Self {
+        let mut bindings: Vec<Binding> = DEFAULTS
+            .iter()
+            .map(|&(scope, action, keys, description)| Binding {
+                scope,
+                action,
+                keys: keys
+                    .iter()
+                    .map(|key| KeyPress::parse(key).expect("default keys are valid"))
+                    .collect(),
+                description,
+            })
+            .collect();
+
+        if preset == Preset::Vim {
+            for i in 0..bindings.len() {
+                let scope = bindings[i].scope;
+                for &(action, key) in VIM_KEYS {
+                    let key = KeyPress::parse(key).expect("vim keys are valid");
+                    let taken = bindings
+                        .iter()
+                        .any(|b| b.scope == scope && b.keys.contains(&key));
+                    if bindings[i].action == action && !taken {
+                        bindings[i].keys.push(key);
+                    }
+                }
+            }
+        }
+
+        Self {
+            bindings,
+            preset,
+            confirm_quit: true,
+            customized: false,
+        }
+    }
+
+    /// Reads `.polyglot/keymap.json` of the project at `root_dir`. A missing
+    /// file gives the default keymap.
+    pub fn load(root_dir: &Path) -> Result<Self, String> {
+        let path = root_dir.join(DATA_DIR).join(KEYMAP_FILE);
+        match fs::read_to_string(&path) {
+            Ok(content) => Self::parse(&content)
+                .map_err(|e| format!("Invalid keymap in {}: {}", path.display(), e)),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(e) => Err(format!("Failed to read {}: {}", path.display(), e)),
+        }
+    }
+
+    fn parse(content: &str) -> Result<Self, String> {
+        let file: KeymapFile = serde_json::from_str(content).map_err(|e| e.to_string())?;
+        let mut keymap = Self::preset(file.preset);
+        keymap.confirm_quit = file.confirm_quit;
+        keymap.customized = true;
+
+        for (scope, actions) in file.bindings {
+            for (action, keys) in actions {
+                let keys = match keys {
+                    Keys::One(key) => vec![key],
+                    Keys::Many(keys) => keys,
+                };
+                let keys = keys
+                    .iter()
+                    .map(|key| KeyPress::parse(key))
+                    .collect::<Result<Vec<_>, _>>()?;
+                let binding = keymap
+                    .bindings
+                    .iter_mut()
+                    .find(|b| b.scope == scope && b.action == action)
+                    .ok_or_else(|| {
+                        format!("the {} has no action '{}'", scope.label(), action.name())
+                    })?;
+                binding.keys = keys;
+            }
+        }
+        Ok(keymap)
+    }
+
+    /// The action bound to a key in `scope`, falling back to the global
+    /// bindings for views.
+    pub fn action(&self, scope: Scope, code: KeyCode, modifiers: KeyModifiers) -> Option<Action> {
+        let key = KeyPress::new(code, modifiers);
+        let find = |scope: Scope| {
+            self.bindings
+                .iter()
+                .find(|b| b.scope == scope && b.keys.contains(&key))
+                .map(|b| b.action)
+        };
+        match scope {
+            Scope::Viewer | Scope::Global => find(scope),
+            _ => find(scope).or_else(|| find(Scope::Global)),
+        }
+    }
+
+    pub fn keys(&self, scope: Scope, action: Action) -> &[KeyPress] {
+        self.bindings
+            .iter()
+            .find(|b| b.scope == scope && b.action == action)
+            .map_or(&[], |b| &b.keys)
+    }
+
+    /// The first key of an action, for hints such as "Press 'x' to ...".
+    pub fn label(&self, scope: Scope, action: Action) -> String {
+        self.keys(scope, action)
+            .first()
+            .map_or_else(|| "unbound".to_string(), KeyPress::label)
+    }
+
+    /// The bindings of a scope, in help order.
+    pub fn bindings(&self, scope: Scope) -> impl Iterator<Item = &Binding> {
+        self.bindings.iter().filter(move |b| b.scope == scope)
+    }
+
+    /// Keys bound to more than one action of a scope, and view keys that
+    /// hide a global one.
+    pub fn conflicts(&self) -> Vec<String> {
+        let mut conflicts = Vec::new();
+        for (i, a) in self.bindings.iter().enumerate() {
+            for b in &self.bindings[i + 1..] {
+                let hides = a.scope == Scope::Global && b.scope != Scope::Viewer;
+                if a.scope != b.scope && !hides {
+                    continue;
+                }
+                for key in a.keys.iter().filter(|key| b.keys.contains(key)) {
+                    conflicts.push(format!(
+                        "{} is bound to both '{}' ({}) and '{}' ({})",
+                        key.label(),
+                        a.action.name(),
+                        a.scope.label(),
+                        b.action.name(),
+                        b.scope.label()
+                    ));
+                }
+            }
+        }
+        conflicts
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_keys() {
+        let key = |text| KeyPress::parse(text).unwrap();
+        assert_eq!(
+            key("x"),
+            KeyPress::new(KeyCode::Char('x'), KeyModifiers::NONE)
+        );
+        assert_eq!(key("shift+k"), key("K"));
+        assert_eq!(
+            key("shift+tab"),
+            KeyPress::new(KeyCode::BackTab, KeyModifiers::SHIFT)
+        );
+        assert_eq!(
+            key("Ctrl+S"),
+            KeyPress::new(KeyCode::Char('s'), KeyModifiers::CONTROL)
+        );
+        assert_eq!(key("ctrl++").label(), "Ctrl++");
+        assert_eq!(key("+").label(), "+");
+        assert_eq!(key("pgdn").label(), "PgDn");
+        assert_eq!(key("f5").label(), "F5");
+        assert_eq!(key("ctrl+s").label(), "Ctrl+S");
+        assert!(KeyPress::parse("hyper+x").is_err());
+        assert!(KeyPress::parse("f99").is_err());
+    }
+
+    #[test]
+    fn applies_presets_and_overrides() {
+        let keymap = Keymap::default();
+        assert!(keymap.conflicts().is_empty());
+        let none = KeyModifiers::NONE;
+        assert_eq!(
+            keymap.action(Scope::Results, KeyCode::Char('q'), none),
+            Some(Action::Quit)
+        );
+        assert_eq!(
+            keymap.action(Scope::Viewer, KeyCode::Char('q'), none),
+            Some(Action::Close)
+        );
+        assert_eq!(
+            keymap.action(Scope::Results, KeyCode::Char('j'), none),
+            None
+        );
+
+        let vim = Keymap::preset(Preset::Vim);
+        assert!(vim.conflicts().is_empty());
+        assert_eq!(
+            vim.action(Scope::Results, KeyCode::Char('j'), none),
+            Some(Action::Down)
+        );
+        // `l` stays the language filter in the Results Explorer.
+        assert_eq!(
+            vim.action(Scope::Results, KeyCode::Char('l'), none),
+            Some(Action::FilterLanguage)
+        );
+        assert_eq!(
+            vim.action(Scope::Runs, KeyCode::Char('l'), none),
+            Some(Action::Right)
+        );
+
+        let custom = Keymap::parse(
+            r#"{
+                "preset": "vim",
+                "confirm_quit": false,
+                "bindings": {
+                    "global": { "quit": "ctrl+q" },
+                    "results": { "replay": ["R", "ctrl+r"], "clear_history": [] }
+                }
+            }"#,
+        )
+        .unwrap();
+        assert!(!custom.confirm_quit);
+        assert_eq!(custom.action(Scope::Logs, KeyCode::Char('q'), none), None);
+        assert_eq!(
+            custom.action(Scope::Results, KeyCode::Char('R'), KeyModifiers::SHIFT),
+            Some(Action::Replay)
+        );
+        assert_eq!(
+            custom.action(Scope::Results, KeyCode::Char('C'), none),
+            None
+        );
+        assert_eq!(
+            custom.label(Scope::Results, Action::ClearHistory),
+            "unbound"
+        );
+
+        let clash = Keymap::parse(r#"{"bindings": {"results": {"replay": "d"}}}"#).unwrap();
+        assert_eq!(clash.conflicts().len(), 1);
+        assert!(Keymap::parse(r#"{"bindings": {"stats": {"replay": "r"}}}"#).is_err());
+        assert!(Keymap::parse(r#"{"bindings": {"results": {"fly": "r"}}}"#).is_err());
+        assert!(Keymap::parse(r#"{"preset": "emacs"}"#).is_err());
+        assert_eq!(Action::NextView.name(), "next_view");
+    }
+}
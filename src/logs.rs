@@ -0,0 +1,237 @@
+use crate::config::DATA_DIR;
+use crate::models::{LogEntry, LogLevel};
+use crate::time;
+use std::{
+    collections::VecDeque,
+    fs::{self, OpenOptions},
+    io::{self, Write},
+    path::{Path, PathBuf},
+};
+
+const LOG_FILE: &str = "polyglot.log";
+
+/// The newest log entries, oldest first, up to a fixed number.
+pub struct LogBuffer {
+    entries: VecDeque<LogEntry>,
+    capacity: usize,
+}
+
+impl LogBuffer {
+    /// A buffer keeping at most `capacity` entries, and at least one.
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            entries: VecDeque::new(),
+            capacity: capacity.max(1),
+        }
+    }
+
+    /// Adds an entry, returning the oldest one if it had to be dropped.
+    pub fn push(&mut self, entry: LogEntry) -> Option<LogEntry> {
+        self.entries.push_back(entry);
+        if self.entries.len() > self.capacity {
+            self.entries.pop_front()
+        } else {
+            None
+        }
+    }
+
+    /// Changes the capacity, dropping the oldest entries that no longer fit.
+    pub fn set_capacity(&mut self, capacity: usize) {
+        self.capacity = capacity.max(1);
+        let excess = self.entries.len().saturating_sub(self.capacity);
+        self.entries.drain(..excess);
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn last(&self) -> Option<&LogEntry> {
+        self.entries.back()
+    }
+
+    pub fn iter(&self) -> impl DoubleEndedIterator<Item = &LogEntry> {
+        self.entries.iter()
+    }
+
+    pub fn clear(&mut self) {
+        self.entries.clear();
+    }
+}
+
+/// Which entries the Logs view shows.
+#[derive(Debug, Clone, Default)]
+pub struct LogFilter {
+    pub hidden: Vec<LogLevel>,
+    /// Text the message must contain, ignoring case.
+    pub search: String,
+}
+
+impl LogFilter {
+    pub fn shows(&self, level: LogLevel) -> bool {
+        !self.hidden.contains(&level)
+    }
+
+    /// Shows `level` if it was hidden, and hides it otherwise.
+    pub fn toggle(&mut self, level: LogLevel) {
+        if self.shows(level) {
+            self.hidden.push(level);
+        } else {
+            self.hidden.retain(|&hidden| hidden != level);
+        }
+    }
+
+    pub fn matches(&self, entry: &LogEntry) -> bool {
+        self.shows(entry.level)
+            && (self.search.is_empty()
+                || entry
+                    .message
+                    .to_lowercase()
+                    .contains(&self.search.to_lowercase()))
+    }
+
+    pub fn is_active(&self) -> bool {
+        !self.hidden.is_empty() || !self.search.is_empty()
+    }
+}
+
+/// The log written as text in `.polyglot/polyglot.log`. When the file would
+/// grow past its maximum size, if it has one, it is renamed to
+/// `polyglot.log.1`, shifting older files up to the number kept, and a new one
+/// is started.
+pub struct LogFile {
+    path: PathBuf,
+    max_bytes: u64,
+    keep: usize,
+}
+
+impl LogFile {
+    pub fn new(root_dir: &Path, max_bytes: u64, keep: usize) -> Self {
+        Self {
+            path: root_dir.join(DATA_DIR).join(LOG_FILE),
+            max_bytes,
+            keep,
+        }
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub fn append(&self, entry: &LogEntry) -> Result<(), String> {
+        let line = format!("{}\n", line(entry));
+        if let Some(dir) = self.path.parent() {
+            fs::create_dir_all(dir)
+                .map_err(|e| format!("Failed to create {}: {}", dir.display(), e))?;
+        }
+        let size = fs::metadata(&self.path).map(|m| m.len()).unwrap_or(0);
+        if self.max_bytes > 0 && size > 0 && size + line.len() as u64 > self.max_bytes {
+            self.rotate()?;
+        }
+        OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)
+            .and_then(|mut file| file.write_all(line.as_bytes()))
+            .map_err(|e| format!("Failed to write {}: {}", self.path.display(), e))
+    }
+
+    /// The path of the `n`th rotated file, `polyglot.log.n`.
+    pub fn rotated(&self, n: usize) -> PathBuf {
+        let mut path = self.path.clone().into_os_string();
+        path.push(format!(".{}", n));
+        path.into()
+    }
+
+    fn rotate(&self) -> Result<(), String> {
+        let rename = |from: &Path, to: &Path| match fs::rename(from, to) {
+            Err(e) if e.kind() != io::ErrorKind::NotFound => {
+                Err(format!("Failed to rotate {}: {}", from.display(), e))
+            }
+            _ => Ok(()),
+        };
+        if self.keep == 0 {
+            return fs::remove_file(&self.path)
+                .map_err(|e| format!("Failed to rotate {}: {}", self.path.display(), e));
+        }
+        for n in (1..self.keep).rev() {
+            rename(&self.rotated(n), &self.rotated(n + 1))?;
+        }
+        rename(&self.path, &self.rotated(1))
+    }
+}
+
+/// An entry as written to the log file, e.g.
+/// `2024-01-01T12:00:00.000Z warning Step failed`.
+pub fn line(entry: &LogEntry) -> String {
+    format!(
+        "{} {:<7} {}",
+        time::rfc3339(&entry.timestamp),
+        entry.level.label(),
+        entry.message
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(level: LogLevel, message: &str) -> LogEntry {
+        LogEntry {
+            timestamp: time::parse("2024-01-01T12:00:00Z").unwrap(),
+            level,
+            message: message.to_string(),
+        }
+    }
+
+    #[test]
+    fn keeps_the_newest_entries_and_filters_them() {
+        let mut buffer = LogBuffer::new(3);
+        for n in 0..3 {
+            assert!(buffer.push(entry(LogLevel::Info, &n.to_string())).is_none());
+        }
+        let dropped = buffer.push(entry(LogLevel::Error, "Load FAILED"));
+        assert_eq!(dropped.unwrap().message, "0");
+        buffer.set_capacity(2);
+        let messages: Vec<&str> = buffer.iter().map(|e| e.message.as_str()).collect();
+        assert_eq!(messages, ["2", "Load FAILED"]);
+
+        let mut filter = LogFilter::default();
+        filter.toggle(LogLevel::Info);
+        assert!(filter.is_active());
+        assert_eq!(buffer.iter().filter(|e| filter.matches(e)).count(), 1);
+        filter.toggle(LogLevel::Info);
+        filter.search = "failed".to_string();
+        assert_eq!(buffer.iter().filter(|e| filter.matches(e)).count(), 1);
+        filter.search.clear();
+        assert!(!filter.is_active());
+    }
+
+    #[test]
+    fn rotates_the_log_file() {
+        let dir = std::env::temp_dir().join(format!("polyglot-logs-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        let message = "x".repeat(20);
+        let size = line(&entry(LogLevel::Warning, &message)).len() as u64 + 1;
+        let file = LogFile::new(&dir, size * 2, 2);
+
+        for _ in 0..7 {
+            file.append(&entry(LogLevel::Warning, &message)).unwrap();
+        }
+        let lines = |path: &Path| fs::read_to_string(path).unwrap().lines().count();
+        assert_eq!(lines(file.path()), 1);
+        assert_eq!(lines(&file.rotated(1)), 2);
+        assert_eq!(lines(&file.rotated(2)), 2);
+        assert!(!file.rotated(3).exists());
+        assert!(
+            fs::read_to_string(file.path())
+                .unwrap()
+                .starts_with("2024-01-01T12:00:00.000Z warning xxx")
+        );
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
@@ -1,27 +1,58 @@
 use crossterm::{
-    event::{self, Event, KeyCode, KeyEventKind, KeyModifiers},
+    event::{
+        self, DisableBracketedPaste, DisableMouseCapture, EnableBracketedPaste, EnableMouseCapture,
+        Event, KeyCode, KeyEventKind, KeyModifiers, MouseButton, MouseEvent, MouseEventKind,
+    },
     execute,
     terminal::{EnterAlternateScreen, LeaveAlternateScreen, disable_raw_mode, enable_raw_mode},
 };
-use metacall::{initialize, load, metacall};
+use metacall::{initialize, load};
 use ratatui::{
     Frame, Terminal,
     backend::CrosstermBackend,
     layout::{Alignment, Constraint, Direction, Layout, Rect},
     style::{Color, Modifier, Style},
+    symbols,
     text::{Line, Span, Text},
-    widgets::{Block, Borders, Cell, List, ListItem, Padding, Paragraph, Row, Table, Tabs, Wrap},
+    widgets::{
+        Axis, Block, Borders, Cell, Chart, Clear, Dataset, Gauge, GraphType, List, ListItem,
+        ListState, Padding, Paragraph, Row, Sparkline, Table, TableState, Tabs, Wrap,
+    },
 };
 use serde::{Deserialize, Serialize};
+use serde_json::Value;
 use std::{
-    collections::HashMap,
+    cell::RefCell,
+    collections::{BTreeMap, HashMap},
     fs, io,
+    ops::Range,
     path::{Path, PathBuf},
     time::{Duration, Instant},
 };
+use tui_textarea::{Input, Key};
 use walkdir::WalkDir;
 
+mod assertions;
+mod clipboard;
+mod config;
+mod diff;
+mod editor;
+mod export;
+mod headless;
+mod history;
+mod import;
+mod keymap;
+mod logs;
 mod models;
+mod mouse;
+mod palette;
+mod params;
+mod stats;
+mod theme;
+mod time;
+mod values;
+mod viewer;
+use keymap::{Action, Scope};
 use models::*;
 
 struct App {
@@ -31,13 +62,71 @@ struct App {
     results: Vec<ExecutionResult>,
     pipeline: Vec<PipelineStep>,
     selected_pipeline_step: usize,
+    params: Vec<PipelineParam>,
+    param_inputs: Vec<String>,
+    selected_param: usize,
+    runs: Vec<PipelineRun>,
+    selected_run: usize,
+    selected_run_step: usize,
+    compare_run: Option<u64>,
     current_view: View,
-    logs: Vec<LogEntry>,
+    logs: logs::LogBuffer,
+    /// Where the log is written; `None` for headless runs and when turned off.
+    log_file: Option<logs::LogFile>,
+    log_filter: logs::LogFilter,
+    /// Index in the filtered log of the selected entry; `None` follows the
+    /// newest one.
+    selected_log: Option<usize>,
     input_mode: InputMode,
     input_buffer: String,
     function_input: FunctionInput,
     selected_result: usize,
+    selected_stat: usize,
     show_help: bool,
+    step_editor: StepEditor,
+    export_format: ExportFormat,
+    export_relative_paths: bool,
+    export_output_dir: PathBuf,
+    export_scroll: u16,
+    pending_export: Vec<export::ExportFile>,
+    pending_export_label: &'static str,
+    /// Whether the pending export includes the pipeline itself.
+    pending_export_pipeline: bool,
+    results_filter: ResultsFilter,
+    results_sort: ResultsSort,
+    results_export_format: ResultsFormat,
+    results_export_all: bool,
+    /// Steps whose script or function was not found, with the reason.
+    /// Checked again whenever the pipeline or the scripts change.
+    import_issues: Vec<(String, String)>,
+    /// Whether steps were changed since the pipeline was last imported or
+    /// exported.
+    pipeline_modified: bool,
+    /// A pipeline read for import, waiting for confirmation to replace
+    /// modified steps.
+    pending_import: Option<(PathBuf, PipelineFile)>,
+    config: config::Config,
+    /// Where results are persisted; `None` for headless runs and when the
+    /// retention is 0.
+    history: Option<history::History>,
+    baselines: history::Baselines,
+    /// Index in `results` of the result marked to diff others against.
+    compare_result: Option<usize>,
+    results_diff: bool,
+    /// The time of the result being replayed, while it is.
+    replaying: Option<time::Timestamp>,
+    /// The expectations of the pipeline step being executed, checked against
+    /// its result before it is recorded.
+    expecting: Vec<Expectation>,
+    output_viewer: Option<viewer::OutputViewer>,
+    /// The Function Tester's argument editor, while it is open.
+    arg_editor: Option<editor::ArgEditor>,
+    keymap: keymap::Keymap,
+    /// The tabs and lists of the last frame, for mouse clicks.
+    regions: RefCell<mouse::Regions>,
+    theme: theme::Theme,
+    /// The command palette, while it is open.
+    palette: Option<palette::Palette>,
 }
 
 impl App {
@@ -49,17 +138,66 @@ impl App {
             results: Vec::new(),
             pipeline: Vec::new(),
             selected_pipeline_step: 0,
+            params: Vec::new(),
+            param_inputs: Vec::new(),
+            selected_param: 0,
+            runs: Vec::new(),
+            selected_run: 0,
+            selected_run_step: 0,
+            compare_run: None,
             current_view: View::ScriptBrowser,
-            logs: Vec::new(),
+            logs: logs::LogBuffer::new(config::LogsConfig::default().buffer),
+            log_file: None,
+            log_filter: logs::LogFilter::default(),
+            selected_log: None,
             input_mode: InputMode::Normal,
             input_buffer: String::new(),
             function_input: FunctionInput {
                 selected_function: 0,
                 args: Vec::new(),
-                current_arg: String::new(),
             },
             selected_result: 0,
+            selected_stat: 0,
             show_help: false,
+            step_editor: StepEditor {
+                position: 0,
+                field: StepField::Id,
+                id: String::new(),
+                script: 0,
+                function: 0,
+                args: String::new(),
+                map_over: String::new(),
+                expect: String::new(),
+                description: String::new(),
+                missing_function: None,
+            },
+            export_format: ExportFormat::Rust,
+            export_relative_paths: false,
+            export_output_dir: std::env::current_dir().unwrap_or_default(),
+            export_scroll: 0,
+            pending_export: Vec::new(),
+            pending_export_label: "",
+            pending_export_pipeline: false,
+            results_filter: ResultsFilter::default(),
+            results_sort: ResultsSort::Newest,
+            results_export_format: ResultsFormat::JsonLines,
+            results_export_all: false,
+            import_issues: Vec::new(),
+            pipeline_modified: false,
+            pending_import: None,
+            config: config::Config::default(),
+            history: None,
+            baselines: history::Baselines::new(&root_dir),
+            compare_result: None,
+            results_diff: false,
+            replaying: None,
+            expecting: Vec::new(),
+            output_viewer: None,
+            arg_editor: None,
+            keymap: keymap::Keymap::default(),
+            regions: RefCell::default(),
+            theme: theme::Theme::default(),
+            palette: None,
         };
 
         app.add_log(LogLevel::Info, "MetaCall Playground started".to_string());
@@ -71,28 +209,408 @@ impl App {
         app
     }
 
-    fn timestamp() -> String {
-        let now = std::time::SystemTime::now()
-            .duration_since(std::time::UNIX_EPOCH)
-            .unwrap();
-        let secs = now.as_secs() % 86400;
-        format!(
-            "{:02}:{:02}:{:02}",
-            secs / 3600,
-            (secs % 3600) / 60,
-            secs % 60
-        )
+    /// Reads the project settings and loads the results kept from earlier
+    /// sessions.
+    fn load_config(&mut self) {
+        match config::Config::load(&self.root_dir) {
+            Ok(config) => self.config = config,
+            Err(e) => self.add_log(LogLevel::Warning, format!("{}; using defaults", e)),
+        }
+        self.open_log_file();
+        match theme::Theme::from_config(&self.config.theme) {
+            Ok(theme) => self.theme = theme,
+            Err(e) => {
+                self.add_log(LogLevel::Warning, format!("{}; using the dark theme", e));
+                self.theme.ascii = self.config.theme.ascii;
+            }
+        }
+        match keymap::Keymap::load(&self.root_dir) {
+            Ok(keymap) => self.keymap = keymap,
+            Err(e) => self.add_log(LogLevel::Warning, format!("{}; using the default keys", e)),
+        }
+        for conflict in self.keymap.conflicts() {
+            self.add_log(LogLevel::Warning, format!("Key conflict: {}", conflict));
+        }
+        if let Err(e) = self.baselines.load() {
+            self.add_log(LogLevel::Error, e);
+        }
+
+        let retention = self.config.history.retention;
+        if retention == 0 {
+            return;
+        }
+        let history = history::History::new(&self.root_dir, retention);
+        match history.load() {
+            Ok(loaded) => {
+                if loaded.skipped > 0 {
+                    self.add_log(
+                        LogLevel::Warning,
+                        format!("Dropped {} unreadable history entries", loaded.skipped),
+                    );
+                }
+                if !loaded.results.is_empty() {
+                    self.add_log(
+                        LogLevel::Info,
+                        format!("Loaded {} results from history", loaded.results.len()),
+                    );
+                }
+                self.results = loaded.results;
+                self.history = Some(history);
+            }
+            Err(e) => self.add_log(LogLevel::Error, format!("History disabled: {}", e)),
+        }
+    }
+
+    fn clear_history(&mut self) {
+        if let Some(history) = &self.history
+            && let Err(e) = history.clear()
+        {
+            self.add_log(LogLevel::Error, e);
+            return;
+        }
+        self.results.clear();
+        self.selected_result = 0;
+        self.compare_result = None;
+        self.add_log(LogLevel::Info, "Cleared results history".to_string());
+    }
+
+    /// What a call's result is checked against: its baseline, or else the
+    /// latest of the first `before` results with the same args.
+    fn reference_for(
+        &self,
+        function: &str,
+        args: &[String],
+        before: usize,
+    ) -> Option<(&'static str, &ExecutionResult)> {
+        if let Some(baseline) = self.baselines.get(function, args) {
+            return Some(("baseline", baseline));
+        }
+        self.results[..before]
+            .iter()
+            .rev()
+            .find(|r| r.function == function && r.args == args)
+            .map(|r| ("previous result", r))
+    }
+
+    /// What the selected result is diffed against: the marked result, the
+    /// result it replays, or its baseline or previous result.
+    fn diff_reference(&self, selected: &ExecutionResult) -> Option<(String, &ExecutionResult)> {
+        let position = self
+            .results
+            .iter()
+            .position(|r| std::ptr::eq(r, selected))?;
+        if let Some(marked) = self.compare_result
+            && marked != position
+            && let Some(result) = self.results.get(marked)
+        {
+            let time = self.config.display.time(&result.timestamp);
+            return Some((format!("marked result [{}]", time), result));
+        }
+        if let Some(original) = self.replayed_result(selected) {
+            let time = self.config.display.time(&original.timestamp);
+            return Some((format!("original [{}]", time), original));
+        }
+        self.reference_for(&selected.function, &selected.args, position)
+            .map(|(label, result)| (label.to_string(), result))
+    }
+
+    /// Index in `results` of the result selected in the Results Explorer.
+    fn selected_result_index(&self) -> Option<usize> {
+        let selected = *self.filtered_results().get(self.selected_result)?;
+        self.results.iter().position(|r| std::ptr::eq(r, selected))
+    }
+
+    /// The index of the scanned script a result came from, loading it if
+    /// needed.
+    fn result_script(&mut self, result: &ExecutionResult) -> Result<usize, String> {
+        let index = self
+            .scripts
+            .iter()
+            .position(|s| s.name == result.script())
+            .ok_or_else(|| format!("Script {} not found", result.script()))?;
+        if !self.scripts[index].loaded {
+            self.load_script(index)?;
+        }
+        if !self.scripts[index]
+            .functions
+            .iter()
+            .any(|f| f == result.function_name())
+        {
+            return Err(format!(
+                "{} does not define {}",
+                result.script(),
+                result.function_name()
+            ));
+        }
+        Ok(index)
+    }
+
+    /// Calls the function of the selected result again with the same args.
+    /// The new result links back to the one it replays and is selected.
+    fn replay_result(&mut self) -> Result<(), String> {
+        let index = self.selected_result_index().ok_or("No result selected")?;
+        let original = self.results[index].clone();
+        let script = self.result_script(&original)?;
+        let script_name = self.scripts[script].name.clone();
+        let mut args: Vec<Value> = original.args.iter().map(|a| values::parse_arg(a)).collect();
+
+        self.add_log(
+            LogLevel::Info,
+            format!(
+                "↻ Replaying {}({})",
+                original.function,
+                original.args.join(", ")
+            ),
+        );
+        self.replaying = Some(original.timestamp);
+        let replay = if original.mapped {
+            let items = match args.first() {
+                Some(Value::Array(items)) => Ok(items.clone()),
+                _ => Err("The mapped-over argument is not an array".to_string()),
+            };
+            items.map(|items| {
+                let extra = args.split_off(1);
+                self.map_function(&script_name, original.function_name(), items, extra, None)
+            })
+        } else {
+            Ok(self.call_function(&script_name, original.function_name(), args, None))
+        };
+        self.replaying = None;
+        let replay = replay?;
+
+        self.selected_result = self
+            .filtered_results()
+            .iter()
+            .position(|r| r.timestamp == replay.timestamp && r.function == replay.function)
+            .unwrap_or(self.selected_result);
+        Ok(())
+    }
+
+    /// The result that `result` replays, while it is still in the results.
+    fn replayed_result(&self, result: &ExecutionResult) -> Option<&ExecutionResult> {
+        let original = result.replay_of?;
+        self.results
+            .iter()
+            .find(|r| r.timestamp == original && r.function == result.function)
+    }
+
+    /// Opens the argument editor on the current args, offering the argument
+    /// lists used before with the selected function for recall.
+    fn open_arg_editor(&mut self) {
+        let function = self.scripts.get(self.selected_script).and_then(|script| {
+            script
+                .functions
+                .get(self.function_input.selected_function)
+                .map(|f| format!("{}::{}", script.name, f))
+        });
+        let mut recall: Vec<Vec<String>> = Vec::new();
+        for result in self.results.iter().rev() {
+            if Some(&result.function) == function.as_ref()
+                && !result.mapped
+                && !recall.contains(&result.args)
+            {
+                recall.push(result.args.clone());
+            }
+        }
+        self.arg_editor = Some(editor::ArgEditor::new(
+            &self.function_input.args,
+            recall,
+            &self.theme,
+        ));
+        self.input_mode = InputMode::EditingArgs;
+    }
+
+    fn close_arg_editor(&mut self, apply: bool) {
+        if let Some(editor) = self.arg_editor.take()
+            && apply
+        {
+            self.function_input.args = editor.args();
+        }
+        self.input_mode = InputMode::Normal;
+    }
+
+    /// Opens the function and args of the selected result in the Function
+    /// Tester.
+    fn send_to_tester(&mut self) -> Result<(), String> {
+        let index = self.selected_result_index().ok_or("No result selected")?;
+        let result = self.results[index].clone();
+        if result.mapped {
+            return Err("Results of Map steps can only be replayed".to_string());
+        }
+        let script = self.result_script(&result)?;
+        self.selected_script = script;
+        self.function_input.selected_function = self.scripts[script]
+            .functions
+            .iter()
+            .position(|f| f == result.function_name())
+            .unwrap_or(0);
+        self.function_input.args = result.args.clone();
+        self.current_view = View::FunctionTester;
+        self.add_log(
+            LogLevel::Info,
+            format!("Loaded {} into the Function Tester", result.function),
+        );
+        Ok(())
+    }
+
+    /// Saves the selected result as the baseline of its call, or removes
+    /// the baseline when the selected result is the baseline.
+    fn toggle_baseline(&mut self) {
+        let Some(index) = self.selected_result_index() else {
+            return;
+        };
+        let result = self.results[index].clone();
+        let call = format!("{}({})", result.function, result.args.join(", "));
+        let is_baseline = self
+            .baselines
+            .get(&result.function, &result.args)
+            .is_some_and(|b| !diff::outputs_differ(b, &result));
+        let outcome = if is_baseline {
+            self.baselines
+                .remove(&result.function, &result.args)
+                .map(|_| format!("Removed the baseline of {}", call))
+        } else {
+            self.baselines
+                .set(&result)
+                .map(|_| format!("Saved the baseline of {}", call))
+        };
+        match outcome {
+            Ok(message) => self.add_log(LogLevel::Info, message),
+            Err(e) => self.add_log(LogLevel::Error, e),
+        }
+    }
+
+    /// Applies the log settings and starts writing the log file with what
+    /// was logged so far.
+    fn open_log_file(&mut self) {
+        let settings = self.config.logs.clone();
+        self.logs.set_capacity(settings.buffer);
+        if !settings.file {
+            return;
+        }
+        let file = logs::LogFile::new(
+            &self.root_dir,
+            settings.max_file_kb * 1024,
+            settings.max_files,
+        );
+        let written = self.logs.iter().try_for_each(|entry| file.append(entry));
+        match written {
+            Ok(()) => self.log_file = Some(file),
+            Err(e) => self.add_log(LogLevel::Error, format!("Log file disabled: {}", e)),
+        }
     }
 
     fn add_log(&mut self, level: LogLevel, message: String) {
-        self.logs.push(LogEntry {
-            timestamp: Self::timestamp(),
+        let entry = LogEntry {
+            timestamp: time::now(),
             level,
             message,
-        });
-        if self.logs.len() > 100 {
-            self.logs.remove(0);
+        };
+        let write = self.log_file.as_ref().map(|file| file.append(&entry));
+
+        if let Some(dropped) = self.logs.push(entry)
+            && self.log_filter.matches(&dropped)
+        {
+            self.selected_log = self.selected_log.map(|i| i.saturating_sub(1));
+        }
+
+        if let Some(Err(e)) = write {
+            self.log_file = None;
+            self.add_log(LogLevel::Error, format!("Log file disabled: {}", e));
+        }
+    }
+
+    /// Opens the command palette over the functions of the loaded scripts,
+    /// the views, and the actions of every view.
+    fn open_palette(&mut self) {
+        let mut entries = Vec::new();
+        for (script, s) in self.scripts.iter().enumerate().filter(|(_, s)| s.loaded) {
+            for (function, name) in s.functions.iter().enumerate() {
+                entries.push(palette::Entry {
+                    label: format!("{}::{}", s.name, name),
+                    detail: s.language.clone(),
+                    keywords: String::new(),
+                    command: palette::Command::Function { script, function },
+                });
+            }
+        }
+        for view in View::ALL {
+            entries.push(palette::Entry {
+                label: format!("Go to {}", Scope::of(&view).label()),
+                detail: String::new(),
+                keywords: String::new(),
+                command: palette::Command::View(view),
+            });
+        }
+        let scopes = std::iter::once(Scope::Global).chain(View::ALL.iter().map(Scope::of));
+        for scope in scopes {
+            for binding in self.keymap.bindings(scope) {
+                if matches!(
+                    binding.action,
+                    Action::Up
+                        | Action::Down
+                        | Action::Left
+                        | Action::Right
+                        | Action::PageUp
+                        | Action::PageDown
+                        | Action::Top
+                        | Action::Bottom
+                        | Action::NextView
+                        | Action::PrevView
+                        | Action::CommandPalette
+                ) {
+                    continue;
+                }
+                entries.push(palette::Entry {
+                    label: format!("{}: {}", scope.label(), binding.description),
+                    detail: self.key_label(scope, binding.action),
+                    keywords: binding.action.name().replace('_', " "),
+                    command: palette::Command::Action(scope, binding.action),
+                });
+            }
+        }
+        self.palette = Some(palette::Palette::new(entries));
+        self.input_mode = InputMode::CommandPalette;
+    }
+
+    /// Selects a function in the Function Tester with the args it was last
+    /// called with, so that it is ready to execute.
+    fn open_function(&mut self, script: usize, function: usize) {
+        let name = format!(
+            "{}::{}",
+            self.scripts[script].name, self.scripts[script].functions[function]
+        );
+        if self.selected_script != script || self.function_input.selected_function != function {
+            self.function_input.args = self
+                .results
+                .iter()
+                .rev()
+                .find(|r| r.function == name && !r.mapped)
+                .map(|r| r.args.clone())
+                .unwrap_or_default();
         }
+        self.selected_script = script;
+        self.function_input.selected_function = function;
+        self.current_view = View::FunctionTester;
+        self.add_log(
+            LogLevel::Info,
+            format!("Opened {} in the Function Tester", name),
+        );
+    }
+
+    /// The keys bound to an action, for hints.
+    fn key_label(&self, scope: Scope, action: Action) -> String {
+        self.theme
+            .icons(&self.keymap.label(scope, action))
+            .into_owned()
+    }
+
+    /// The log entries shown in the Logs view, oldest first.
+    fn filtered_logs(&self) -> Vec<&LogEntry> {
+        self.logs
+            .iter()
+            .filter(|entry| self.log_filter.matches(entry))
+            .collect()
     }
 
     fn scan_scripts(&mut self) {
@@ -105,38 +623,30 @@ impl App {
             .filter_map(|e| e.ok())
         {
             let path = entry.path();
-            if let Some(ext) = path.extension() {
-                let ext = ext.to_string_lossy().to_lowercase();
-                let (lang, runtime) = match ext.as_str() {
-                    "py" => (Some("Python"), "py"),
-                    "js" => (Some("JavaScript"), "node"),
-                    "rb" => (Some("Ruby"), "rb"),
-                    "ts" => (Some("TypeScript"), "ts"),
-                    _ => (None, ""),
-                };
-
-                if let Some(language) = lang {
-                    let name = path
-                        .file_name()
-                        .unwrap_or_default()
-                        .to_string_lossy()
-                        .to_string();
-
-                    self.scripts.push(Script {
-                        path: path.to_path_buf(),
-                        name: name.clone(),
-                        language: language.to_string(),
-                        runtime: runtime.to_string(),
-                        functions: Vec::new(),
-                        loaded: false,
-                        error: None,
-                    });
-                    found += 1;
-                }
+            if let Some(ext) = path.extension()
+                && let Some((language, runtime)) = script_language(&ext.to_string_lossy())
+            {
+                let name = path
+                    .file_name()
+                    .unwrap_or_default()
+                    .to_string_lossy()
+                    .to_string();
+
+                self.scripts.push(Script {
+                    path: path.to_path_buf(),
+                    name: name.clone(),
+                    language: language.to_string(),
+                    runtime: runtime.to_string(),
+                    functions: Vec::new(),
+                    loaded: false,
+                    error: None,
+                });
+                found += 1;
             }
         }
 
         self.add_log(LogLevel::Success, format!("Found {} scripts", found));
+        self.refresh_import_issues();
     }
 
     fn load_script(&mut self, index: usize) -> Result<(), String> {
@@ -251,7 +761,10 @@ impl App {
 
         let script = &self.scripts[self.selected_script];
         if !script.loaded {
-            return Err("Script not loaded. Press 'l' to load first".into());
+            return Err(format!(
+                "Script not loaded. Press '{}' in the Script Browser to load it first",
+                self.key_label(Scope::Scripts, Action::LoadScript)
+            ));
         }
 
         if script.functions.is_empty() {
@@ -260,90 +773,148 @@ impl App {
 
         let func_name = script.functions[self.function_input.selected_function].clone();
         let script_name = script.name.clone();
-        let args = self.function_input.args.clone();
+        let args = self
+            .function_input
+            .args
+            .iter()
+            .map(|a| values::parse_arg(a))
+            .collect();
+
+        let result = self.call_function(&script_name, &func_name, args, None);
+        if result.success {
+            Ok(())
+        } else {
+            Err(result.output)
+        }
+    }
 
+    /// Calls `func_name`, records the outcome in `results` and returns it.
+    fn call_function(
+        &mut self,
+        script_name: &str,
+        func_name: &str,
+        args: Vec<Value>,
+        run_id: Option<u64>,
+    ) -> ExecutionResult {
+        let shown: Vec<String> = args.iter().map(values::display_arg).collect();
         self.add_log(
             LogLevel::Info,
-            format!("Executing {}({:?})", func_name, args),
+            format!("Executing {}({:?})", func_name, shown),
         );
 
         let start = Instant::now();
+        let result = values::call(func_name, &args);
+        let duration = start.elapsed().as_millis() as u64;
 
-        // Fixed: Use single generic parameter for metacall
-        let result: Result<String, String> = if args.is_empty() {
-            metacall::<String>(&func_name, Vec::<i32>::new())
-                .map_err(|e| format!("{:?}", e))
-                .or_else(|_| {
-                    metacall::<i64>(&func_name, Vec::<i32>::new())
-                        .map(|v| v.to_string())
-                        .map_err(|e| format!("{:?}", e))
-                })
-        } else if args.len() == 1 {
-            let arg = &args[0];
-
-            if let Ok(num) = arg.parse::<i64>() {
-                metacall::<String>(&func_name, vec![num])
-                    .map_err(|e| format!("{:?}", e))
-                    .or_else(|_| {
-                        metacall::<i64>(&func_name, vec![num])
-                            .map(|v| v.to_string())
-                            .map_err(|e| format!("{:?}", e))
-                    })
-            } else {
-                metacall::<String>(&func_name, vec![arg.clone()])
-                    .map_err(|e| format!("{:?}", e))
-                    .or_else(|_| {
-                        metacall::<i64>(&func_name, vec![arg.clone()])
-                            .map(|v| v.to_string())
-                            .map_err(|e| format!("{:?}", e))
-                    })
-            }
-        } else {
-            let nums: Result<Vec<i64>, _> = args.iter().map(|s| s.parse::<i64>()).collect();
-
-            if let Ok(nums) = nums {
-                metacall::<String>(&func_name, nums.clone())
-                    .map_err(|e| format!("{:?}", e))
-                    .or_else(|_| {
-                        metacall::<i64>(&func_name, nums)
-                            .map(|v| v.to_string())
-                            .map_err(|e| format!("{:?}", e))
-                    })
-            } else {
-                metacall::<String>(&func_name, args.clone()).map_err(|e| format!("{:?}", e))
-            }
-        };
+        let function = format!("{}::{}", script_name, func_name);
+        self.record_result(function, shown, result, duration, run_id, false)
+    }
+
+    /// Calls `func_name` once per item, prepending the item to `extra`.
+    /// Recorded as a single result holding the array of outputs.
+    fn map_function(
+        &mut self,
+        script_name: &str,
+        func_name: &str,
+        items: Vec<Value>,
+        extra: Vec<Value>,
+        run_id: Option<u64>,
+    ) -> ExecutionResult {
+        self.add_log(
+            LogLevel::Info,
+            format!("Mapping {} over {} items", func_name, items.len()),
+        );
 
+        let start = Instant::now();
+        let result = map_items(&items, &extra, |args| values::call(func_name, args));
         let duration = start.elapsed().as_millis() as u64;
 
-        match result {
-            Ok(output) => {
-                self.results.push(ExecutionResult {
-                    function: format!("{}::{}", script_name, func_name),
-                    args: self.function_input.args.clone(),
-                    output: output.clone(),
-                    duration_ms: duration,
-                    success: true,
-                    timestamp: Self::timestamp(),
-                });
+        let mut args = vec![values::display_arg(&Value::Array(items))];
+        args.extend(extra.iter().map(values::display_arg));
+        let function = format!("{}::{}", script_name, func_name);
+        self.record_result(function, args, result, duration, run_id, true)
+    }
 
+    fn record_result(
+        &mut self,
+        function: String,
+        args: Vec<String>,
+        result: Result<Value, String>,
+        duration: u64,
+        run_id: Option<u64>,
+        mapped: bool,
+    ) -> ExecutionResult {
+        let (output, value, success) = match result {
+            Ok(value) => {
+                let output = values::display(&value);
                 self.add_log(LogLevel::Success, format!("✓ {}ms → {}", duration, output));
-                Ok(())
+                (output, Some(value), true)
             }
             Err(e) => {
-                self.results.push(ExecutionResult {
-                    function: format!("{}::{}", script_name, func_name),
-                    args: self.function_input.args.clone(),
-                    output: e.clone(),
-                    duration_ms: duration,
-                    success: false,
-                    timestamp: Self::timestamp(),
-                });
-
                 self.add_log(LogLevel::Error, format!("✗ Error: {}", e));
-                Err(e)
+                (e, None, false)
+            }
+        };
+
+        let mut result = ExecutionResult {
+            function,
+            args,
+            output,
+            value,
+            duration_ms: duration,
+            success,
+            timestamp: time::now(),
+            run_id,
+            changed: false,
+            mapped,
+            replay_of: self.replaying,
+            assertions: Vec::new(),
+        };
+        if result.success {
+            result.assertions = assertions::check_all(&self.expecting, &result);
+        }
+        if let Some((label, reference)) =
+            self.reference_for(&result.function, &result.args, self.results.len())
+            && diff::outputs_differ(reference, &result)
+        {
+            let message = format!(
+                "Δ {}({}) differs from its {}: {} → {}",
+                result.function,
+                result.args.join(", "),
+                label,
+                reference.output,
+                result.output
+            );
+            result.changed = true;
+            self.add_log(LogLevel::Warning, message);
+        }
+
+        let selected = self.selected_result_index();
+        self.results.push(result.clone());
+        if let Some(history) = &self.history {
+            let saved = history.append(&result);
+            let excess = self.results.len().saturating_sub(history.retention());
+            self.results.drain(..excess);
+            self.compare_result = self.compare_result.and_then(|i| i.checked_sub(excess));
+            if excess > 0 {
+                self.reselect_result(selected.and_then(|i| i.checked_sub(excess)));
+            }
+            if let Err(e) = saved {
+                self.add_log(LogLevel::Error, e);
             }
         }
+        result
+    }
+
+    /// Selects the result at `index` in `results` in the Results Explorer,
+    /// or keeps the selection within the list when that result is gone.
+    fn reselect_result(&mut self, index: Option<usize>) {
+        let results = self.filtered_results();
+        let position = index
+            .and_then(|i| self.results.get(i))
+            .and_then(|selected| results.iter().position(|r| std::ptr::eq(*r, selected)));
+        self.selected_result =
+            position.unwrap_or(self.selected_result.min(results.len().saturating_sub(1)));
     }
 
     fn add_to_pipeline(&mut self) {
@@ -358,674 +929,3092 @@ impl App {
 
         let func = &script.functions[self.function_input.selected_function];
 
-        let id = format!("step_{}", self.pipeline.len() + 1);
-        let description = format!("{}({})", func, self.function_input.args.join(", "));
-
-        self.pipeline.push(PipelineStep {
+        let id = self.next_step_id();
+        let mut step = PipelineStep {
             id: id.clone(),
             script: script.name.clone(),
             function: func.clone(),
             args: self.function_input.args.clone(),
-            description,
-        });
+            description: String::new(),
+            kind: StepKind::Call,
+            expect: Vec::new(),
+        };
+        step.description = default_description(&step);
+        self.pipeline.push(step);
 
+        self.pipeline_changed();
         self.add_log(LogLevel::Success, format!("Added {} to pipeline", id));
     }
 
-    fn execute_pipeline(&mut self) -> Result<(), String> {
-        if self.pipeline.is_empty() {
-            return Err("Pipeline is empty".into());
-        }
+    fn next_step_id(&self) -> String {
+        (1..)
+            .map(|n| format!("step_{}", n))
+            .find(|id| !self.pipeline.iter().any(|s| &s.id == id))
+            .unwrap()
+    }
 
-        self.add_log(LogLevel::Info, "🚀 Executing pipeline...".into());
-        let start = Instant::now();
-        let mut success_count = 0;
+    fn begin_step_insert(&mut self) {
+        if self.scripts.is_empty() {
+            self.add_log(LogLevel::Error, "No scripts available".to_string());
+            return;
+        }
 
-        // Clone pipeline to avoid borrow issues
-        let pipeline = self.pipeline.clone();
+        let position = if self.pipeline.is_empty() {
+            0
+        } else {
+            self.selected_pipeline_step + 1
+        };
 
-        for (i, step) in pipeline.iter().enumerate() {
-            self.selected_pipeline_step = i;
+        self.step_editor = StepEditor {
+            position,
+            field: StepField::Script,
+            id: self.next_step_id(),
+            script: self.selected_script,
+            function: 0,
+            args: String::new(),
+            map_over: String::new(),
+            expect: String::new(),
+            description: String::new(),
+            missing_function: None,
+        };
+        self.ensure_editor_script_loaded();
+        self.input_mode = InputMode::AddingStep;
+    }
 
-            let script_idx = self.scripts.iter().position(|s| s.name == step.script);
-            if script_idx.is_none() {
-                self.add_log(LogLevel::Error, format!("Script {} not found", step.script));
-                continue;
-            }
+    fn begin_step_edit(&mut self) {
+        let Some(step) = self.pipeline.get(self.selected_pipeline_step).cloned() else {
+            return;
+        };
 
-            let script_idx = script_idx.unwrap();
-            if !self.scripts[script_idx].loaded {
-                let _ = self.load_script(script_idx);
+        let script = match self.scripts.iter().position(|s| s.name == step.script) {
+            Some(idx) => idx,
+            None => {
+                self.add_log(
+                    LogLevel::Warning,
+                    format!("Script {} not found, pick another one", step.script),
+                );
+                self.selected_script
+                    .min(self.scripts.len().saturating_sub(1))
             }
+        };
 
-            let old_input = self.function_input.clone();
-            self.function_input.args = step.args.clone();
+        // A description generated from the function and args is generated
+        // again on commit, so it follows changes to them.
+        let description = if step.description == default_description(&step) {
+            String::new()
+        } else {
+            step.description.clone()
+        };
+        self.step_editor = StepEditor {
+            position: self.selected_pipeline_step,
+            field: StepField::Args,
+            id: step.id.clone(),
+            script,
+            function: 0,
+            args: format_arg_list(&step.args),
+            map_over: String::new(),
+            expect: assertions::format_expectations(&step.expect),
+            description,
+            missing_function: None,
+        };
+        if let StepKind::Map { over } = &step.kind {
+            self.step_editor.map_over = over.clone();
+        }
+        self.ensure_editor_script_loaded();
 
-            if let Some(func_idx) = self.scripts[script_idx]
-                .functions
-                .iter()
-                .position(|f| f == &step.function)
-            {
-                self.function_input.selected_function = func_idx;
-                let _ = self.execute_function();
-                success_count += 1;
+        if let Some(script) = self.scripts.get(script)
+            && script.name == step.script
+        {
+            match script.functions.iter().position(|f| f == &step.function) {
+                Some(func_idx) => self.step_editor.function = func_idx,
+                None => {
+                    self.add_log(
+                        LogLevel::Error,
+                        format!(
+                            "Function {} not found in {}, keeping it unless you pick another one",
+                            step.function, step.script
+                        ),
+                    );
+                    self.step_editor.missing_function = Some(step.function.clone());
+                }
             }
-
-            self.function_input = old_input;
         }
 
-        let duration = start.elapsed().as_millis() as u64;
-        self.add_log(
-            LogLevel::Success,
-            format!(
-                "Pipeline completed: {}/{} steps in {}ms",
-                success_count,
-                self.pipeline.len(),
-                duration
-            ),
-        );
+        self.input_mode = InputMode::EditingStep;
+    }
 
-        Ok(())
+    fn ensure_editor_script_loaded(&mut self) {
+        let idx = self.step_editor.script;
+        if idx < self.scripts.len() && !self.scripts[idx].loaded {
+            let _ = self.load_script(idx);
+        }
     }
 
-    fn export_pipeline(&self) -> String {
-        let mut output = String::new();
-
-        output.push_str("// Generated MetaCall Pipeline\n");
-        output.push_str("// Export Date: ");
-        output.push_str(&Self::timestamp());
-        output.push_str("\n\n");
-
-        output.push_str("// === Rust Implementation ===\n");
-        output.push_str("use metacall::{initialize, load, metacall};\n\n");
-        output.push_str("fn execute_pipeline() -> Result<(), String> {\n");
-        output.push_str("    let _metacall = initialize()?;\n\n");
-
-        let mut loaded_scripts = std::collections::HashSet::new();
-        for step in &self.pipeline {
-            if !loaded_scripts.contains(&step.script) {
-                if let Some(script) = self.scripts.iter().find(|s| s.name == step.script) {
-                    output.push_str(&format!(
-                        "    load::from_single_file(\"{}\", \"{}\")?;\n",
-                        script.runtime,
-                        script.path.display()
-                    ));
-                    loaded_scripts.insert(step.script.clone());
-                }
-            }
+    fn cycle_editor_script(&mut self, forward: bool) {
+        let count = self.scripts.len();
+        if count == 0 {
+            return;
         }
 
-        output.push_str("\n");
+        let editor = &mut self.step_editor;
+        editor.script = if forward {
+            (editor.script + 1) % count
+        } else {
+            (editor.script + count - 1) % count
+        };
+        editor.function = 0;
+        editor.missing_function = None;
+        self.ensure_editor_script_loaded();
+    }
 
-        for (i, step) in self.pipeline.iter().enumerate() {
-            output.push_str(&format!("    // Step {}: {}\n", i + 1, step.description));
-            output.push_str(&format!(
-                "    let result_{}: String = metacall(\"{}\", vec![",
-                i + 1,
-                step.function
-            ));
+    fn cycle_editor_function(&mut self, forward: bool) {
+        let count = self
+            .scripts
+            .get(self.step_editor.script)
+            .map_or(0, |s| s.functions.len());
+        if count == 0 {
+            return;
+        }
 
-            for (j, arg) in step.args.iter().enumerate() {
-                if j > 0 {
-                    output.push_str(", ");
-                }
-                if arg.parse::<i64>().is_ok() {
-                    output.push_str(arg);
-                } else {
-                    output.push_str(&format!("\"{}\".to_string()", arg));
-                }
-            }
+        let editor = &mut self.step_editor;
+        if editor.missing_function.take().is_some() {
+            return;
+        }
+        editor.function = if forward {
+            (editor.function + 1) % count
+        } else {
+            (editor.function + count - 1) % count
+        };
+    }
 
-            output.push_str("])?;\n");
-            output.push_str(&format!(
-                "    println!(\"Step {}: {{}}\", result_{});\n\n",
-                i + 1,
-                i + 1
-            ));
+    fn commit_step_editor(&mut self) -> Result<(), String> {
+        let editor = self.step_editor.clone();
+        let adding = self.input_mode == InputMode::AddingStep;
+
+        let id = editor.id.trim().to_string();
+        if id.is_empty() {
+            return Err("Step id cannot be empty".into());
+        }
+        let taken = self
+            .pipeline
+            .iter()
+            .enumerate()
+            .any(|(i, s)| s.id == id && (adding || i != editor.position));
+        if taken {
+            return Err(format!("Step id {} is already used", id));
         }
 
-        output.push_str("    Ok(())\n");
-        output.push_str("}\n\n");
+        let script = self
+            .scripts
+            .get(editor.script)
+            .ok_or("No script selected")?;
+        if !script.loaded {
+            return Err(format!("Script {} is not loaded", script.name));
+        }
+        let function = match &editor.missing_function {
+            Some(function) => function.clone(),
+            None => script
+                .functions
+                .get(editor.function)
+                .ok_or(format!("No functions found in {}", script.name))?
+                .clone(),
+        };
 
-        output.push_str("// === JSON Configuration ===\n");
-        output.push_str("/*\n");
-        output.push_str(&serde_json::to_string_pretty(&self.pipeline).unwrap_or_default());
-        output.push_str("\n*/\n");
+        let args = parse_arg_list(&editor.args);
+        let over = editor.map_over.trim();
+        let kind = if over.is_empty() {
+            StepKind::Call
+        } else {
+            StepKind::Map {
+                over: over.to_string(),
+            }
+        };
 
-        output
-    }
+        let expect = assertions::parse_expectations(&editor.expect)?;
 
-    fn next_view(&mut self) {
-        self.current_view = match self.current_view {
-            View::ScriptBrowser => View::FunctionTester,
-            View::FunctionTester => View::PipelineBuilder,
-            View::PipelineBuilder => View::ResultsExplorer,
-            View::ResultsExplorer => View::Export,
-            View::Export => View::ScriptBrowser,
+        let mut step = PipelineStep {
+            id: id.clone(),
+            script: script.name.clone(),
+            function,
+            args,
+            description: editor.description.clone(),
+            kind,
+            expect,
         };
-    }
+        if step.description.trim().is_empty() {
+            step.description = default_description(&step);
+        }
+
+        if adding {
+            let position = editor.position.min(self.pipeline.len());
+            self.pipeline.insert(position, step);
+            self.selected_pipeline_step = position;
+            self.add_log(LogLevel::Success, format!("Inserted {}", id));
+        } else {
+            self.pipeline[editor.position] = step;
+            self.add_log(LogLevel::Success, format!("Updated {}", id));
+        }
+        self.pipeline_changed();
+
+        Ok(())
+    }
+
+    fn move_step(&mut self, up: bool) {
+        let i = self.selected_pipeline_step;
+        if i >= self.pipeline.len() {
+            return;
+        }
+
+        let target = if up {
+            i.checked_sub(1)
+        } else {
+            Some(i + 1).filter(|&t| t < self.pipeline.len())
+        };
+
+        if let Some(target) = target {
+            self.pipeline.swap(i, target);
+            self.selected_pipeline_step = target;
+            self.pipeline_modified = true;
+        }
+    }
+
+    fn duplicate_step(&mut self) {
+        let Some(step) = self.pipeline.get(self.selected_pipeline_step).cloned() else {
+            return;
+        };
+
+        let id = self.next_step_id();
+        let position = self.selected_pipeline_step + 1;
+        self.pipeline.insert(
+            position,
+            PipelineStep {
+                id: id.clone(),
+                ..step
+            },
+        );
+        self.selected_pipeline_step = position;
+        self.pipeline_changed();
+        self.add_log(LogLevel::Success, format!("Duplicated step as {}", id));
+    }
+
+    /// Runs the whole pipeline with raw parameter inputs, which fall back to
+    /// the declared defaults.
+    fn execute_pipeline(&mut self, inputs: &HashMap<String, String>) -> Result<(), String> {
+        let params = params::resolve_inputs(&self.params, inputs).inspect_err(|e| {
+            self.add_log(LogLevel::Error, e.clone());
+        })?;
+        self.run_pipeline(self.pipeline.clone(), 0, None, params)
+    }
+
+    /// Runs the steps of run `run_idx` again starting at `from`, with its
+    /// parameters, reusing the outputs it recorded for the steps before it.
+    fn rerun_pipeline(&mut self, run_idx: usize, from: usize) -> Result<(), String> {
+        let previous = self.run_at(run_idx).cloned().ok_or("Invalid run index")?;
+        let params = previous.params.clone();
+        self.run_pipeline(previous.pipeline.clone(), from, Some(previous), params)
+    }
+
+    fn begin_pipeline_inputs(&mut self) {
+        if self.params.is_empty() {
+            let _ = self.execute_pipeline(&HashMap::new());
+            return;
+        }
+
+        self.param_inputs.resize(self.params.len(), String::new());
+        self.selected_param = 0;
+        self.input_mode = InputMode::PipelineInputs;
+    }
+
+    fn submit_pipeline_inputs(&mut self) -> Result<(), String> {
+        let inputs: HashMap<String, String> = self
+            .params
+            .iter()
+            .zip(&self.param_inputs)
+            .map(|(p, v)| (p.name.clone(), v.clone()))
+            .collect();
+        params::resolve_inputs(&self.params, &inputs)?;
+
+        self.input_mode = InputMode::Normal;
+        let _ = self.execute_pipeline(&inputs);
+        Ok(())
+    }
+
+    fn set_params(&mut self, declarations: &str) -> Result<(), String> {
+        let declared = params::parse_declarations(declarations)?;
+
+        let previous: HashMap<String, String> = self
+            .params
+            .iter()
+            .zip(&self.param_inputs)
+            .map(|(p, v)| (p.name.clone(), v.clone()))
+            .collect();
+        self.param_inputs = declared
+            .iter()
+            .map(|p| previous.get(&p.name).cloned().unwrap_or_default())
+            .collect();
+        self.params = declared;
+
+        self.add_log(
+            LogLevel::Success,
+            format!("Pipeline declares {} parameters", self.params.len()),
+        );
+        Ok(())
+    }
+
+    /// Replaces the pipeline with one read from a pipeline JSON or YAML file,
+    /// or from an exported file. Relative paths are resolved against the
+    /// export output directory. Modified steps are only replaced once
+    /// confirmed.
+    fn import_pipeline(&mut self, input: &str) -> Result<(), String> {
+        let path = self.export_output_dir.join(input.trim());
+        let content = fs::read_to_string(&path)
+            .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+        let file = import::parse_pipeline(&content, &path)?;
+
+        if self.pipeline_modified && !self.pipeline.is_empty() {
+            self.pending_import = Some((path, file));
+            self.input_mode = InputMode::ConfirmImport;
+        } else {
+            self.apply_import(path, file);
+        }
+        Ok(())
+    }
+
+    fn apply_import(&mut self, path: PathBuf, file: PipelineFile) {
+        self.param_inputs = vec![String::new(); file.params.len()];
+        self.params = file.params;
+        self.pipeline = file.steps;
+        self.selected_pipeline_step = 0;
+        self.pipeline_modified = false;
+        self.refresh_import_issues();
+
+        self.add_log(
+            LogLevel::Success,
+            format!(
+                "Imported {} steps from {}",
+                self.pipeline.len(),
+                path.display()
+            ),
+        );
+        let issues: Vec<String> = self
+            .import_issues
+            .iter()
+            .map(|(id, issue)| format!("Step {}: {}", id, issue))
+            .collect();
+        for issue in issues {
+            self.add_log(LogLevel::Warning, issue);
+        }
+    }
+
+    /// Marks the pipeline as modified and checks its references again.
+    fn pipeline_changed(&mut self) {
+        self.pipeline_modified = true;
+        self.refresh_import_issues();
+    }
+
+    fn refresh_import_issues(&mut self) {
+        self.import_issues = self.missing_references(&self.pipeline);
+    }
+
+    /// The steps whose script is not among the scanned scripts, or whose
+    /// function is not defined in it. Scripts that are not loaded yet are
+    /// read to find their functions.
+    fn missing_references(&self, steps: &[PipelineStep]) -> Vec<(String, String)> {
+        let mut functions: HashMap<&str, Vec<String>> = HashMap::new();
+        let mut issues = Vec::new();
+
+        for step in steps {
+            let Some(script) = self.scripts.iter().find(|s| s.name == step.script) else {
+                issues.push((
+                    step.id.clone(),
+                    format!("script {} is not among the scanned scripts", step.script),
+                ));
+                continue;
+            };
+            let known = functions.entry(&script.name).or_insert_with(|| {
+                if script.loaded {
+                    script.functions.clone()
+                } else {
+                    fs::read_to_string(&script.path)
+                        .map(|content| self.extract_functions(&content, &script.language))
+                        .unwrap_or_default()
+                }
+            });
+            if !known.contains(&step.function) {
+                issues.push((
+                    step.id.clone(),
+                    format!("function {} not found in {}", step.function, step.script),
+                ));
+            }
+        }
+        issues
+    }
+
+    /// Runs are listed newest first, so index 0 is the latest run.
+    fn run_at(&self, index: usize) -> Option<&PipelineRun> {
+        self.runs.iter().rev().nth(index)
+    }
+
+    fn run_pipeline(
+        &mut self,
+        pipeline: Vec<PipelineStep>,
+        from: usize,
+        previous: Option<PipelineRun>,
+        params: BTreeMap<String, Value>,
+    ) -> Result<(), String> {
+        if pipeline.is_empty() {
+            return Err("Pipeline is empty".into());
+        }
+
+        // Results from earlier sessions keep their run ids, so numbering
+        // continues after them.
+        let run_id = self
+            .runs
+            .iter()
+            .map(|r| r.id)
+            .chain(self.results.iter().filter_map(|r| r.run_id))
+            .max()
+            .map_or(1, |id| id + 1);
+        match &previous {
+            Some(prev) => self.add_log(
+                LogLevel::Info,
+                format!(
+                    "🚀 Re-running pipeline from step {} (run #{})...",
+                    from + 1,
+                    prev.id
+                ),
+            ),
+            None => self.add_log(LogLevel::Info, "🚀 Executing pipeline...".into()),
+        }
+
+        let start = Instant::now();
+        let mut run = PipelineRun {
+            id: run_id,
+            started_at: time::now(),
+            finished_at: time::now(),
+            duration_ms: 0,
+            total_steps: pipeline.len(),
+            resumed_from: previous.as_ref().map(|p| p.id),
+            params: params.clone(),
+            pipeline: pipeline.clone(),
+            steps: Vec::new(),
+        };
+        let mut outputs = param_outputs(&params);
+        // What the args of the reused steps resolved against in that run.
+        let recorded = previous.as_ref().map(|p| {
+            let mut outputs = param_outputs(&p.params);
+            for s in &p.steps {
+                outputs.insert(s.step.id.clone(), s.result.output_value());
+            }
+            outputs
+        });
+
+        for (i, step) in pipeline.iter().enumerate() {
+            if previous.is_none() {
+                self.selected_pipeline_step = i;
+            }
+
+            if i < from
+                && let (Some(prev), Some(recorded)) = (&previous, &recorded)
+            {
+                let cached = prev.steps.iter().find(|s| s.step.id == step.id);
+                match reusable(step, cached, &outputs, recorded) {
+                    Ok(cached) => {
+                        outputs.insert(step.id.clone(), cached.result.output_value());
+                        let assertions = self.check_step(step, &cached.result);
+                        let result = ExecutionResult {
+                            assertions: assertions.clone(),
+                            ..cached.result.clone()
+                        };
+                        run.steps.push(StepRun {
+                            step: step.clone(),
+                            result,
+                            cached: true,
+                            assertions,
+                        });
+                        continue;
+                    }
+                    Err(reason) => {
+                        self.add_log(
+                            LogLevel::Warning,
+                            format!("{} {}, executing it", step.id, reason),
+                        );
+                    }
+                }
+            }
+
+            self.expecting = step.expect.clone();
+            let result = self.execute_step(step, &outputs, run_id);
+            self.expecting.clear();
+            let success = result.success;
+            let assertions = result.assertions.clone();
+            self.log_failed_assertions(step, &assertions);
+            outputs.insert(step.id.clone(), result.output_value());
+            run.steps.push(StepRun {
+                step: step.clone(),
+                result,
+                cached: false,
+                assertions,
+            });
+
+            if !success {
+                break;
+            }
+        }
+
+        run.duration_ms = start.elapsed().as_millis() as u64;
+        run.finished_at = time::now();
+
+        let succeeded = run.steps.iter().filter(|s| s.result.success).count();
+        let mut summary = format!(
+            "Pipeline run #{}: {}/{} steps in {}ms",
+            run.id, succeeded, run.total_steps, run.duration_ms
+        );
+        let (passed, failed) = run.assertion_counts();
+        if passed + failed > 0 {
+            summary.push_str(&format!(
+                ", assertions {} passed / {} failed",
+                passed, failed
+            ));
+        }
+        let success = run.success();
+
+        self.runs.push(run);
+        self.selected_run = 0;
+        self.selected_run_step = 0;
+
+        if success {
+            self.add_log(LogLevel::Success, summary);
+            Ok(())
+        } else {
+            self.add_log(LogLevel::Error, summary.clone());
+            Err(summary)
+        }
+    }
+
+    fn check_step(
+        &mut self,
+        step: &PipelineStep,
+        result: &ExecutionResult,
+    ) -> Vec<AssertionResult> {
+        let assertions = assertions::check_all(&step.expect, result);
+        self.log_failed_assertions(step, &assertions);
+        assertions
+    }
+
+    fn log_failed_assertions(&mut self, step: &PipelineStep, assertions: &[AssertionResult]) {
+        for failed in assertions.iter().filter(|a| !a.passed) {
+            self.add_log(
+                LogLevel::Error,
+                format!(
+                    "✗ {} expected {}: {}",
+                    step.id, failed.expectation, failed.message
+                ),
+            );
+        }
+    }
+
+    fn execute_step(
+        &mut self,
+        step: &PipelineStep,
+        outputs: &HashMap<String, Value>,
+        run_id: u64,
+    ) -> ExecutionResult {
+        let failed = |output: String| ExecutionResult {
+            function: format!("{}::{}", step.script, step.function),
+            args: step.args.clone(),
+            output,
+            value: None,
+            duration_ms: 0,
+            success: false,
+            timestamp: time::now(),
+            run_id: Some(run_id),
+            changed: false,
+            mapped: false,
+            replay_of: None,
+            assertions: Vec::new(),
+        };
+
+        let Some(script_idx) = self.scripts.iter().position(|s| s.name == step.script) else {
+            let error = format!("Script {} not found", step.script);
+            self.add_log(LogLevel::Error, error.clone());
+            return failed(error);
+        };
+
+        if !self.scripts[script_idx].loaded {
+            let _ = self.load_script(script_idx);
+        }
+
+        if !self.scripts[script_idx].functions.contains(&step.function) {
+            let error = format!("Function {} not found in {}", step.function, step.script);
+            self.add_log(LogLevel::Error, error.clone());
+            return failed(error);
+        }
+
+        let args = match resolve_args(&step.args, outputs) {
+            Ok(args) => args,
+            Err(e) => {
+                self.add_log(LogLevel::Error, format!("{}: {}", step.id, e));
+                return failed(e);
+            }
+        };
+
+        match &step.kind {
+            StepKind::Call => self.call_function(&step.script, &step.function, args, Some(run_id)),
+            StepKind::Map { over } => {
+                let items = match resolve_arg(over, outputs) {
+                    Ok(Value::Array(items)) => items,
+                    Ok(other) => {
+                        let error = format!(
+                            "{} expects an array to map over, got {}",
+                            step.id,
+                            values::display(&other)
+                        );
+                        self.add_log(LogLevel::Error, error.clone());
+                        return failed(error);
+                    }
+                    Err(e) => {
+                        self.add_log(LogLevel::Error, format!("{}: {}", step.id, e));
+                        return failed(e);
+                    }
+                };
+
+                self.map_function(&step.script, &step.function, items, args, Some(run_id))
+            }
+        }
+    }
+
+    fn export_source(&self) -> export::ExportSource<'_> {
+        // Outputs and parameters come from one run, so generated tests only
+        // assert what that run produced with those parameters.
+        let last_success = self.runs.iter().rev().find(|run| run.success());
+
+        export::ExportSource {
+            scripts: &self.scripts,
+            params: &self.params,
+            steps: &self.pipeline,
+            observed: last_success
+                .map(|run| {
+                    run.steps
+                        .iter()
+                        .map(|s| (s.step.id.clone(), s.result.output_value()))
+                        .collect()
+                })
+                .unwrap_or_default(),
+            last_params: last_success
+                .map(|run| run.params.clone())
+                .unwrap_or_default(),
+            timestamp: time::rfc3339(&time::now()),
+        }
+    }
+
+    fn export_pipeline(&self) -> (PathBuf, String) {
+        export::preview(
+            &self.export_source(),
+            self.export_format,
+            &self.export_output_dir,
+            "pipeline",
+            self.export_relative_paths,
+        )
+    }
+
+    /// Exports the pipeline as `name` in the output directory, asking for
+    /// confirmation first when that would overwrite existing files.
+    fn export_files(&mut self, name: &str) {
+        let files = export::files(
+            &self.export_source(),
+            self.export_format,
+            &self.export_output_dir,
+            name,
+            self.export_relative_paths,
+        );
+
+        self.pending_export_pipeline = true;
+        self.confirm_export(files, self.export_format.label());
+    }
+
+    /// Exports the results shown in the Results Explorer, or all of them, as
+    /// `name` in the output directory.
+    fn export_results(&mut self, name: &str) {
+        let results = if self.results_export_all {
+            self.results.iter().collect()
+        } else {
+            self.matching_results()
+        };
+        if results.is_empty() {
+            self.add_log(LogLevel::Warning, "No results to export".to_string());
+            return;
+        }
+
+        let file = export::results_file(
+            &results,
+            self.results_export_format,
+            &self.export_output_dir,
+            name,
+        );
+        self.pending_export_pipeline = false;
+        self.confirm_export(vec![file], self.results_export_format.label());
+    }
+
+    /// Writes the files, asking for confirmation first when that would
+    /// overwrite existing ones.
+    fn confirm_export(&mut self, files: Vec<export::ExportFile>, label: &'static str) {
+        if files.iter().any(|file| file.path.exists()) {
+            self.pending_export = files;
+            self.pending_export_label = label;
+            self.input_mode = InputMode::ConfirmOverwrite;
+        } else {
+            self.write_export(files, label);
+        }
+    }
+
+    fn write_export(&mut self, files: Vec<export::ExportFile>, label: &str) {
+        let target = match files.as_slice() {
+            [file] => file.path.clone(),
+            _ => files
+                .iter()
+                .map(|file| file.path.clone())
+                .reduce(|common, path| {
+                    common
+                        .ancestors()
+                        .find(|dir| path.starts_with(dir))
+                        .map(Path::to_path_buf)
+                        .unwrap_or_default()
+                })
+                .unwrap_or_default(),
+        };
+
+        match export::write(&files) {
+            Ok(()) => {
+                if self.pending_export_pipeline {
+                    self.pipeline_modified = false;
+                }
+                self.add_log(
+                    LogLevel::Success,
+                    format!("Exported {} to {}", label, target.display()),
+                )
+            }
+            Err(e) => self.add_log(LogLevel::Error, format!("Failed to export: {}", e)),
+        }
+    }
+
+    fn set_export_dir(&mut self, input: &str) -> Result<(), String> {
+        let dir = match input.trim() {
+            "" => std::env::current_dir().map_err(|e| e.to_string())?,
+            dir => PathBuf::from(dir),
+        };
+        if dir.exists() && !dir.is_dir() {
+            return Err(format!("{} is not a directory", dir.display()));
+        }
+
+        self.add_log(LogLevel::Info, format!("Exporting to {}", dir.display()));
+        self.export_output_dir = dir;
+        Ok(())
+    }
+
+    /// Results that pass the Results Explorer filters, oldest first.
+    fn matching_results(&self) -> Vec<&ExecutionResult> {
+        self.results
+            .iter()
+            .filter(|r| self.results_filter.matches(r, self.result_language(r)))
+            .collect()
+    }
+
+    /// Results as the Results Explorer lists them: filtered and sorted.
+    fn filtered_results(&self) -> Vec<&ExecutionResult> {
+        let mut results = self.matching_results();
+        self.results_sort.apply(&mut results);
+        results
+    }
+
+    /// The language of the script a result came from, also for scripts that
+    /// are no longer scanned.
+    fn result_language(&self, result: &ExecutionResult) -> Option<&'static str> {
+        let script = result.script();
+        let extension = self
+            .scripts
+            .iter()
+            .find(|s| s.name == script)
+            .and_then(|s| s.path.extension())
+            .or_else(|| Path::new(script).extension())?;
+        script_language(&extension.to_string_lossy()).map(|(language, _)| language)
+    }
+
+    /// Steps a results filter to the next value seen in the results, and
+    /// back to no filter after the last one.
+    fn cycle_results_filter(&mut self, field: ResultsFilterField) {
+        let mut values: Vec<String> = self
+            .results
+            .iter()
+            .filter(|r| {
+                field != ResultsFilterField::Function
+                    || self
+                        .results_filter
+                        .script
+                        .as_ref()
+                        .is_none_or(|script| r.script() == script)
+            })
+            .filter_map(|r| match field {
+                ResultsFilterField::Script => Some(r.script().to_string()),
+                ResultsFilterField::Function => Some(r.function_name().to_string()),
+                ResultsFilterField::Language => self.result_language(r).map(str::to_string),
+            })
+            .collect();
+        values.sort();
+        values.dedup();
+
+        let current = match field {
+            ResultsFilterField::Script => &mut self.results_filter.script,
+            ResultsFilterField::Function => &mut self.results_filter.function,
+            ResultsFilterField::Language => &mut self.results_filter.language,
+        };
+        *current = match current.as_ref() {
+            None => values.into_iter().next(),
+            Some(value) => values.into_iter().find(|v| v > value),
+        };
+        if field == ResultsFilterField::Script {
+            self.results_filter.function = None;
+        }
+        self.selected_result = 0;
+    }
+
+    fn next_view(&mut self) {
+        self.current_view = match self.current_view {
+            View::ScriptBrowser => View::FunctionTester,
+            View::FunctionTester => View::PipelineBuilder,
+            View::PipelineBuilder => View::RunHistory,
+            View::RunHistory => View::ResultsExplorer,
+            View::ResultsExplorer => View::Statistics,
+            View::Statistics => View::Export,
+            View::Export => View::Logs,
+            View::Logs => View::ScriptBrowser,
+        };
+    }
 
     fn prev_view(&mut self) {
         self.current_view = match self.current_view {
-            View::ScriptBrowser => View::Export,
+            View::ScriptBrowser => View::Logs,
             View::FunctionTester => View::ScriptBrowser,
             View::PipelineBuilder => View::FunctionTester,
-            View::ResultsExplorer => View::PipelineBuilder,
-            View::Export => View::ResultsExplorer,
+            View::RunHistory => View::PipelineBuilder,
+            View::ResultsExplorer => View::RunHistory,
+            View::Statistics => View::ResultsExplorer,
+            View::Export => View::Statistics,
+            View::Logs => View::Export,
+        };
+    }
+}
+
+/// The language and MetaCall runtime tag for a script file extension.
+fn script_language(extension: &str) -> Option<(&'static str, &'static str)> {
+    match extension.to_lowercase().as_str() {
+        "py" => Some(("Python", "py")),
+        "js" => Some(("JavaScript", "node")),
+        "rb" => Some(("Ruby", "rb")),
+        "ts" => Some(("TypeScript", "ts")),
+        _ => None,
+    }
+}
+
+/// Calls `call` once per item, with the item prepended to `extra`, one
+/// item at a time: MetaCall loaders make no thread-safety guarantee. The
+/// outputs are collected into an array, or the first failure is returned.
+fn map_items(
+    items: &[Value],
+    extra: &[Value],
+    mut call: impl FnMut(&[Value]) -> Result<Value, String>,
+) -> Result<Value, String> {
+    items
+        .iter()
+        .enumerate()
+        .map(|(i, item)| {
+            let mut args = vec![item.clone()];
+            args.extend(extra.iter().cloned());
+            call(&args).map_err(|e| format!("Item {}: {}", i, e))
+        })
+        .collect::<Result<Vec<Value>, String>>()
+        .map(Value::Array)
+}
+
+/// The outputs `${params.name}` references resolve to.
+fn param_outputs(params: &BTreeMap<String, Value>) -> HashMap<String, Value> {
+    params
+        .iter()
+        .map(|(name, value)| (format!("params.{}", name), value.clone()))
+        .collect()
+}
+
+/// The recorded run of `step` when a rerun can reuse its output: it
+/// succeeded, called the same function the same way, and its args resolve
+/// against `outputs` to what they resolved to against `recorded`, the
+/// outputs of the run it comes from. Otherwise why the step runs again.
+fn reusable<'a>(
+    step: &PipelineStep,
+    cached: Option<&'a StepRun>,
+    outputs: &HashMap<String, Value>,
+    recorded: &HashMap<String, Value>,
+) -> Result<&'a StepRun, String> {
+    let cached = cached
+        .filter(|s| s.result.success)
+        .ok_or("has no cached output")?;
+    let recorded_step = &cached.step;
+    if (
+        &recorded_step.script,
+        &recorded_step.function,
+        &recorded_step.kind,
+    ) != (&step.script, &step.function, &step.kind)
+    {
+        return Err("changed since it was cached".to_string());
+    }
+
+    let resolve = |step: &PipelineStep, outputs| {
+        let over = match &step.kind {
+            StepKind::Call => None,
+            StepKind::Map { over } => Some(resolve_arg(over, outputs)?),
         };
+        Ok::<_, String>((resolve_args(&step.args, outputs)?, over))
+    };
+    match (resolve(step, outputs), resolve(recorded_step, recorded)) {
+        (Ok(now), Ok(then)) if now == then => Ok(cached),
+        _ => Err("has args that resolve differently now".to_string()),
+    }
+}
+
+fn resolve_args(args: &[String], outputs: &HashMap<String, Value>) -> Result<Vec<Value>, String> {
+    args.iter().map(|arg| resolve_arg(arg, outputs)).collect()
+}
+
+/// Resolves `${step_id}` references to the output of that earlier step, as
+/// well as `${params.name}` and `${env.NAME}`. An argument that is only a
+/// reference keeps the value's type, otherwise the values are spliced into the
+/// text before it is parsed.
+fn resolve_arg(arg: &str, outputs: &HashMap<String, Value>) -> Result<Value, String> {
+    let lookup = |name: &str| {
+        if let Some(value) = params::env_value(name) {
+            return value;
+        }
+        outputs
+            .get(name)
+            .cloned()
+            .ok_or_else(|| format!("Unknown reference ${{{}}}", name))
+    };
+
+    let trimmed = arg.trim();
+    if let Some(name) = trimmed
+        .strip_prefix("${")
+        .and_then(|rest| rest.strip_suffix('}'))
+        && !name.contains(['{', '}'])
+    {
+        return lookup(name);
+    }
+
+    let mut resolved = String::new();
+    let mut rest = arg;
+
+    while let Some(start) = rest.find("${") {
+        let end = rest[start..]
+            .find('}')
+            .ok_or_else(|| format!("Unterminated reference in '{}'", arg))?;
+        let value = lookup(&rest[start + 2..start + end])?;
+
+        resolved.push_str(&rest[..start]);
+        resolved.push_str(&values::display(&value));
+        rest = &rest[start + end + 1..];
+    }
+
+    resolved.push_str(rest);
+    Ok(values::parse_arg(&resolved))
+}
+
+/// Splits a comma separated argument list. Commas inside JSON arrays,
+/// objects and strings stay in their arg, and `\,` is a literal comma.
+/// Empty entries are kept as empty args; only blank input has none.
+fn parse_arg_list(input: &str) -> Vec<String> {
+    if input.trim().is_empty() {
+        return Vec::new();
+    }
+    values::split_list(input)
+        .into_iter()
+        .map(|a| a.trim().to_string())
+        .collect()
+}
+
+/// The description a step gets when none is written.
+fn default_description(step: &PipelineStep) -> String {
+    match &step.kind {
+        StepKind::Map { over } => format!("map {} over {}", step.function, over),
+        StepKind::Call => format!("{}({})", step.function, step.args.join(", ")),
     }
 }
 
-fn ui(f: &mut Frame, app: &App) {
-    let chunks = Layout::default()
-        .direction(Direction::Vertical)
-        .constraints([
-            Constraint::Length(3),
-            Constraint::Min(0),
-            Constraint::Length(6),
-        ])
-        .split(f.area());
+/// Joins args for the step editor, escaping the commas of those that would
+/// not split back into the same arg.
+fn format_arg_list(args: &[String]) -> String {
+    args.iter()
+        .map(|a| match values::split_list(a).as_slice() {
+            [single] if single == a => a.clone(),
+            _ => a.replace(',', "\\,"),
+        })
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+fn ui(f: &mut Frame, app: &App) {
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(3),
+            Constraint::Min(0),
+            Constraint::Length(6),
+        ])
+        .split(f.area());
+
+    app.regions.borrow_mut().clear();
+    render_header(f, app, chunks[0]);
+    render_main_view(f, app, chunks[1]);
+    render_footer(f, app, chunks[2]);
+
+    if matches!(
+        app.input_mode,
+        InputMode::AddingStep | InputMode::EditingStep
+    ) {
+        render_step_editor(f, app);
+    }
+
+    if app.input_mode == InputMode::PipelineInputs {
+        render_pipeline_inputs(f, app);
+    }
+
+    if app.input_mode == InputMode::ExportResults {
+        render_results_export(f, app);
+    }
+
+    if app.input_mode == InputMode::ConfirmOverwrite {
+        render_overwrite_warning(f, app);
+    }
+
+    if app.input_mode == InputMode::ConfirmClearHistory {
+        render_clear_history_warning(f, app);
+    }
+
+    if app.input_mode == InputMode::ConfirmImport {
+        render_import_warning(f, app);
+    }
+
+    if let Some(viewer) = &app.output_viewer {
+        render_output_viewer(f, app, viewer);
+    }
+
+    if app.show_help {
+        render_help_popup(f, app);
+    }
+
+    if let Some(palette) = &app.palette {
+        render_command_palette(f, app, palette);
+    }
+
+    if app.input_mode == InputMode::ConfirmQuit {
+        render_quit_confirmation(f, app);
+    }
+}
+
+fn render_header(f: &mut Frame, app: &App, area: Rect) {
+    let theme = &app.theme;
+    let titles = [
+        ("Scripts", View::ScriptBrowser),
+        ("Tester", View::FunctionTester),
+        ("Pipeline", View::PipelineBuilder),
+        ("Runs", View::RunHistory),
+        ("Results", View::ResultsExplorer),
+        ("Stats", View::Statistics),
+        ("Export", View::Export),
+        ("Logs", View::Logs),
+    ];
+    let selected = titles
+        .iter()
+        .position(|(_, view)| *view == app.current_view);
+    app.regions.borrow_mut().tabs(area, &titles);
+
+    let tabs = Tabs::new(titles.iter().map(|(title, _)| *title))
+        .block(
+            Block::default()
+                .title(theme.icons("🔧 MetaCall Playground"))
+                .borders(Borders::ALL)
+                .border_style(Style::default().fg(theme.primary)),
+        )
+        .select(selected.unwrap_or(0))
+        .style(Style::default().fg(theme.text))
+        .highlight_style(
+            Style::default()
+                .fg(theme.highlight)
+                .add_modifier(Modifier::BOLD),
+        );
+
+    f.render_widget(tabs, area);
+}
+
+fn render_main_view(f: &mut Frame, app: &App, area: Rect) {
+    match app.current_view {
+        View::ScriptBrowser => render_script_browser(f, app, area),
+        View::FunctionTester => render_function_tester(f, app, area),
+        View::PipelineBuilder => render_pipeline_builder(f, app, area),
+        View::RunHistory => render_run_history(f, app, area),
+        View::ResultsExplorer => render_results(f, app, area),
+        View::Statistics => render_statistics(f, app, area),
+        View::Export => render_export(f, app, area),
+        View::Logs => render_logs(f, app, area),
+    }
+}
+
+fn render_script_browser(f: &mut Frame, app: &App, area: Rect) {
+    let theme = &app.theme;
+    let chunks = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
+        .split(area);
+
+    let items: Vec<ListItem> = app
+        .scripts
+        .iter()
+        .enumerate()
+        .map(|(i, script)| {
+            let icon = theme.icons(match script.language.as_str() {
+                "Python" => "🐍 ",
+                "JavaScript" => "📜 ",
+                "TypeScript" => "📘 ",
+                "Ruby" => "💎 ",
+                _ => "📄 ",
+            });
+
+            let status = if script.loaded {
+                "✓"
+            } else if script.error.is_some() {
+                "✗"
+            } else {
+                "○"
+            };
+
+            let style = if i == app.selected_script {
+                Style::default()
+                    .fg(theme.highlight)
+                    .add_modifier(Modifier::BOLD)
+            } else if script.loaded {
+                Style::default().fg(theme.success)
+            } else if script.error.is_some() {
+                Style::default().fg(theme.error)
+            } else {
+                Style::default().fg(theme.muted)
+            };
+
+            ListItem::new(format!(
+                "{} {}{} [{}]",
+                theme.icons(status),
+                icon,
+                script.name,
+                script.language
+            ))
+            .style(style)
+        })
+        .collect();
+
+    f.render_widget(
+        List::new(items)
+            .block(
+                Block::default()
+                    .title(theme.icons("📂 Scripts"))
+                    .borders(Borders::ALL)
+                    .border_style(Style::default().fg(theme.primary)),
+            )
+            .highlight_style(Style::default().bg(theme.selection)),
+        chunks[0],
+    );
+    app.regions
+        .borrow_mut()
+        .list(mouse::List::Scripts, chunks[0], 0, 0, app.scripts.len());
+
+    let details = if !app.scripts.is_empty() {
+        let script = &app.scripts[app.selected_script];
+        let mut lines = vec![
+            Line::from(vec![
+                Span::styled("Name: ", Style::default().fg(theme.muted)),
+                Span::styled(&script.name, Style::default().fg(theme.text)),
+            ]),
+            Line::from(vec![
+                Span::styled("Language: ", Style::default().fg(theme.muted)),
+                Span::styled(&script.language, Style::default().fg(theme.primary)),
+            ]),
+            Line::from(vec![
+                Span::styled("Path: ", Style::default().fg(theme.muted)),
+                Span::styled(
+                    script.path.display().to_string(),
+                    Style::default().fg(theme.subtle),
+                ),
+            ]),
+            Line::from(""),
+        ];
+
+        if let Some(error) = &script.error {
+            lines.push(Line::from(vec![
+                Span::styled("Error: ", Style::default().fg(theme.error)),
+                Span::styled(error, Style::default().fg(theme.error)),
+            ]));
+        } else if script.loaded {
+            lines.push(Line::from(vec![Span::styled(
+                format!("Functions ({}): ", script.functions.len()),
+                Style::default().fg(theme.muted),
+            )]));
+
+            for func in &script.functions {
+                lines.push(Line::from(format!("  {} {}", theme.icons("•"), func)));
+            }
+        } else {
+            lines.push(Line::from(Span::styled(
+                format!(
+                    "Press '{}' to load this script",
+                    app.key_label(Scope::Scripts, Action::LoadScript)
+                ),
+                Style::default().fg(theme.highlight),
+            )));
+        }
+
+        Paragraph::new(lines)
+            .block(
+                Block::default()
+                    .title(theme.icons("📋 Details"))
+                    .borders(Borders::ALL)
+                    .border_style(Style::default().fg(theme.primary))
+                    .padding(Padding::uniform(1)),
+            )
+            .wrap(Wrap { trim: true })
+    } else {
+        Paragraph::new("No scripts found")
+            .block(
+                Block::default()
+                    .title(theme.icons("📋 Details"))
+                    .borders(Borders::ALL)
+                    .border_style(Style::default().fg(theme.primary)),
+            )
+            .alignment(Alignment::Center)
+    };
+
+    f.render_widget(details, chunks[1]);
+}
+
+fn render_function_tester(f: &mut Frame, app: &App, area: Rect) {
+    let theme = &app.theme;
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(10),
+            Constraint::Length(if app.arg_editor.is_some() { 14 } else { 8 }),
+            Constraint::Min(0),
+        ])
+        .split(area);
+
+    if !app.scripts.is_empty() && app.selected_script < app.scripts.len() {
+        let script = &app.scripts[app.selected_script];
+
+        let func_items: Vec<ListItem> = script
+            .functions
+            .iter()
+            .enumerate()
+            .map(|(i, func)| {
+                let style = if i == app.function_input.selected_function {
+                    Style::default()
+                        .fg(theme.highlight)
+                        .add_modifier(Modifier::BOLD)
+                } else {
+                    Style::default().fg(theme.text)
+                };
+                ListItem::new(format!("{}()", func)).style(style)
+            })
+            .collect();
+
+        f.render_widget(
+            List::new(func_items).block(
+                Block::default()
+                    .title(
+                        theme
+                            .icons(&format!("🔧 Functions - {}", script.name))
+                            .into_owned(),
+                    )
+                    .borders(Borders::ALL)
+                    .border_style(Style::default().fg(theme.primary))
+                    .padding(Padding::horizontal(1)),
+            ),
+            chunks[0],
+        );
+        app.regions.borrow_mut().list(
+            mouse::List::Functions,
+            chunks[0],
+            0,
+            0,
+            script.functions.len(),
+        );
+    }
+
+    if let Some(editor) = &app.arg_editor {
+        render_arg_editor(f, app, editor, chunks[1]);
+    } else {
+        render_args(f, app, chunks[1]);
+    }
+    render_recent_results(f, app, chunks[2]);
+}
+
+fn render_arg_editor(f: &mut Frame, app: &App, editor: &editor::ArgEditor, area: Rect) {
+    let theme = &app.theme;
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Min(0), Constraint::Length(2)])
+        .split(area);
+    f.render_widget(editor.textarea(), chunks[0]);
+
+    let args = editor.args();
+    let mut preview = vec![Span::styled(
+        format!("{} args: ", args.len()),
+        Style::default().fg(theme.muted),
+    )];
+    for (i, arg) in args.iter().enumerate() {
+        if i > 0 {
+            preview.push(Span::styled(", ", Style::default().fg(theme.subtle)));
+        }
+        let value = values::parse_arg(arg);
+        let kind = match &value {
+            Value::Null => "null",
+            Value::Bool(_) => "bool",
+            Value::Number(_) => "number",
+            Value::String(_) if arg.trim_start().starts_with(['[', '{']) => "invalid JSON",
+            Value::String(_) => "string",
+            Value::Array(_) => "array",
+            Value::Object(_) => "object",
+        };
+        let color = if kind == "invalid JSON" {
+            theme.error
+        } else {
+            theme.primary
+        };
+        preview.push(Span::styled(kind, Style::default().fg(color)));
+    }
+
+    let history = match editor.recall_position() {
+        (_, 0) => "no earlier args".to_string(),
+        (Some(i), n) => format!("history {}/{}", i + 1, n),
+        (None, n) => format!("{} earlier", n),
+    };
+    f.render_widget(
+        Paragraph::new(vec![
+            Line::from(preview),
+            Line::from(Span::styled(
+                theme
+                    .icons(&format!(
+                        "Ctrl+S/Alt+Enter: Apply | Esc: Cancel | Ctrl+↑↓: Recall ({})",
+                        history
+                    ))
+                    .into_owned(),
+                Style::default().fg(theme.subtle),
+            )),
+        ]),
+        chunks[1],
+    );
+}
+
+fn render_args(f: &mut Frame, app: &App, area: Rect) {
+    let theme = &app.theme;
+    let arg_lines = vec![
+        Line::from(vec![
+            Span::styled("Arguments: ", Style::default().fg(theme.muted)),
+            Span::styled(
+                format!("{:?}", app.function_input.args),
+                Style::default().fg(theme.primary),
+            ),
+        ]),
+        Line::from(""),
+        Line::from(Span::styled(
+            format!(
+                "Press '{}' to edit arguments, '{}' to execute",
+                app.key_label(Scope::Tester, Action::EditArgs),
+                app.key_label(Scope::Tester, Action::Execute)
+            ),
+            Style::default().fg(theme.subtle),
+        )),
+    ];
+
+    f.render_widget(
+        Paragraph::new(arg_lines).block(
+            Block::default()
+                .title(theme.icons("⚙️  Input"))
+                .borders(Borders::ALL)
+                .border_style(Style::default().fg(theme.primary))
+                .padding(Padding::uniform(1)),
+        ),
+        area,
+    );
+}
+
+fn render_recent_results(f: &mut Frame, app: &App, area: Rect) {
+    let theme = &app.theme;
+    let result_items: Vec<ListItem> = app
+        .results
+        .iter()
+        .rev()
+        .take(10)
+        .map(|r| {
+            let color = if r.success {
+                theme.success
+            } else {
+                theme.error
+            };
+            let icon = if r.success { "✓" } else { "✗" };
+
+            ListItem::new(vec![
+                Line::from(vec![
+                    Span::styled(
+                        format!("{} ", theme.icons(icon)),
+                        Style::default().fg(color),
+                    ),
+                    Span::styled(&r.function, Style::default().fg(theme.text)),
+                    Span::styled(
+                        format!(" ({}ms)", r.duration_ms),
+                        Style::default().fg(theme.subtle),
+                    ),
+                ]),
+                Line::from(vec![
+                    Span::styled("  Args: ", Style::default().fg(theme.muted)),
+                    Span::styled(format!("{:?}", r.args), Style::default().fg(theme.subtle)),
+                ]),
+                Line::from(vec![
+                    Span::styled("  Result: ", Style::default().fg(theme.muted)),
+                    Span::styled(&r.output, Style::default().fg(color)),
+                ]),
+            ])
+        })
+        .collect();
+
+    f.render_widget(
+        List::new(result_items).block(
+            Block::default()
+                .title(theme.icons("📊 Recent Results"))
+                .borders(Borders::ALL)
+                .border_style(Style::default().fg(theme.primary))
+                .padding(Padding::horizontal(1)),
+        ),
+        area,
+    );
+}
+
+fn render_pipeline_builder(f: &mut Frame, app: &App, area: Rect) {
+    let theme = &app.theme;
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Percentage(70), Constraint::Percentage(30)])
+        .split(area);
+
+    let rows: Vec<Row> = app
+        .pipeline
+        .iter()
+        .enumerate()
+        .map(|(i, step)| {
+            let missing = app.import_issues.iter().any(|(id, _)| *id == step.id);
+            let style = if i == app.selected_pipeline_step {
+                Style::default().fg(theme.highlight)
+            } else if missing {
+                Style::default().fg(theme.error)
+            } else {
+                Style::default().fg(theme.text)
+            };
+
+            Row::new(vec![
+                Cell::from(if missing {
+                    format!("{} {}", theme.icons("⚠"), step.id)
+                } else {
+                    step.id.clone()
+                }),
+                Cell::from(step.script.clone()),
+                Cell::from(step.function.clone()),
+                Cell::from(match &step.kind {
+                    StepKind::Call => format!("{:?}", step.args),
+                    StepKind::Map { over } => format!("map over {} {:?}", over, step.args),
+                }),
+            ])
+            .style(style)
+        })
+        .collect();
+
+    let table = Table::new(
+        rows,
+        [
+            Constraint::Length(10),
+            Constraint::Length(20),
+            Constraint::Length(20),
+            Constraint::Min(20),
+        ],
+    )
+    .header(
+        Row::new(vec!["Step", "Script", "Function", "Arguments"]).style(
+            Style::default()
+                .fg(theme.primary)
+                .add_modifier(Modifier::BOLD),
+        ),
+    )
+    .block(
+        Block::default()
+            .title(theme.icons("🔗 Pipeline Steps"))
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(theme.primary)),
+    )
+    .row_highlight_style(Style::default().bg(theme.selection));
+
+    f.render_widget(table, chunks[0]);
+    app.regions
+        .borrow_mut()
+        .list(mouse::List::Steps, chunks[0], 1, 0, app.pipeline.len());
+
+    let key = |action| app.key_label(Scope::Pipeline, action);
+    let info_text = if app.input_mode == InputMode::EditingParams {
+        vec![
+            Line::from(Span::styled(
+                "Declare parameters as name[:type][=default], comma separated:",
+                Style::default().fg(theme.highlight),
+            )),
+            Line::from(Span::styled(
+                "types: string, int, float, bool, json - reference them as ${params.name}, env as ${env.NAME}",
+                Style::default().fg(theme.subtle),
+            )),
+            Line::from(vec![
+                Span::styled(">> ", Style::default().fg(theme.highlight)),
+                Span::styled(&app.input_buffer, Style::default().fg(theme.text)),
+                Span::styled(
+                    "_",
+                    Style::default()
+                        .fg(theme.highlight)
+                        .add_modifier(Modifier::SLOW_BLINK),
+                ),
+            ]),
+        ]
+    } else if app.input_mode == InputMode::ImportPath {
+        vec![
+            Line::from(Span::styled(
+                "Import a pipeline from pipeline JSON/YAML, or from an exported .rs, .py, .js or .md file:",
+                Style::default().fg(theme.highlight),
+            )),
+            Line::from(Span::styled(
+                format!(
+                    "relative paths start in {}",
+                    app.export_output_dir.display()
+                ),
+                Style::default().fg(theme.subtle),
+            )),
+            Line::from(vec![
+                Span::styled(">> ", Style::default().fg(theme.highlight)),
+                Span::styled(&app.input_buffer, Style::default().fg(theme.text)),
+                Span::styled(
+                    "_",
+                    Style::default()
+                        .fg(theme.highlight)
+                        .add_modifier(Modifier::SLOW_BLINK),
+                ),
+            ]),
+        ]
+    } else if app.pipeline.is_empty() {
+        vec![
+            Line::from(Span::styled(
+                "No steps in pipeline",
+                Style::default().fg(theme.muted),
+            )),
+            Line::from(""),
+            Line::from(Span::styled(
+                format!(
+                    "Press '{}' to insert a step, '{}' to import a pipeline, or press '{}' in the Function Tester",
+                    key(Action::InsertStep),
+                    key(Action::ImportPipeline),
+                    app.key_label(Scope::Tester, Action::AddToPipeline)
+                ),
+                Style::default().fg(theme.highlight),
+            )),
+        ]
+    } else {
+        vec![
+            Line::from(vec![
+                Span::styled("Total Steps: ", Style::default().fg(theme.muted)),
+                Span::styled(
+                    app.pipeline.len().to_string(),
+                    Style::default().fg(theme.primary),
+                ),
+                Span::styled("   Parameters: ", Style::default().fg(theme.muted)),
+                Span::styled(
+                    if app.params.is_empty() {
+                        "none".to_string()
+                    } else {
+                        params::format_declarations(&app.params)
+                    },
+                    Style::default().fg(theme.primary),
+                ),
+            ]),
+            import_issue_line(app),
+            Line::from(Span::styled(
+                format!(
+                    "Press '{}' to execute pipeline, '{}' to declare parameters, '{}' to import",
+                    key(Action::RunPipeline),
+                    key(Action::EditParams),
+                    key(Action::ImportPipeline)
+                ),
+                Style::default().fg(theme.success),
+            )),
+            Line::from(Span::styled(
+                format!(
+                    "Press '{}' to edit, '{}' to insert, '{}' to duplicate, '{}'/'{}' to move",
+                    key(Action::EditStep),
+                    key(Action::InsertStep),
+                    key(Action::DuplicateStep),
+                    key(Action::MoveStepUp),
+                    key(Action::MoveStepDown)
+                ),
+                Style::default().fg(theme.primary),
+            )),
+            Line::from(Span::styled(
+                format!(
+                    "Press '{}' to delete selected step",
+                    key(Action::DeleteStep)
+                ),
+                Style::default().fg(theme.error),
+            )),
+            Line::from(Span::styled(
+                format!("Press '{}' to clear all steps", key(Action::ClearPipeline)),
+                Style::default().fg(theme.highlight),
+            )),
+        ]
+    };
+
+    f.render_widget(
+        Paragraph::new(info_text)
+            .block(
+                Block::default()
+                    .title(theme.icons("ℹ️  Info"))
+                    .borders(Borders::ALL)
+                    .border_style(Style::default().fg(theme.primary))
+                    .padding(Padding::uniform(1)),
+            )
+            .alignment(Alignment::Left),
+        chunks[1],
+    );
+}
+
+/// The missing reference of the selected step, or how many steps have one.
+fn import_issue_line(app: &App) -> Line<'static> {
+    let theme = &app.theme;
+    let selected = app.pipeline.get(app.selected_pipeline_step);
+    let issue = app
+        .import_issues
+        .iter()
+        .find(|(id, _)| selected.is_some_and(|step| step.id == *id));
+
+    let text = match issue {
+        Some((id, issue)) => format!("⚠ {}: {}", id, issue),
+        None if !app.import_issues.is_empty() => format!(
+            "⚠ {} steps reference a missing script or function",
+            app.import_issues.len()
+        ),
+        None => return Line::from(""),
+    };
+    Line::from(Span::styled(
+        theme.icons(&text).into_owned(),
+        Style::default().fg(theme.error),
+    ))
+}
+
+fn render_run_history(f: &mut Frame, app: &App, area: Rect) {
+    let theme = &app.theme;
+    let chunks = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Percentage(35), Constraint::Percentage(65)])
+        .split(area);
+
+    let items: Vec<ListItem> = app
+        .runs
+        .iter()
+        .rev()
+        .enumerate()
+        .map(|(i, run)| {
+            let success = run.success();
+            let color = if success { theme.success } else { theme.error };
+            let icon = if success { "✓" } else { "✗" };
+            let marker = if app.compare_run == Some(run.id) {
+                "⇄ "
+            } else {
+                ""
+            };
+            let style = if i == app.selected_run {
+                Style::default()
+                    .fg(theme.highlight)
+                    .add_modifier(Modifier::BOLD)
+            } else {
+                Style::default().fg(color)
+            };
+
+            let passed = run.steps.iter().filter(|s| s.result.success).count();
+            let (checks_passed, checks_failed) = run.assertion_counts();
+            let checks = if checks_passed + checks_failed > 0 {
+                format!(
+                    " ({}/{} checks)",
+                    checks_passed,
+                    checks_passed + checks_failed
+                )
+            } else {
+                String::new()
+            };
+            ListItem::new(format!(
+                "{}{} #{} [{}] {}/{} - {}ms{}",
+                theme.icons(marker),
+                theme.icons(icon),
+                run.id,
+                app.config.display.time(&run.started_at),
+                passed,
+                run.total_steps,
+                run.duration_ms,
+                checks
+            ))
+            .style(style)
+        })
+        .collect();
+
+    f.render_widget(
+        List::new(items).block(
+            Block::default()
+                .title(
+                    theme
+                        .icons(&format!("🕘 Pipeline Runs ({})", app.runs.len()))
+                        .into_owned(),
+                )
+                .borders(Borders::ALL)
+                .border_style(Style::default().fg(theme.primary)),
+        ),
+        chunks[0],
+    );
+    app.regions
+        .borrow_mut()
+        .list(mouse::List::Runs, chunks[0], 0, 0, app.runs.len());
+
+    let Some(run) = app.run_at(app.selected_run) else {
+        f.render_widget(
+            Paragraph::new(format!(
+                "No pipeline runs yet. Press '{}' in the Pipeline Builder.",
+                app.key_label(Scope::Pipeline, Action::RunPipeline)
+            ))
+            .style(Style::default().fg(theme.muted))
+            .block(
+                Block::default()
+                    .title(theme.icons("🔍 Run Details"))
+                    .borders(Borders::ALL)
+                    .border_style(Style::default().fg(theme.primary)),
+            )
+            .alignment(Alignment::Center),
+            chunks[1],
+        );
+        return;
+    };
+
+    let baseline = app
+        .compare_run
+        .filter(|id| *id != run.id)
+        .and_then(|id| app.runs.iter().find(|r| r.id == id));
+
+    match baseline {
+        Some(baseline) => render_run_comparison(f, app, baseline, run, chunks[1]),
+        None => {
+            let details = Layout::default()
+                .direction(Direction::Vertical)
+                .constraints([Constraint::Percentage(60), Constraint::Percentage(40)])
+                .split(chunks[1]);
+
+            render_run_steps(f, app, run, details[0]);
+            render_run_report(f, app, run, details[1]);
+        }
+    }
+}
+
+fn render_run_report(f: &mut Frame, app: &App, run: &PipelineRun, area: Rect) {
+    let theme = &app.theme;
+    let (passed, failed) = run.assertion_counts();
+    let executed_ok = run.steps.iter().filter(|s| s.result.success).count();
+    let verdict = if run.success() {
+        Span::styled(
+            "PASS",
+            Style::default()
+                .fg(theme.success)
+                .add_modifier(Modifier::BOLD),
+        )
+    } else {
+        Span::styled(
+            "FAIL",
+            Style::default()
+                .fg(theme.error)
+                .add_modifier(Modifier::BOLD),
+        )
+    };
+
+    let mut lines = vec![
+        Line::from(vec![
+            verdict,
+            Span::styled(
+                format!(
+                    "  steps {}/{}  assertions {} passed, {} failed",
+                    executed_ok, run.total_steps, passed, failed
+                ),
+                Style::default().fg(theme.muted),
+            ),
+        ]),
+        Line::from(""),
+    ];
+
+    for (i, step_run) in run.steps.iter().enumerate() {
+        let selected = i == app.selected_run_step;
+        let id_style = if selected {
+            Style::default()
+                .fg(theme.highlight)
+                .add_modifier(Modifier::BOLD)
+        } else {
+            Style::default().fg(theme.text)
+        };
+
+        if !step_run.result.success {
+            lines.push(Line::from(vec![
+                Span::styled(theme.icons("✗ "), Style::default().fg(theme.error)),
+                Span::styled(format!("{:<10} ", step_run.step.id), id_style),
+                Span::styled(
+                    format!("failed: {}", step_run.result.output),
+                    Style::default().fg(theme.error),
+                ),
+            ]));
+        }
+
+        for assertion in &step_run.assertions {
+            let (icon, color) = if assertion.passed {
+                ("✓ ", theme.success)
+            } else {
+                ("✗ ", theme.error)
+            };
+            let mut spans = vec![
+                Span::styled(theme.icons(icon), Style::default().fg(color)),
+                Span::styled(format!("{:<10} ", step_run.step.id), id_style),
+                Span::styled(&assertion.expectation, Style::default().fg(color)),
+            ];
+            if !assertion.passed {
+                spans.push(Span::styled(
+                    theme
+                        .icons(&format!(" — {}", assertion.message))
+                        .into_owned(),
+                    Style::default().fg(theme.subtle),
+                ));
+            }
+            lines.push(Line::from(spans));
+        }
+    }
+
+    if passed + failed == 0 {
+        lines.push(Line::from(Span::styled(
+            "No expectations. Add them with the Expect field when editing a step.",
+            Style::default().fg(theme.subtle),
+        )));
+    }
+
+    f.render_widget(
+        Paragraph::new(lines)
+            .block(
+                Block::default()
+                    .title(theme.icons("🧪 Test Report"))
+                    .borders(Borders::ALL)
+                    .border_style(Style::default().fg(theme.primary))
+                    .padding(Padding::horizontal(1)),
+            )
+            .wrap(Wrap { trim: false }),
+        area,
+    );
+}
+
+fn render_run_steps(f: &mut Frame, app: &App, run: &PipelineRun, area: Rect) {
+    let theme = &app.theme;
+    let rows: Vec<Row> = run
+        .steps
+        .iter()
+        .enumerate()
+        .map(|(i, step_run)| {
+            let result = &step_run.result;
+            let (status, color) = if step_run.cached {
+                ("cached", theme.info)
+            } else if result.success {
+                ("✓", theme.success)
+            } else {
+                ("✗", theme.error)
+            };
+            let style = if i == app.selected_run_step {
+                Style::default()
+                    .fg(theme.highlight)
+                    .add_modifier(Modifier::BOLD)
+            } else {
+                Style::default().fg(color)
+            };
 
-    render_header(f, app, chunks[0]);
-    render_main_view(f, app, chunks[1]);
-    render_footer(f, app, chunks[2]);
+            Row::new(vec![
+                Cell::from(step_run.step.id.clone()),
+                Cell::from(result.function.clone()),
+                Cell::from(theme.icons(status)),
+                Cell::from(format!("{}ms", result.duration_ms)),
+                Cell::from(result.output.clone()),
+            ])
+            .style(style)
+        })
+        .chain((run.steps.len()..run.total_steps).map(|i| {
+            let style = if i == app.selected_run_step {
+                Style::default().fg(theme.highlight)
+            } else {
+                Style::default().fg(theme.subtle)
+            };
+            Row::new(vec![
+                Cell::from(format!("#{}", i + 1)),
+                Cell::from(""),
+                Cell::from("not run"),
+                Cell::from(""),
+                Cell::from(""),
+            ])
+            .style(style)
+        }))
+        .collect();
 
-    if app.show_help {
-        render_help_popup(f);
+    let mut title = match run.resumed_from {
+        Some(prev) => format!(
+            "🔍 Run #{} ({} → {}, resumed from #{})",
+            run.id,
+            app.config.display.time(&run.started_at),
+            app.config.display.time(&run.finished_at),
+            prev
+        ),
+        None => format!(
+            "🔍 Run #{} ({} → {})",
+            run.id,
+            app.config.display.time(&run.started_at),
+            app.config.display.time(&run.finished_at)
+        ),
+    };
+    if !run.params.is_empty() {
+        let params: Vec<String> = run
+            .params
+            .iter()
+            .map(|(name, value)| format!("{}={}", name, values::display(value)))
+            .collect();
+        title.push_str(&format!(" [{}]", params.join(", ")));
     }
+
+    let table = Table::new(
+        rows,
+        [
+            Constraint::Length(10),
+            Constraint::Length(24),
+            Constraint::Length(8),
+            Constraint::Length(8),
+            Constraint::Min(10),
+        ],
+    )
+    .header(
+        Row::new(vec!["Step", "Function", "Status", "Time", "Output"]).style(
+            Style::default()
+                .fg(theme.primary)
+                .add_modifier(Modifier::BOLD),
+        ),
+    )
+    .block(
+        Block::default()
+            .title(theme.icons(&title))
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(theme.primary)),
+    );
+
+    f.render_widget(table, area);
 }
 
-fn render_header(f: &mut Frame, app: &App, area: Rect) {
-    let titles = vec!["Scripts", "Tester", "Pipeline", "Results", "Export"];
-    let selected = match app.current_view {
-        View::ScriptBrowser => 0,
-        View::FunctionTester => 1,
-        View::PipelineBuilder => 2,
-        View::ResultsExplorer => 3,
-        View::Export => 4,
+fn render_run_comparison(
+    f: &mut Frame,
+    app: &App,
+    baseline: &PipelineRun,
+    run: &PipelineRun,
+    area: Rect,
+) {
+    let theme = &app.theme;
+    let mut step_ids: Vec<&String> = baseline.steps.iter().map(|s| &s.step.id).collect();
+    for step in &run.steps {
+        if !step_ids.contains(&&step.step.id) {
+            step_ids.push(&step.step.id);
+        }
+    }
+
+    let find = |r: &'_ PipelineRun, id: &str| -> Option<ExecutionResult> {
+        r.steps
+            .iter()
+            .find(|s| s.step.id == id)
+            .map(|s| s.result.clone())
     };
 
-    let tabs = Tabs::new(titles)
-        .block(
-            Block::default()
-                .title("🔧 MetaCall Playground")
-                .borders(Borders::ALL)
-                .border_style(Style::default().fg(Color::Cyan)),
-        )
-        .select(selected)
-        .style(Style::default().fg(Color::White))
-        .highlight_style(
+    let rows: Vec<Row> = step_ids
+        .iter()
+        .map(|id| {
+            let a = find(baseline, id);
+            let b = find(run, id);
+            let changed = match (&a, &b) {
+                (Some(a), Some(b)) => a.output != b.output || a.success != b.success,
+                _ => true,
+            };
+            let delta = match (&a, &b) {
+                (Some(a), Some(b)) => {
+                    format!("{:+}ms", b.duration_ms as i64 - a.duration_ms as i64)
+                }
+                _ => String::new(),
+            };
+            let output = |r: &Option<ExecutionResult>| {
+                r.as_ref()
+                    .map_or_else(|| theme.icons("—").into_owned(), |r| r.output.clone())
+            };
+
+            Row::new(vec![
+                Cell::from(id.to_string()),
+                Cell::from(output(&a)),
+                Cell::from(output(&b)),
+                Cell::from(delta),
+            ])
+            .style(if changed {
+                Style::default().fg(theme.highlight)
+            } else {
+                Style::default().fg(theme.muted)
+            })
+        })
+        .collect();
+
+    let table = Table::new(
+        rows,
+        [
+            Constraint::Length(10),
+            Constraint::Percentage(40),
+            Constraint::Percentage(40),
+            Constraint::Length(10),
+        ],
+    )
+    .header(
+        Row::new(vec![
+            "Step".to_string(),
+            format!("Run #{}", baseline.id),
+            format!("Run #{}", run.id),
+            theme.icons("Δ Time").into_owned(),
+        ])
+        .style(
             Style::default()
-                .fg(Color::Yellow)
+                .fg(theme.primary)
                 .add_modifier(Modifier::BOLD),
-        );
+        ),
+    )
+    .block(
+        Block::default()
+            .title(
+                theme
+                    .icons(&format!(
+                        "⇄ Compare #{} vs #{} (changed rows highlighted)",
+                        baseline.id, run.id
+                    ))
+                    .into_owned(),
+            )
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(theme.primary)),
+    );
 
-    f.render_widget(tabs, area);
+    f.render_widget(table, area);
 }
 
-fn render_main_view(f: &mut Frame, app: &App, area: Rect) {
-    match app.current_view {
-        View::ScriptBrowser => render_script_browser(f, app, area),
-        View::FunctionTester => render_function_tester(f, app, area),
-        View::PipelineBuilder => render_pipeline_builder(f, app, area),
-        View::ResultsExplorer => render_results(f, app, area),
-        View::Export => render_export(f, app, area),
-    }
+fn render_pipeline_inputs(f: &mut Frame, app: &App) {
+    let theme = &app.theme;
+    let area = centered_rect(60, 50, f.area());
+
+    let mut lines: Vec<Line> = app
+        .params
+        .iter()
+        .zip(&app.param_inputs)
+        .enumerate()
+        .map(|(i, (param, input))| {
+            let selected = i == app.selected_param;
+            let label_style = if selected {
+                Style::default()
+                    .fg(theme.highlight)
+                    .add_modifier(Modifier::BOLD)
+            } else {
+                Style::default().fg(theme.muted)
+            };
+
+            let mut spans = vec![
+                Span::styled(
+                    format!("{:<16}", format!("{} ({})", param.name, param.kind.name())),
+                    label_style,
+                ),
+                Span::styled(input.as_str(), Style::default().fg(theme.text)),
+            ];
+            if selected {
+                spans.push(Span::styled(
+                    "_",
+                    Style::default()
+                        .fg(theme.highlight)
+                        .add_modifier(Modifier::SLOW_BLINK),
+                ));
+            }
+            if input.is_empty() {
+                spans.push(Span::styled(
+                    match &param.default {
+                        Some(default) => format!("  default: {}", default),
+                        None => "  required".to_string(),
+                    },
+                    Style::default().fg(theme.subtle),
+                ));
+            }
+
+            Line::from(spans)
+        })
+        .collect();
+
+    lines.push(Line::from(""));
+    lines.push(Line::from(Span::styled(
+        theme.icons("↑↓/Tab: Parameter | Enter: Run | Esc: Cancel | Empty uses the default"),
+        Style::default().fg(theme.subtle),
+    )));
+
+    f.render_widget(Clear, area);
+    f.render_widget(
+        Paragraph::new(lines)
+            .block(
+                Block::default()
+                    .title(theme.icons("🚀 Pipeline Inputs"))
+                    .borders(Borders::ALL)
+                    .border_style(Style::default().fg(theme.highlight))
+                    .padding(Padding::uniform(1)),
+            )
+            .wrap(Wrap { trim: false }),
+        area,
+    );
 }
 
-fn render_script_browser(f: &mut Frame, app: &App, area: Rect) {
+fn render_step_editor(f: &mut Frame, app: &App) {
+    let theme = &app.theme;
+    let area = centered_rect(70, 60, f.area());
+    let editor = &app.step_editor;
+    let script = app.scripts.get(editor.script);
+
+    let value = |field: StepField| -> String {
+        match field {
+            StepField::Id => editor.id.clone(),
+            StepField::Script => script.map(|s| s.name.clone()).unwrap_or_default(),
+            StepField::Function => match &editor.missing_function {
+                Some(function) => theme
+                    .icons(&format!("{} ⚠ not in script", function))
+                    .into_owned(),
+                None => script
+                    .and_then(|s| s.functions.get(editor.function))
+                    .cloned()
+                    .unwrap_or_else(|| "<no functions>".to_string()),
+            },
+            StepField::Args => editor.args.clone(),
+            StepField::MapOver => editor.map_over.clone(),
+            StepField::Expect => editor.expect.clone(),
+            StepField::Description if editor.description.is_empty() && editor.field != field => {
+                "<from function and args>".to_string()
+            }
+            StepField::Description => editor.description.clone(),
+        }
+    };
+
+    let mut lines: Vec<Line> = StepField::ALL
+        .iter()
+        .map(|&field| {
+            let selected = field == editor.field;
+            let cycles = matches!(field, StepField::Script | StepField::Function);
+            let label_style = if selected {
+                Style::default()
+                    .fg(theme.highlight)
+                    .add_modifier(Modifier::BOLD)
+            } else {
+                Style::default().fg(theme.muted)
+            };
+
+            let mut spans = vec![
+                Span::styled(format!("{:<13}", field.label()), label_style),
+                Span::styled(
+                    theme.icons(if selected && cycles { "◀ " } else { "  " }),
+                    Style::default().fg(theme.highlight),
+                ),
+                Span::styled(value(field), Style::default().fg(theme.text)),
+            ];
+
+            if selected {
+                spans.push(Span::styled(
+                    theme.icons(if cycles { " ▶" } else { "_" }),
+                    Style::default()
+                        .fg(theme.highlight)
+                        .add_modifier(Modifier::SLOW_BLINK),
+                ));
+            }
+
+            Line::from(spans)
+        })
+        .collect();
+
+    lines.push(Line::from(""));
+    lines.push(Line::from(Span::styled(
+        theme.icons(
+            "↑↓/Tab: Field | ←→: Change script/function | Args: comma separated, JSON keeps its commas (\\, for a comma)",
+        ),
+        Style::default().fg(theme.subtle),
+    )));
+    lines.push(Line::from(Span::styled(
+        "Map over: array reference such as ${step_1}; leave empty for a plain call",
+        Style::default().fg(theme.subtle),
+    )));
+    lines.push(Line::from(Span::styled(
+        "Expect: '= 120; contains Hi; matches ^H.*; $.items[0] = 1; <= 50ms'",
+        Style::default().fg(theme.subtle),
+    )));
+    lines.push(Line::from(Span::styled(
+        "Enter: Save | Esc: Cancel | ${step_id} in args uses an earlier step's output",
+        Style::default().fg(theme.subtle),
+    )));
+
+    let title = theme.icons(if app.input_mode == InputMode::AddingStep {
+        "➕ Insert Step"
+    } else {
+        "✏️  Edit Step"
+    });
+
+    f.render_widget(Clear, area);
+    f.render_widget(
+        Paragraph::new(lines)
+            .block(
+                Block::default()
+                    .title(title)
+                    .borders(Borders::ALL)
+                    .border_style(Style::default().fg(theme.highlight))
+                    .padding(Padding::uniform(1)),
+            )
+            .wrap(Wrap { trim: false }),
+        area,
+    );
+}
+
+fn render_results(f: &mut Frame, app: &App, area: Rect) {
+    let theme = &app.theme;
     let chunks = Layout::default()
-        .direction(Direction::Horizontal)
-        .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(3),
+            Constraint::Percentage(40),
+            Constraint::Min(0),
+        ])
         .split(area);
 
-    let items: Vec<ListItem> = app
-        .scripts
+    let searching = app.input_mode == InputMode::SearchResults;
+    let filter = &app.results_filter;
+    let any = |value: &Option<String>| value.clone().unwrap_or_else(|| "any".to_string());
+    let mut bar = vec![
+        Span::styled("Status: ", Style::default().fg(theme.muted)),
+        Span::styled(filter.status.label(), Style::default().fg(theme.primary)),
+        Span::styled(" | Script: ", Style::default().fg(theme.muted)),
+        Span::styled(any(&filter.script), Style::default().fg(theme.primary)),
+        Span::styled(" | Function: ", Style::default().fg(theme.muted)),
+        Span::styled(any(&filter.function), Style::default().fg(theme.primary)),
+        Span::styled(" | Language: ", Style::default().fg(theme.muted)),
+        Span::styled(any(&filter.language), Style::default().fg(theme.primary)),
+        Span::styled(" | Sort: ", Style::default().fg(theme.muted)),
+        Span::styled(app.results_sort.label(), Style::default().fg(theme.primary)),
+        Span::styled(" | Search: ", Style::default().fg(theme.muted)),
+    ];
+    if searching {
+        bar.push(Span::styled(
+            format!("{}{}", filter.search, theme.icons("█")),
+            Style::default().fg(theme.highlight),
+        ));
+    } else {
+        bar.push(Span::styled(
+            filter.search.clone(),
+            Style::default().fg(theme.highlight),
+        ));
+    }
+
+    f.render_widget(
+        Paragraph::new(Line::from(bar)).block(
+            Block::default()
+                .title(theme.icons("🔎 Filters"))
+                .borders(Borders::ALL)
+                .border_style(Style::default().fg(if searching {
+                    theme.highlight
+                } else {
+                    theme.subtle
+                })),
+        ),
+        chunks[0],
+    );
+
+    let results = app.filtered_results();
+    let items: Vec<ListItem> = results
         .iter()
         .enumerate()
-        .map(|(i, script)| {
-            let icon = match script.language.as_str() {
-                "Python" => "🐍",
-                "JavaScript" => "📜",
-                "TypeScript" => "📘",
-                "Ruby" => "💎",
-                _ => "📄",
-            };
-
-            let status = if script.loaded {
-                "✓"
-            } else if script.error.is_some() {
-                "✗"
+        .map(|(i, r)| {
+            let color = if r.success {
+                theme.success
             } else {
-                "○"
+                theme.error
             };
-
-            let style = if i == app.selected_script {
+            let icon = if r.success { "✓" } else { "✗" };
+            let style = if i == app.selected_result {
                 Style::default()
-                    .fg(Color::Yellow)
+                    .fg(theme.highlight)
                     .add_modifier(Modifier::BOLD)
-            } else if script.loaded {
-                Style::default().fg(Color::Green)
-            } else if script.error.is_some() {
-                Style::default().fg(Color::Red)
             } else {
-                Style::default().fg(Color::Gray)
+                Style::default().fg(color)
             };
 
+            let marked = app
+                .compare_result
+                .and_then(|i| app.results.get(i))
+                .is_some_and(|m| std::ptr::eq(m, *r));
             ListItem::new(format!(
-                "{} {} {} [{}]",
-                status, icon, script.name, script.language
+                "{}{}{}{} [{}] {} - {}ms",
+                theme.icons(if marked { "⇄ " } else { "" }),
+                theme.icons(icon),
+                theme.icons(if r.replay_of.is_some() { " ↻" } else { "" }),
+                theme.icons(if r.changed { " Δ" } else { "" }),
+                app.config.display.time(&r.timestamp),
+                r.function,
+                r.duration_ms
             ))
             .style(style)
         })
         .collect();
 
-    f.render_widget(
+    let title = if filter.is_active() {
+        format!(
+            "📊 Results: {} ({} of {})",
+            filter.summary(),
+            results.len(),
+            app.results.len()
+        )
+    } else {
+        format!("📊 All Results ({})", app.results.len())
+    };
+    let mut state = ListState::default().with_selected(Some(app.selected_result));
+    f.render_stateful_widget(
         List::new(items)
             .block(
                 Block::default()
-                    .title("📂 Scripts")
+                    .title(theme.icons(&title))
                     .borders(Borders::ALL)
-                    .border_style(Style::default().fg(Color::Cyan)),
+                    .border_style(Style::default().fg(theme.primary)),
             )
-            .highlight_style(Style::default().bg(Color::DarkGray)),
-        chunks[0],
+            .highlight_style(Style::default().bg(theme.selection)),
+        chunks[1],
+        &mut state,
+    );
+    app.regions.borrow_mut().list(
+        mouse::List::Results,
+        chunks[1],
+        0,
+        state.offset(),
+        results.len(),
     );
 
-    let details = if !app.scripts.is_empty() {
-        let script = &app.scripts[app.selected_script];
+    if app.results_diff
+        && let Some(r) = results.get(app.selected_result)
+    {
+        render_result_diff(f, app, r, chunks[2]);
+        return;
+    }
+
+    let detail_text = if let Some(r) = results.get(app.selected_result) {
+        let color = if r.success {
+            theme.success
+        } else {
+            theme.error
+        };
+        let baseline = match app.baselines.get(&r.function, &r.args) {
+            Some(b) if !diff::outputs_differ(b, r) => ("Matches", theme.success),
+            Some(_) => ("Differs", theme.highlight),
+            None => ("None", theme.subtle),
+        };
+
         let mut lines = vec![
             Line::from(vec![
-                Span::styled("Name: ", Style::default().fg(Color::Gray)),
-                Span::styled(&script.name, Style::default().fg(Color::White)),
+                Span::styled("Function: ", Style::default().fg(theme.muted)),
+                Span::styled(&r.function, Style::default().fg(theme.text)),
             ]),
             Line::from(vec![
-                Span::styled("Language: ", Style::default().fg(Color::Gray)),
-                Span::styled(&script.language, Style::default().fg(Color::Cyan)),
+                Span::styled("Arguments: ", Style::default().fg(theme.muted)),
+                Span::styled(format!("{:?}", r.args), Style::default().fg(theme.primary)),
             ]),
             Line::from(vec![
-                Span::styled("Path: ", Style::default().fg(Color::Gray)),
+                Span::styled("Duration: ", Style::default().fg(theme.muted)),
                 Span::styled(
-                    script.path.display().to_string(),
-                    Style::default().fg(Color::DarkGray),
+                    format!("{}ms", r.duration_ms),
+                    Style::default().fg(theme.highlight),
                 ),
             ]),
-            Line::from(""),
+            Line::from(vec![
+                Span::styled("Timestamp: ", Style::default().fg(theme.muted)),
+                Span::styled(
+                    time::format(&r.timestamp, "rfc3339", app.config.display.utc),
+                    Style::default().fg(theme.subtle),
+                ),
+            ]),
+            Line::from(vec![
+                Span::styled("Status: ", Style::default().fg(theme.muted)),
+                Span::styled(
+                    if r.success { "Success" } else { "Failed" },
+                    Style::default().fg(color),
+                ),
+            ]),
+            Line::from(vec![
+                Span::styled("Baseline: ", Style::default().fg(theme.muted)),
+                Span::styled(baseline.0, Style::default().fg(baseline.1)),
+            ]),
         ];
-
-        if let Some(error) = &script.error {
+        if let Some(original) = r.replay_of {
             lines.push(Line::from(vec![
-                Span::styled("Error: ", Style::default().fg(Color::Red)),
-                Span::styled(error, Style::default().fg(Color::Red)),
+                Span::styled("Replay of: ", Style::default().fg(theme.muted)),
+                Span::styled(
+                    app.config.display.time(&original),
+                    Style::default().fg(theme.primary),
+                ),
+                Span::styled(
+                    match app.replayed_result(r) {
+                        Some(o) if diff::outputs_differ(o, r) => " (output differs, d: diff)",
+                        Some(_) => " (same output)",
+                        None => " (no longer in the results)",
+                    },
+                    Style::default().fg(theme.subtle),
+                ),
             ]));
-        } else if script.loaded {
-            lines.push(Line::from(vec![Span::styled(
-                format!("Functions ({}): ", script.functions.len()),
-                Style::default().fg(Color::Gray),
-            )]));
-
-            for func in &script.functions {
-                lines.push(Line::from(format!("  • {}", func)));
-            }
-        } else {
+        }
+        if r.changed {
             lines.push(Line::from(Span::styled(
-                "Press 'l' to load this script",
-                Style::default().fg(Color::Yellow),
+                theme.icons(
+                    "Δ Output differed from the baseline or previous result when recorded (d: diff)",
+                ),
+                Style::default().fg(theme.highlight),
             )));
         }
+        lines.extend([
+            Line::from(""),
+            Line::from(Span::styled("Output:", Style::default().fg(theme.muted))),
+            Line::from(Span::styled(&r.output, Style::default().fg(color))),
+        ]);
+        lines
+    } else {
+        vec![Line::from(Span::styled(
+            if app.results.is_empty() {
+                "No results yet"
+            } else {
+                "No results match the filters"
+            },
+            Style::default().fg(theme.muted),
+        ))]
+    };
 
-        Paragraph::new(lines)
+    f.render_widget(
+        Paragraph::new(detail_text)
             .block(
                 Block::default()
-                    .title("📋 Details")
+                    .title(theme.icons("🔍 Details"))
                     .borders(Borders::ALL)
-                    .border_style(Style::default().fg(Color::Cyan))
+                    .border_style(Style::default().fg(theme.primary))
                     .padding(Padding::uniform(1)),
             )
+            .wrap(Wrap { trim: true }),
+        chunks[2],
+    );
+}
+
+/// The selected result side by side with what it is diffed against, and the
+/// structural changes between their values.
+fn render_result_diff(f: &mut Frame, app: &App, selected: &ExecutionResult, area: Rect) {
+    let theme = &app.theme;
+    let Some((label, reference)) = app.diff_reference(selected) else {
+        f.render_widget(
+            Paragraph::new(
+                "Nothing to compare with: mark a result with m, save a baseline with b, or record the same call again",
+            )
+            .style(Style::default().fg(theme.muted))
             .wrap(Wrap { trim: true })
-    } else {
-        Paragraph::new("No scripts found")
             .block(
                 Block::default()
-                    .title("📋 Details")
+                    .title(theme.icons("⇄ Diff"))
                     .borders(Borders::ALL)
-                    .border_style(Style::default().fg(Color::Cyan)),
-            )
-            .alignment(Alignment::Center)
+                    .border_style(Style::default().fg(theme.primary)),
+            ),
+            area,
+        );
+        return;
     };
 
-    f.render_widget(details, chunks[1]);
-}
+    let old = reference.output_value();
+    let new = selected.output_value();
+    let mut changes = diff::diff(&old, &new);
+    if reference.success != selected.success {
+        changes.insert(
+            0,
+            diff::Change {
+                path: "status".to_string(),
+                old: Some(Value::Bool(reference.success)),
+                new: Some(Value::Bool(selected.success)),
+            },
+        );
+    }
 
-fn render_function_tester(f: &mut Frame, app: &App, area: Rect) {
     let chunks = Layout::default()
         .direction(Direction::Vertical)
         .constraints([
-            Constraint::Length(10),
-            Constraint::Length(8),
-            Constraint::Min(0),
+            Constraint::Min(3),
+            Constraint::Length(changes.len().clamp(1, 8) as u16 + 2),
         ])
         .split(area);
+    let sides = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
+        .split(chunks[0]);
 
-    if !app.scripts.is_empty() && app.selected_script < app.scripts.len() {
-        let script = &app.scripts[app.selected_script];
+    let side = |title: String, result: &ExecutionResult, value: &Value| {
+        let text = serde_json::to_string_pretty(value).unwrap_or_else(|_| result.output.clone());
+        Paragraph::new(text)
+            .style(Style::default().fg(if result.success {
+                theme.text
+            } else {
+                theme.error
+            }))
+            .block(
+                Block::default()
+                    .title(title)
+                    .borders(Borders::ALL)
+                    .border_style(Style::default().fg(theme.primary)),
+            )
+    };
+    f.render_widget(
+        side(
+            format!("{} ({}ms)", label, reference.duration_ms),
+            reference,
+            &old,
+        ),
+        sides[0],
+    );
+    f.render_widget(
+        side(
+            format!(
+                "selected [{}] ({}ms)",
+                app.config.display.time(&selected.timestamp),
+                selected.duration_ms
+            ),
+            selected,
+            &new,
+        ),
+        sides[1],
+    );
 
-        let func_items: Vec<ListItem> = script
-            .functions
+    let lines: Vec<Line> = if changes.is_empty() {
+        vec![Line::from(Span::styled(
+            "No differences",
+            Style::default().fg(theme.success),
+        ))]
+    } else {
+        changes
             .iter()
-            .enumerate()
-            .map(|(i, func)| {
-                let style = if i == app.function_input.selected_function {
-                    Style::default()
-                        .fg(Color::Yellow)
-                        .add_modifier(Modifier::BOLD)
-                } else {
-                    Style::default().fg(Color::White)
-                };
-                ListItem::new(format!("{}()", func)).style(style)
+            .map(|change| match (&change.old, &change.new) {
+                (Some(old), Some(new)) => Line::from(Span::styled(
+                    format!("~ {}: {} {} {}", change.path, old, theme.icons("→"), new),
+                    Style::default().fg(theme.highlight),
+                )),
+                (None, Some(new)) => Line::from(Span::styled(
+                    format!("+ {}: {}", change.path, new),
+                    Style::default().fg(theme.success),
+                )),
+                (Some(old), None) => Line::from(Span::styled(
+                    format!("- {}: {}", change.path, old),
+                    Style::default().fg(theme.error),
+                )),
+                (None, None) => Line::from(change.path.clone()),
             })
-            .collect();
+            .collect()
+    };
+    f.render_widget(
+        Paragraph::new(lines).block(
+            Block::default()
+                .title(
+                    theme
+                        .icons(&format!("⇄ Changes ({})", changes.len()))
+                        .into_owned(),
+                )
+                .borders(Borders::ALL)
+                .border_style(Style::default().fg(theme.primary)),
+        ),
+        chunks[1],
+    );
+}
 
-        f.render_widget(
-            List::new(func_items).block(
-                Block::default()
-                    .title(format!("🔧 Functions - {}", script.name))
-                    .borders(Borders::ALL)
-                    .border_style(Style::default().fg(Color::Cyan))
-                    .padding(Padding::horizontal(1)),
-            ),
-            chunks[0],
-        );
-    }
+fn render_statistics(f: &mut Frame, app: &App, area: Rect) {
+    let theme = &app.theme;
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(3),
+            Constraint::Percentage(50),
+            Constraint::Min(0),
+        ])
+        .split(area);
 
-    let mut arg_lines = vec![
-        Line::from(vec![
-            Span::styled("Arguments: ", Style::default().fg(Color::Gray)),
+    let overall = stats::overall(&app.results);
+    let summary = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Percentage(60), Constraint::Percentage(40)])
+        .split(chunks[0]);
+    f.render_widget(
+        Paragraph::new(Line::from(vec![
+            Span::styled("Calls: ", Style::default().fg(theme.muted)),
             Span::styled(
-                format!("{:?}", app.function_input.args),
-                Style::default().fg(Color::Cyan),
+                overall.calls.to_string(),
+                Style::default().fg(theme.primary),
             ),
-        ]),
-        Line::from(""),
-    ];
-
-    if app.input_mode == InputMode::EditingArgs {
-        arg_lines.push(Line::from(vec![
-            Span::styled(">> ", Style::default().fg(Color::Yellow)),
-            Span::styled(&app.input_buffer, Style::default().fg(Color::White)),
+            Span::styled(" | Failed: ", Style::default().fg(theme.muted)),
             Span::styled(
-                "_",
-                Style::default()
-                    .fg(Color::Yellow)
-                    .add_modifier(Modifier::SLOW_BLINK),
+                (overall.calls - overall.passed).to_string(),
+                Style::default().fg(theme.error),
             ),
-        ]));
-    } else {
-        arg_lines.push(Line::from(Span::styled(
-            "Press 'a' to add arguments, 'Enter' to execute",
-            Style::default().fg(Color::DarkGray),
-        )));
-    }
-
+            Span::styled(" | Avg: ", Style::default().fg(theme.muted)),
+            Span::styled(
+                format!("{}ms", overall.avg_ms()),
+                Style::default().fg(theme.highlight),
+            ),
+            Span::styled(" | Max: ", Style::default().fg(theme.muted)),
+            Span::styled(
+                format!("{}ms", overall.max_ms),
+                Style::default().fg(theme.highlight),
+            ),
+        ]))
+        .block(
+            Block::default()
+                .title(theme.icons("📈 Statistics"))
+                .borders(Borders::ALL)
+                .border_style(Style::default().fg(theme.primary)),
+        ),
+        summary[0],
+    );
     f.render_widget(
-        Paragraph::new(arg_lines).block(
+        Gauge::default()
+            .block(
+                Block::default()
+                    .title("Success Rate")
+                    .borders(Borders::ALL)
+                    .border_style(Style::default().fg(theme.primary)),
+            )
+            .gauge_style(Style::default().fg(theme.success).bg(theme.error))
+            .ratio(if overall.calls == 0 {
+                0.0
+            } else {
+                overall.success_rate() / 100.0
+            })
+            .label(format!("{:.1}%", overall.success_rate())),
+        summary[1],
+    );
+
+    let top = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Percentage(55), Constraint::Percentage(45)])
+        .split(chunks[1]);
+
+    let functions = stats::by_function(&app.results);
+    let rows = functions.iter().enumerate().map(|(i, s)| {
+        let style = if i == app.selected_stat {
+            Style::default()
+                .fg(theme.highlight)
+                .add_modifier(Modifier::BOLD)
+        } else {
+            Style::default().fg(theme.text)
+        };
+        let rate_color = if s.passed == s.calls {
+            theme.success
+        } else {
+            theme.error
+        };
+        Row::new(vec![
+            Cell::from(s.name.clone()),
+            Cell::from(s.calls.to_string()),
+            Cell::from(format!("{:.0}%", s.success_rate())).style(Style::default().fg(rate_color)),
+            Cell::from(format!("{}ms", s.avg_ms())),
+            Cell::from(format!("{}ms", s.max_ms)),
+        ])
+        .style(style)
+    });
+    let mut state = TableState::default().with_selected(Some(app.selected_stat));
+    f.render_stateful_widget(
+        Table::new(
+            rows,
+            [
+                Constraint::Min(16),
+                Constraint::Length(6),
+                Constraint::Length(6),
+                Constraint::Length(9),
+                Constraint::Length(9),
+            ],
+        )
+        .header(
+            Row::new(vec!["Function", "Calls", "OK", "Avg", "Max"])
+                .style(Style::default().fg(theme.muted)),
+        )
+        .row_highlight_style(Style::default().bg(theme.selection))
+        .block(
             Block::default()
-                .title("⚙️  Input")
+                .title(
+                    theme
+                        .icons(&format!("🔧 Functions ({})", functions.len()))
+                        .into_owned(),
+                )
                 .borders(Borders::ALL)
-                .border_style(Style::default().fg(Color::Cyan))
-                .padding(Padding::uniform(1)),
+                .border_style(Style::default().fg(theme.primary)),
         ),
-        chunks[1],
+        top[0],
+        &mut state,
+    );
+    app.regions.borrow_mut().list(
+        mouse::List::Stats,
+        top[0],
+        1,
+        state.offset(),
+        functions.len(),
     );
 
-    let result_items: Vec<ListItem> = app
+    render_latency_chart(f, app, functions.get(app.selected_stat), top[1]);
+
+    let bottom = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([
+            Constraint::Percentage(34),
+            Constraint::Percentage(30),
+            Constraint::Percentage(36),
+        ])
+        .split(chunks[2]);
+
+    // As many of the latest calls as fit between the borders.
+    let width = bottom[0].width.saturating_sub(2) as usize;
+    let latencies: Vec<u64> = app
         .results
         .iter()
-        .rev()
-        .take(10)
-        .map(|r| {
-            let color = if r.success { Color::Green } else { Color::Red };
-            let icon = if r.success { "✓" } else { "✗" };
-
-            ListItem::new(vec![
-                Line::from(vec![
-                    Span::styled(format!("{} ", icon), Style::default().fg(color)),
-                    Span::styled(&r.function, Style::default().fg(Color::White)),
-                    Span::styled(
-                        format!(" ({}ms)", r.duration_ms),
-                        Style::default().fg(Color::DarkGray),
-                    ),
-                ]),
-                Line::from(vec![
-                    Span::styled("  Args: ", Style::default().fg(Color::Gray)),
-                    Span::styled(
-                        format!("{:?}", r.args),
-                        Style::default().fg(Color::DarkGray),
-                    ),
-                ]),
-                Line::from(vec![
-                    Span::styled("  Result: ", Style::default().fg(Color::Gray)),
-                    Span::styled(&r.output, Style::default().fg(color)),
-                ]),
-            ])
-        })
+        .skip(app.results.len().saturating_sub(width))
+        .map(|r| r.duration_ms)
         .collect();
+    f.render_widget(
+        Sparkline::default()
+            .block(
+                Block::default()
+                    .title(theme.icons("⏱ Latest Calls"))
+                    .borders(Borders::ALL)
+                    .border_style(Style::default().fg(theme.primary)),
+            )
+            .data(&latencies)
+            .bar_set(theme.bar_set())
+            .style(Style::default().fg(theme.highlight)),
+        bottom[0],
+    );
 
+    let languages = stats::by_language(&app.results, |r| app.result_language(r));
+    let rows = languages.iter().map(|s| {
+        Row::new(vec![
+            s.name.clone(),
+            s.calls.to_string(),
+            format!("{:.0}%", s.success_rate()),
+            format!("{}ms", s.avg_ms()),
+        ])
+    });
     f.render_widget(
-        List::new(result_items).block(
+        Table::new(
+            rows,
+            [
+                Constraint::Min(10),
+                Constraint::Length(6),
+                Constraint::Length(5),
+                Constraint::Length(8),
+            ],
+        )
+        .header(
+            Row::new(vec!["Language", "Calls", "OK", "Avg"])
+                .style(Style::default().fg(theme.muted)),
+        )
+        .block(
             Block::default()
-                .title("📊 Recent Results")
+                .title(theme.icons("🌐 Languages"))
                 .borders(Borders::ALL)
-                .border_style(Style::default().fg(Color::Cyan))
-                .padding(Padding::horizontal(1)),
+                .border_style(Style::default().fg(theme.primary)),
         ),
-        chunks[2],
+        bottom[1],
     );
-}
 
-fn render_pipeline_builder(f: &mut Frame, app: &App, area: Rect) {
-    let chunks = Layout::default()
-        .direction(Direction::Vertical)
-        .constraints([Constraint::Percentage(70), Constraint::Percentage(30)])
-        .split(area);
-
-    let rows: Vec<Row> = app
-        .pipeline
-        .iter()
-        .enumerate()
-        .map(|(i, step)| {
-            let style = if i == app.selected_pipeline_step {
-                Style::default().fg(Color::Yellow)
+    let slowest: Vec<ListItem> = stats::slowest(&app.results, bottom[2].height as usize)
+        .into_iter()
+        .map(|r| {
+            ListItem::new(format!(
+                "{:>6}ms {}({})",
+                r.duration_ms,
+                r.function,
+                r.args.join(", ")
+            ))
+            .style(Style::default().fg(if r.passed() {
+                theme.text
             } else {
-                Style::default().fg(Color::White)
-            };
-
-            Row::new(vec![
-                Cell::from(step.id.clone()),
-                Cell::from(step.script.clone()),
-                Cell::from(step.function.clone()),
-                Cell::from(format!("{:?}", step.args)),
-            ])
-            .style(style)
+                theme.error
+            }))
         })
         .collect();
-
-    let table = Table::new(
-        rows,
-        [
-            Constraint::Length(10),
-            Constraint::Length(20),
-            Constraint::Length(20),
-            Constraint::Min(20),
-        ],
-    )
-    .header(
-        Row::new(vec!["Step", "Script", "Function", "Arguments"]).style(
-            Style::default()
-                .fg(Color::Cyan)
-                .add_modifier(Modifier::BOLD),
+    f.render_widget(
+        List::new(slowest).block(
+            Block::default()
+                .title(theme.icons("🐢 Slowest Calls"))
+                .borders(Borders::ALL)
+                .border_style(Style::default().fg(theme.primary)),
         ),
-    )
-    .block(
-        Block::default()
-            .title("🔗 Pipeline Steps")
-            .borders(Borders::ALL)
-            .border_style(Style::default().fg(Color::Cyan)),
-    )
-    .row_highlight_style(Style::default().bg(Color::DarkGray));
+        bottom[2],
+    );
+}
 
-    f.render_widget(table, chunks[0]);
+/// Latency of every call of one function over time, in seconds since its
+/// first call, with failed calls and calls that missed an expectation marked
+/// in red.
+fn render_latency_chart(f: &mut Frame, app: &App, function: Option<&stats::CallStats>, area: Rect) {
+    let theme = &app.theme;
+    let block = Block::default()
+        .title(match function {
+            Some(s) => format!("{}{}", theme.icons("📉 Latency: "), s.name),
+            None => theme.icons("📉 Latency").into_owned(),
+        })
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(theme.primary));
+    let Some(function) = function else {
+        f.render_widget(
+            Paragraph::new("No calls yet")
+                .style(Style::default().fg(theme.muted))
+                .block(block),
+            area,
+        );
+        return;
+    };
 
-    let info_text = if app.pipeline.is_empty() {
-        vec![
-            Line::from(Span::styled(
-                "No steps in pipeline",
-                Style::default().fg(Color::Gray),
-            )),
-            Line::from(""),
-            Line::from(Span::styled(
-                "Go to Function Tester (Tab) and press 'p' to add steps",
-                Style::default().fg(Color::Yellow),
-            )),
-        ]
-    } else {
-        vec![
-            Line::from(vec![
-                Span::styled("Total Steps: ", Style::default().fg(Color::Gray)),
-                Span::styled(
-                    app.pipeline.len().to_string(),
-                    Style::default().fg(Color::Cyan),
-                ),
-            ]),
-            Line::from(""),
-            Line::from(Span::styled(
-                "Press 'x' to execute pipeline",
-                Style::default().fg(Color::Green),
-            )),
-            Line::from(Span::styled(
-                "Press 'd' to delete selected step",
-                Style::default().fg(Color::Red),
-            )),
-            Line::from(Span::styled(
-                "Press 'c' to clear all steps",
-                Style::default().fg(Color::Yellow),
-            )),
-        ]
+    let calls: Vec<&ExecutionResult> = app
+        .results
+        .iter()
+        .filter(|r| r.function == function.name)
+        .collect();
+    let (first, last) = match (calls.first(), calls.last()) {
+        (Some(first), Some(last)) => (first.timestamp, last.timestamp),
+        _ => return,
     };
+    let points: Vec<(f64, f64)> = calls
+        .iter()
+        .map(|r| {
+            let seconds = (r.timestamp - first).num_milliseconds() as f64 / 1000.0;
+            (seconds, r.duration_ms as f64)
+        })
+        .collect();
+    let failures: Vec<(f64, f64)> = calls
+        .iter()
+        .zip(&points)
+        .filter(|(r, _)| !r.passed())
+        .map(|(_, point)| *point)
+        .collect();
 
+    let max_x = points.iter().map(|(x, _)| *x).fold(1.0, f64::max);
+    let max_y = (function.max_ms.max(1) as f64 * 1.1).ceil();
+    let datasets = vec![
+        Dataset::default()
+            .name("ms")
+            .marker(theme.marker())
+            .graph_type(GraphType::Line)
+            .style(Style::default().fg(theme.highlight))
+            .data(&points),
+        Dataset::default()
+            .name("failed")
+            .marker(symbols::Marker::Dot)
+            .graph_type(GraphType::Scatter)
+            .style(Style::default().fg(theme.error))
+            .data(&failures),
+    ];
     f.render_widget(
-        Paragraph::new(info_text)
-            .block(
-                Block::default()
-                    .title("ℹ️  Info")
-                    .borders(Borders::ALL)
-                    .border_style(Style::default().fg(Color::Cyan))
-                    .padding(Padding::uniform(1)),
+        Chart::new(datasets)
+            .block(block)
+            .x_axis(
+                Axis::default()
+                    .title("time")
+                    .style(Style::default().fg(theme.muted))
+                    .bounds([0.0, max_x])
+                    .labels([
+                        app.config.display.time(&first),
+                        app.config.display.time(&last),
+                    ]),
             )
-            .alignment(Alignment::Left),
-        chunks[1],
+            .y_axis(
+                Axis::default()
+                    .title("ms")
+                    .style(Style::default().fg(theme.muted))
+                    .bounds([0.0, max_y])
+                    .labels(["0".to_string(), format!("{}", max_y as u64)]),
+            ),
+        area,
     );
 }
 
-fn render_results(f: &mut Frame, app: &App, area: Rect) {
+fn log_style(theme: &theme::Theme, level: LogLevel) -> (&'static str, Color) {
+    match level {
+        LogLevel::Info if theme.ascii => ("i", theme.primary),
+        LogLevel::Info => ("ℹ️", theme.primary),
+        LogLevel::Success => ("✓", theme.success),
+        LogLevel::Error => ("✗", theme.error),
+        LogLevel::Warning => ("⚠", theme.highlight),
+    }
+}
+
+fn log_line<'a>(app: &App, entry: &'a LogEntry) -> Line<'a> {
+    let theme = &app.theme;
+    let (icon, color) = log_style(theme, entry.level);
+    Line::from(vec![
+        Span::styled(
+            format!("[{}] ", app.config.display.time(&entry.timestamp)),
+            Style::default().fg(theme.subtle),
+        ),
+        Span::styled(
+            format!("{} ", theme.icons(icon)),
+            Style::default().fg(color),
+        ),
+        Span::styled(theme.icons(&entry.message), Style::default().fg(color)),
+    ])
+}
+
+fn render_logs(f: &mut Frame, app: &App, area: Rect) {
+    let theme = &app.theme;
     let chunks = Layout::default()
         .direction(Direction::Vertical)
-        .constraints([Constraint::Percentage(40), Constraint::Percentage(60)])
+        .constraints([
+            Constraint::Length(3),
+            Constraint::Min(0),
+            Constraint::Length(6),
+        ])
         .split(area);
 
-    let items: Vec<ListItem> = app
-        .results
-        .iter()
-        .rev()
-        .enumerate()
-        .map(|(i, r)| {
-            let color = if r.success { Color::Green } else { Color::Red };
-            let icon = if r.success { "✓" } else { "✗" };
-            let style = if i == app.selected_result {
-                Style::default()
-                    .fg(Color::Yellow)
-                    .add_modifier(Modifier::BOLD)
-            } else {
-                Style::default().fg(color)
-            };
-
-            ListItem::new(format!(
-                "{} [{}] {} - {}ms",
-                icon, r.timestamp, r.function, r.duration_ms
-            ))
-            .style(style)
-        })
-        .collect();
+    let searching = app.input_mode == InputMode::SearchLogs;
+    let mut bar = vec![Span::styled("Levels: ", Style::default().fg(theme.muted))];
+    for level in LogLevel::ALL {
+        let (_, color) = log_style(theme, level);
+        let (mark, style) = if app.log_filter.shows(level) {
+            ("■", Style::default().fg(color))
+        } else {
+            ("□", Style::default().fg(theme.subtle))
+        };
+        bar.push(Span::styled(
+            format!("{} {}  ", theme.icons(mark), level.label()),
+            style,
+        ));
+    }
+    bar.push(Span::styled("| Search: ", Style::default().fg(theme.muted)));
+    bar.push(Span::styled(
+        if searching {
+            format!("{}{}", app.log_filter.search, theme.icons("█"))
+        } else {
+            app.log_filter.search.clone()
+        },
+        Style::default().fg(theme.highlight),
+    ));
 
     f.render_widget(
+        Paragraph::new(Line::from(bar)).block(
+            Block::default()
+                .title(theme.icons("🔎 Filters"))
+                .borders(Borders::ALL)
+                .border_style(Style::default().fg(if searching {
+                    theme.highlight
+                } else {
+                    theme.subtle
+                })),
+        ),
+        chunks[0],
+    );
+
+    let entries = app.filtered_logs();
+    let selected = app
+        .selected_log
+        .unwrap_or(entries.len().saturating_sub(1))
+        .min(entries.len().saturating_sub(1));
+    let items: Vec<ListItem> = entries
+        .iter()
+        .map(|entry| ListItem::new(log_line(app, entry)))
+        .collect();
+    let title = format!(
+        "📝 Logs ({}/{}, buffer {}){}",
+        entries.len(),
+        app.logs.len(),
+        app.logs.capacity(),
+        if app.selected_log.is_none() {
+            " - following"
+        } else {
+            ""
+        }
+    );
+    let mut state = ListState::default().with_selected((!entries.is_empty()).then_some(selected));
+    f.render_stateful_widget(
         List::new(items)
             .block(
                 Block::default()
-                    .title(format!("📊 All Results ({})", app.results.len()))
+                    .title(theme.icons(&title))
                     .borders(Borders::ALL)
-                    .border_style(Style::default().fg(Color::Cyan)),
+                    .border_style(Style::default().fg(theme.primary)),
             )
-            .highlight_style(Style::default().bg(Color::DarkGray)),
-        chunks[0],
+            .highlight_style(Style::default().add_modifier(Modifier::REVERSED)),
+        chunks[1],
+        &mut state,
+    );
+    app.regions.borrow_mut().list(
+        mouse::List::Logs,
+        chunks[1],
+        0,
+        state.offset(),
+        entries.len(),
     );
 
-    let detail_text = if !app.results.is_empty() && app.selected_result < app.results.len() {
-        let r = &app.results[app.results.len() - 1 - app.selected_result];
-        let color = if r.success { Color::Green } else { Color::Red };
-
-        vec![
-            Line::from(vec![
-                Span::styled("Function: ", Style::default().fg(Color::Gray)),
-                Span::styled(&r.function, Style::default().fg(Color::White)),
-            ]),
-            Line::from(vec![
-                Span::styled("Arguments: ", Style::default().fg(Color::Gray)),
-                Span::styled(format!("{:?}", r.args), Style::default().fg(Color::Cyan)),
-            ]),
-            Line::from(vec![
-                Span::styled("Duration: ", Style::default().fg(Color::Gray)),
-                Span::styled(
-                    format!("{}ms", r.duration_ms),
-                    Style::default().fg(Color::Yellow),
-                ),
-            ]),
-            Line::from(vec![
-                Span::styled("Timestamp: ", Style::default().fg(Color::Gray)),
-                Span::styled(&r.timestamp, Style::default().fg(Color::DarkGray)),
-            ]),
-            Line::from(vec![
-                Span::styled("Status: ", Style::default().fg(Color::Gray)),
-                Span::styled(
-                    if r.success { "Success" } else { "Failed" },
-                    Style::default().fg(color),
-                ),
-            ]),
-            Line::from(""),
-            Line::from(Span::styled("Output:", Style::default().fg(Color::Gray))),
-            Line::from(Span::styled(&r.output, Style::default().fg(color))),
-        ]
-    } else {
-        vec![Line::from(Span::styled(
-            "No results yet",
-            Style::default().fg(Color::Gray),
-        ))]
+    let details = match entries.get(selected) {
+        Some(entry) => {
+            let (_, color) = log_style(theme, entry.level);
+            vec![
+                Line::from(vec![
+                    Span::styled(
+                        format!("{} ", time::rfc3339(&entry.timestamp)),
+                        Style::default().fg(theme.subtle),
+                    ),
+                    Span::styled(entry.level.label(), Style::default().fg(color)),
+                ]),
+                Line::from(theme.icons(&entry.message)),
+            ]
+        }
+        None if app.log_filter.is_active() => vec![Line::from("No log entries match the filters")],
+        None => vec![Line::from("Nothing logged yet")],
+    };
+    let file = match &app.log_file {
+        Some(file) => format!("🔍 Entry - {}", file.path().display()),
+        None => "🔍 Entry".to_string(),
     };
-
     f.render_widget(
-        Paragraph::new(detail_text)
+        Paragraph::new(details)
             .block(
                 Block::default()
-                    .title("🔍 Details")
+                    .title(theme.icons(&file))
                     .borders(Borders::ALL)
-                    .border_style(Style::default().fg(Color::Cyan))
-                    .padding(Padding::uniform(1)),
+                    .border_style(Style::default().fg(theme.subtle)),
             )
-            .wrap(Wrap { trim: true }),
-        chunks[1],
+            .wrap(Wrap { trim: false }),
+        chunks[2],
     );
 }
 
 fn render_export(f: &mut Frame, app: &App, area: Rect) {
+    let theme = &app.theme;
     let chunks = Layout::default()
         .direction(Direction::Vertical)
         .constraints([Constraint::Length(5), Constraint::Min(0)])
@@ -1033,117 +4022,578 @@ fn render_export(f: &mut Frame, app: &App, area: Rect) {
 
     let stats = vec![
         Line::from(vec![
-            Span::styled("Scripts Loaded: ", Style::default().fg(Color::Gray)),
+            Span::styled("Scripts Loaded: ", Style::default().fg(theme.muted)),
             Span::styled(
                 app.scripts.iter().filter(|s| s.loaded).count().to_string(),
-                Style::default().fg(Color::Green),
+                Style::default().fg(theme.success),
             ),
-            Span::styled(" / ", Style::default().fg(Color::Gray)),
+            Span::styled(" / ", Style::default().fg(theme.muted)),
             Span::styled(
                 app.scripts.len().to_string(),
-                Style::default().fg(Color::Cyan),
+                Style::default().fg(theme.primary),
             ),
         ]),
         Line::from(vec![
-            Span::styled("Pipeline Steps: ", Style::default().fg(Color::Gray)),
+            Span::styled("Pipeline Steps: ", Style::default().fg(theme.muted)),
             Span::styled(
                 app.pipeline.len().to_string(),
-                Style::default().fg(Color::Yellow),
+                Style::default().fg(theme.highlight),
             ),
         ]),
         Line::from(vec![
-            Span::styled("Total Executions: ", Style::default().fg(Color::Gray)),
+            Span::styled("Total Executions: ", Style::default().fg(theme.muted)),
             Span::styled(
                 app.results.len().to_string(),
-                Style::default().fg(Color::Cyan),
+                Style::default().fg(theme.primary),
+            ),
+        ]),
+    ];
+
+    f.render_widget(
+        Paragraph::new(stats).block(
+            Block::default()
+                .title(theme.icons("📈 Statistics"))
+                .borders(Borders::ALL)
+                .border_style(Style::default().fg(theme.primary))
+                .padding(Padding::uniform(1)),
+        ),
+        chunks[0],
+    );
+
+    let body = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Length(34), Constraint::Min(0)])
+        .split(chunks[1]);
+    let sidebar = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(ExportFormat::ALL.len() as u16 + 2),
+            Constraint::Min(0),
+        ])
+        .split(body[0]);
+
+    let formats: Vec<ListItem> = ExportFormat::ALL
+        .iter()
+        .map(|format| {
+            let style = if *format == app.export_format {
+                Style::default()
+                    .fg(theme.highlight)
+                    .add_modifier(Modifier::BOLD)
+            } else {
+                Style::default().fg(theme.muted)
+            };
+            ListItem::new(format.label()).style(style)
+        })
+        .collect();
+
+    f.render_widget(
+        List::new(formats).block(
+            Block::default()
+                .title(theme.icons("🗂 Format"))
+                .borders(Borders::ALL)
+                .border_style(Style::default().fg(theme.primary)),
+        ),
+        sidebar[0],
+    );
+
+    let destination = vec![
+        Line::from(Span::styled(
+            "Output directory:",
+            Style::default().fg(theme.muted),
+        )),
+        Line::from(Span::styled(
+            app.export_output_dir.display().to_string(),
+            Style::default().fg(theme.primary),
+        )),
+        Line::from(""),
+        Line::from(vec![
+            Span::styled("Script paths: ", Style::default().fg(theme.muted)),
+            Span::styled(
+                if app.export_relative_paths {
+                    "relative"
+                } else {
+                    "absolute"
+                },
+                Style::default().fg(theme.primary),
+            ),
+        ]),
+    ];
+
+    f.render_widget(
+        Paragraph::new(destination)
+            .block(
+                Block::default()
+                    .title(theme.icons("📁 Destination"))
+                    .borders(Borders::ALL)
+                    .border_style(Style::default().fg(theme.primary)),
+            )
+            .wrap(Wrap { trim: false }),
+        sidebar[1],
+    );
+
+    let (path, code) = app.export_pipeline();
+    let prompt = match app.input_mode {
+        InputMode::ExportName => Some(match app.export_format.extension() {
+            Some(extension) => format!("Enter file name (saved as <name>.{}):", extension),
+            None => "Enter project directory name:".to_string(),
+        }),
+        InputMode::ExportDir => Some("Enter output directory:".to_string()),
+        _ => None,
+    };
+
+    let (export_text, scroll): (Vec<Line>, u16) = match prompt {
+        Some(prompt) => (
+            vec![
+                Line::from(Span::styled(prompt, Style::default().fg(theme.highlight))),
+                Line::from(""),
+                Line::from(vec![
+                    Span::styled(">> ", Style::default().fg(theme.highlight)),
+                    Span::styled(&app.input_buffer, Style::default().fg(theme.text)),
+                    Span::styled(
+                        "_",
+                        Style::default()
+                            .fg(theme.highlight)
+                            .add_modifier(Modifier::SLOW_BLINK),
+                    ),
+                ]),
+            ],
+            0,
+        ),
+        None => (
+            code.lines()
+                .map(|line| {
+                    Line::from(Span::styled(
+                        line.to_owned(),
+                        Style::default().fg(theme.text),
+                    ))
+                })
+                .collect(),
+            app.export_scroll,
+        ),
+    };
+
+    let file_name = path
+        .strip_prefix(&app.export_output_dir)
+        .unwrap_or(&path)
+        .display()
+        .to_string();
+
+    f.render_widget(
+        Paragraph::new(export_text)
+            .block(
+                Block::default()
+                    .title(
+                        theme
+                            .icons(&format!("📤 Export Preview: {}", file_name))
+                            .into_owned(),
+                    )
+                    .borders(Borders::ALL)
+                    .border_style(Style::default().fg(theme.primary))
+                    .padding(Padding::uniform(1)),
+            )
+            .wrap(Wrap { trim: false })
+            .scroll((scroll, 0)),
+        body[1],
+    );
+}
+
+fn render_results_export(f: &mut Frame, app: &App) {
+    let theme = &app.theme;
+    let area = centered_rect(60, 50, f.area());
+    let shown = app.filtered_results().len();
+
+    let mut lines = vec![Line::from(Span::styled(
+        "Format:",
+        Style::default().fg(theme.muted),
+    ))];
+    lines.extend(ResultsFormat::ALL.iter().map(|format| {
+        if *format == app.results_export_format {
+            Line::from(Span::styled(
+                format!("  {} {}", theme.icons("▶"), format.label()),
+                Style::default()
+                    .fg(theme.highlight)
+                    .add_modifier(Modifier::BOLD),
+            ))
+        } else {
+            Line::from(Span::styled(
+                format!("    {}", format.label()),
+                Style::default().fg(theme.text),
+            ))
+        }
+    }));
+
+    let scope = if app.results_export_all {
+        format!("All results ({})", app.results.len())
+    } else {
+        format!(
+            "Results shown: {} ({})",
+            app.results_filter.summary(),
+            shown
+        )
+    };
+    let file_name = format!(
+        "{}.{}",
+        app.input_buffer,
+        app.results_export_format.extension()
+    );
+    lines.extend([
+        Line::from(""),
+        Line::from(vec![
+            Span::styled("Results: ", Style::default().fg(theme.muted)),
+            Span::styled(scope, Style::default().fg(theme.primary)),
+        ]),
+        Line::from(""),
+        Line::from(vec![
+            Span::styled("File name: ", Style::default().fg(theme.muted)),
+            Span::styled(
+                format!("{}{}", app.input_buffer, theme.icons("█")),
+                Style::default().fg(theme.highlight),
             ),
         ]),
+        Line::from(Span::styled(
+            format!(
+                "Saved as {}",
+                app.export_output_dir.join(file_name).display()
+            ),
+            Style::default().fg(theme.subtle),
+        )),
+        Line::from(""),
+        Line::from(Span::styled(
+            theme.icons("↑↓: Format | Tab: Shown/All | Enter: Export | Esc: Cancel"),
+            Style::default().fg(theme.subtle),
+        )),
+    ]);
+
+    f.render_widget(Clear, area);
+    f.render_widget(
+        Paragraph::new(lines)
+            .block(
+                Block::default()
+                    .title(theme.icons("📤 Export Results"))
+                    .borders(Borders::ALL)
+                    .border_style(Style::default().fg(theme.primary))
+                    .padding(Padding::uniform(1)),
+            )
+            .wrap(Wrap { trim: false }),
+        area,
+    );
+}
+
+fn render_overwrite_warning(f: &mut Frame, app: &App) {
+    let theme = &app.theme;
+    let area = centered_rect(60, 40, f.area());
+
+    let mut lines = vec![
+        Line::from(Span::styled(
+            "These files already exist and will be overwritten:",
+            Style::default().fg(theme.highlight),
+        )),
+        Line::from(""),
+    ];
+    lines.extend(
+        app.pending_export
+            .iter()
+            .filter(|file| file.path.exists())
+            .map(|file| {
+                Line::from(Span::styled(
+                    format!("  {}", file.path.display()),
+                    Style::default().fg(theme.text),
+                ))
+            }),
+    );
+    lines.push(Line::from(""));
+    lines.push(Line::from(Span::styled(
+        "y: Overwrite | n/Esc: Cancel",
+        Style::default().fg(theme.subtle),
+    )));
+
+    f.render_widget(Clear, area);
+    f.render_widget(
+        Paragraph::new(lines)
+            .block(
+                Block::default()
+                    .title(theme.icons("⚠ Overwrite Files?"))
+                    .borders(Borders::ALL)
+                    .border_style(Style::default().fg(theme.error))
+                    .padding(Padding::uniform(1)),
+            )
+            .wrap(Wrap { trim: false }),
+        area,
+    );
+}
+
+fn render_output_viewer(f: &mut Frame, app: &App, viewer: &viewer::OutputViewer) {
+    let theme = &app.theme;
+    let area = centered_rect(90, 90, f.area());
+
+    // Rows are wrapped here rather than by the paragraph so that scrolling
+    // counts the same rows as are drawn.
+    viewer.width.set(area.width.saturating_sub(2));
+    let highlight = Style::default()
+        .fg(theme.highlight)
+        .add_modifier(Modifier::REVERSED);
+    let text = viewer.text();
+    let text_lines: Vec<&str> = text.lines().collect();
+    let ranges: Vec<_> = text_lines
+        .iter()
+        .map(|line| viewer::match_ranges(line, &viewer.search))
+        .collect();
+    let rows = viewer.rows();
+    // The width may have shrunk since the viewer last scrolled.
+    let scroll = viewer.scroll.min(rows.len().saturating_sub(1) as u16);
+    let lines: Vec<Line> = rows
+        .into_iter()
+        .map(|(i, range)| highlight_matches(text_lines[i], range, &ranges[i], highlight))
+        .collect();
+    let total = text_lines.len();
+    let matches = viewer.matches().len();
+
+    let mut status = vec![format!(
+        "line {}/{}",
+        (viewer.top_line() + 1).min(total.max(1)),
+        total
+    )];
+    if viewer.is_json() {
+        status.push(if viewer.pretty { "pretty" } else { "compact" }.to_string());
+    }
+    status.push(if viewer.wrap { "wrap" } else { "no wrap" }.to_string());
+    if viewer.searching {
+        status.push(format!("search: {}{}", viewer.search, theme.icons("█")));
+    } else if !viewer.search.is_empty() {
+        status.push(format!("\"{}\": {} rows", viewer.search, matches));
+    }
+    let keys = key_hints(
+        app,
+        Scope::Viewer,
+        &[
+            (
+                &[Action::Up, Action::Down, Action::PageUp, Action::PageDown],
+                "Scroll",
+            ),
+            (&[Action::ToggleWrap], "Wrap"),
+            (&[Action::TogglePretty], "Pretty"),
+            (&[Action::Search], "Search"),
+            (&[Action::NextMatch, Action::PrevMatch], "Next/Prev"),
+            (&[Action::Copy], "Copy"),
+            (&[Action::Close], "Close"),
+        ],
+    );
+
+    let paragraph = Paragraph::new(lines)
+        .style(Style::default().fg(theme.text))
+        .block(
+            Block::default()
+                .title(theme.icons(&format!("📄 {}", viewer.title)).into_owned())
+                .title_bottom(Line::from(format!(" {} | {} ", status.join(" | "), keys)))
+                .borders(Borders::ALL)
+                .border_style(Style::default().fg(theme.primary)),
+        )
+        .scroll((scroll, viewer.hscroll));
+
+    f.render_widget(Clear, area);
+    f.render_widget(paragraph, area);
+}
+
+/// The part `row` of `line` with the parts inside `matches` highlighted.
+fn highlight_matches<'a>(
+    line: &'a str,
+    row: Range<usize>,
+    matches: &[Range<usize>],
+    highlight: Style,
+) -> Line<'a> {
+    let mut spans = Vec::new();
+    let mut rest = row.start;
+    for m in matches {
+        let (start, end) = (m.start.max(rest), m.end.min(row.end));
+        if start >= end {
+            continue;
+        }
+        spans.push(Span::raw(&line[rest..start]));
+        spans.push(Span::styled(&line[start..end], highlight));
+        rest = end;
+    }
+    spans.push(Span::raw(&line[rest..row.end]));
+    Line::from(spans)
+}
+
+fn render_clear_history_warning(f: &mut Frame, app: &App) {
+    let theme = &app.theme;
+    let area = centered_rect(60, 30, f.area());
+
+    let stored = match &app.history {
+        Some(history) => format!(
+            "and delete {} ({} kept)",
+            history.path().display(),
+            history.retention()
+        ),
+        None => "(history is not saved to disk)".to_string(),
+    };
+    let lines = vec![
+        Line::from(Span::styled(
+            format!("Remove all {} results", app.results.len()),
+            Style::default().fg(theme.highlight),
+        )),
+        Line::from(Span::styled(stored, Style::default().fg(theme.text))),
+        Line::from(""),
+        Line::from(Span::styled(
+            "y: Clear | n/Esc: Cancel",
+            Style::default().fg(theme.subtle),
+        )),
     ];
 
+    f.render_widget(Clear, area);
     f.render_widget(
-        Paragraph::new(stats).block(
-            Block::default()
-                .title("📈 Statistics")
-                .borders(Borders::ALL)
-                .border_style(Style::default().fg(Color::Cyan))
-                .padding(Padding::uniform(1)),
-        ),
-        chunks[0],
+        Paragraph::new(lines)
+            .block(
+                Block::default()
+                    .title(theme.icons("⚠ Clear History?"))
+                    .borders(Borders::ALL)
+                    .border_style(Style::default().fg(theme.error))
+                    .padding(Padding::uniform(1)),
+            )
+            .wrap(Wrap { trim: false }),
+        area,
     );
+}
 
-    let export_text: Vec<Line> = if app.input_mode == InputMode::ExportName {
-        vec![
-            Line::from(Span::styled(
-                "Enter filename (without extension):",
-                Style::default().fg(Color::Yellow),
-            )),
-            Line::from(""),
-            Line::from(vec![
-                Span::styled(">> ", Style::default().fg(Color::Yellow)),
-                Span::styled(&app.input_buffer, Style::default().fg(Color::White)),
-                Span::styled(
-                    "_",
-                    Style::default()
-                        .fg(Color::Yellow)
-                        .add_modifier(Modifier::SLOW_BLINK),
-                ),
-            ]),
-        ]
-    } else {
-        let code = app.export_pipeline();
-        code.lines()
-            .map(|line| {
-                Line::from(Span::styled(
-                    line.to_owned(),
-                    Style::default().fg(Color::White),
-                ))
-            })
-            .collect()
+fn render_import_warning(f: &mut Frame, app: &App) {
+    let theme = &app.theme;
+    let area = centered_rect(60, 30, f.area());
+
+    let source = match &app.pending_import {
+        Some((path, file)) => format!("with {} steps from {}", file.steps.len(), path.display()),
+        None => String::new(),
     };
+    let lines = vec![
+        Line::from(Span::styled(
+            format!("Replace the {} modified pipeline steps", app.pipeline.len()),
+            Style::default().fg(theme.highlight),
+        )),
+        Line::from(Span::styled(source, Style::default().fg(theme.text))),
+        Line::from(""),
+        Line::from(Span::styled(
+            "y: Import | n/Esc: Cancel",
+            Style::default().fg(theme.subtle),
+        )),
+    ];
 
+    f.render_widget(Clear, area);
     f.render_widget(
-        Paragraph::new(export_text)
+        Paragraph::new(lines)
             .block(
                 Block::default()
-                    .title("📤 Export Preview (Press 's' to save)")
+                    .title(theme.icons("⚠ Replace Pipeline?"))
                     .borders(Borders::ALL)
-                    .border_style(Style::default().fg(Color::Cyan))
+                    .border_style(Style::default().fg(theme.error))
                     .padding(Padding::uniform(1)),
             )
-            .wrap(Wrap { trim: false })
-            .scroll((0, 0)),
-        chunks[1],
+            .wrap(Wrap { trim: false }),
+        area,
     );
 }
 
 fn render_footer(f: &mut Frame, app: &App, area: Rect) {
+    let theme = &app.theme;
     let chunks = Layout::default()
         .direction(Direction::Vertical)
         .constraints([Constraint::Length(3), Constraint::Length(3)])
         .split(area);
 
-    let keybinds = match app.current_view {
-        View::ScriptBrowser => {
-            "↑↓: Select | l: Load/unload | r: Reload | Tab: Next View | ?: Help | q: Quit"
-        }
-        View::FunctionTester => {
-            "↑↓: Select Function | a: Add Args | Enter: Execute | p: Add to Pipeline | Tab: Next View"
-        }
-        View::PipelineBuilder => {
-            "↑↓: Select Step | x: Execute | d: Delete | c: Clear | Tab: Next View"
-        }
-        View::ResultsExplorer => "↑↓: Navigate | Tab: Next View",
-        View::Export => "s: Save to File | Tab: Next View",
+    let hints: &[(&[Action], &str)] = match app.current_view {
+        View::ScriptBrowser => &[
+            (&[Action::Up, Action::Down], "Select"),
+            (&[Action::LoadScript], "Load/unload"),
+            (&[Action::Rescan], "Reload"),
+            (&[Action::NextView], "Next View"),
+            (&[Action::Help], "Help"),
+            (&[Action::Quit], "Quit"),
+        ],
+        View::FunctionTester => &[
+            (&[Action::Up, Action::Down], "Select Function"),
+            (&[Action::EditArgs], "Edit Args"),
+            (&[Action::Execute], "Execute"),
+            (&[Action::AddToPipeline], "Add to Pipeline"),
+            (&[Action::NextView], "Next View"),
+        ],
+        View::PipelineBuilder => &[
+            (&[Action::Up, Action::Down], "Select"),
+            (&[Action::EditStep], "Edit"),
+            (&[Action::InsertStep], "Insert"),
+            (&[Action::DuplicateStep], "Duplicate"),
+            (&[Action::MoveStepUp, Action::MoveStepDown], "Move"),
+            (&[Action::EditParams], "Params"),
+            (&[Action::ImportPipeline], "Import"),
+            (&[Action::RunPipeline], "Execute"),
+            (&[Action::DeleteStep], "Delete"),
+            (&[Action::ClearPipeline], "Clear"),
+        ],
+        View::RunHistory => &[
+            (&[Action::Up, Action::Down], "Select Run"),
+            (&[Action::Left, Action::Right], "Select Step"),
+            (&[Action::Rerun], "Rerun from Step"),
+            (&[Action::RerunFailed], "Rerun from Failure"),
+            (&[Action::Compare], "Compare"),
+        ],
+        View::ResultsExplorer => &[
+            (&[Action::Up, Action::Down], "Navigate"),
+            (&[Action::ViewOutput], "View Output"),
+            (
+                &[
+                    Action::FilterStatus,
+                    Action::FilterScript,
+                    Action::FilterFunction,
+                    Action::FilterLanguage,
+                ],
+                "Filter",
+            ),
+            (&[Action::Search], "Search"),
+            (&[Action::Sort], "Sort"),
+            (&[Action::Replay], "Replay"),
+            (&[Action::Diff], "Diff"),
+            (&[Action::Help], "Help"),
+        ],
+        View::Statistics => &[
+            (&[Action::Up, Action::Down], "Select Function"),
+            (&[Action::NextView], "Next View"),
+        ],
+        View::Export => &[
+            (&[Action::Up, Action::Down], "Format"),
+            (&[Action::PageUp, Action::PageDown], "Scroll"),
+            (&[Action::SaveExport], "Save"),
+            (&[Action::OutputDir], "Output Dir"),
+            (&[Action::TogglePaths], "Script Paths"),
+            (&[Action::NextView], "Next View"),
+        ],
+        View::Logs => &[
+            (
+                &[Action::Up, Action::Down, Action::PageUp, Action::PageDown],
+                "Scroll",
+            ),
+            (&[Action::Bottom], "Follow"),
+            (
+                &[
+                    Action::ToggleInfo,
+                    Action::ToggleSuccess,
+                    Action::ToggleWarning,
+                    Action::ToggleError,
+                ],
+                "Toggle Level",
+            ),
+            (&[Action::Search], "Search"),
+            (&[Action::ResetFilters], "Reset"),
+            (&[Action::ClearLogs], "Clear"),
+        ],
     };
+    let keybinds = key_hints(app, Scope::of(&app.current_view), hints);
 
     f.render_widget(
         Paragraph::new(keybinds)
-            .style(Style::default().fg(Color::Cyan))
+            .style(Style::default().fg(theme.primary))
             .block(
                 Block::default()
-                    .title("⌨️  Keybindings")
+                    .title(theme.icons("⌨️  Keybindings"))
                     .borders(Borders::ALL)
-                    .border_style(Style::default().fg(Color::DarkGray)),
+                    .border_style(Style::default().fg(theme.subtle)),
             )
             .alignment(Alignment::Center),
         chunks[0],
@@ -1151,112 +4601,228 @@ fn render_footer(f: &mut Frame, app: &App, area: Rect) {
 
     let log_items: Vec<Line> = app
         .logs
-        .iter()
-        .rev()
-        .take(1)
-        .map(|log| {
-            let (icon, color) = match log.level {
-                LogLevel::Info => ("ℹ️", Color::Cyan),
-                LogLevel::Success => ("✓", Color::Green),
-                LogLevel::Error => ("✗", Color::Red),
-                LogLevel::Warning => ("⚠", Color::Yellow),
-            };
-
-            Line::from(vec![
-                Span::styled(
-                    format!("[{}] ", log.timestamp),
-                    Style::default().fg(Color::DarkGray),
-                ),
-                Span::styled(format!("{} ", icon), Style::default().fg(color)),
-                Span::styled(&log.message, Style::default().fg(color)),
-            ])
-        })
+        .last()
+        .map(|log| log_line(app, log))
+        .into_iter()
         .collect();
 
     f.render_widget(
         Paragraph::new(log_items)
             .block(
                 Block::default()
-                    .title("📝 Log")
+                    .title(theme.icons("📝 Log"))
                     .borders(Borders::ALL)
-                    .border_style(Style::default().fg(Color::DarkGray)),
+                    .border_style(Style::default().fg(theme.subtle)),
             )
             .alignment(Alignment::Left),
         chunks[1],
     );
 }
 
-fn render_help_popup(f: &mut Frame) {
-    let area = centered_rect(60, 70, f.area());
+fn render_help_popup(f: &mut Frame, app: &App) {
+    let theme = &app.theme;
+    let area = centered_rect(70, 80, f.area());
 
-    let help_text = vec![
+    let mut help_text = vec![
         Line::from(Span::styled(
             "MetaCall Playground - Help",
             Style::default()
-                .fg(Color::Cyan)
+                .fg(theme.primary)
                 .add_modifier(Modifier::BOLD),
         )),
         Line::from(""),
-        Line::from(Span::styled(
-            "Global Keys:",
-            Style::default().fg(Color::Yellow),
-        )),
-        Line::from("  Tab / Shift+Tab  - Navigate between views"),
-        Line::from("  ?                - Toggle this help"),
-        Line::from("  q                - Quit application"),
-        Line::from(""),
-        Line::from(Span::styled(
-            "Script Browser:",
-            Style::default().fg(Color::Yellow),
-        )),
-        Line::from("  ↑ / ↓            - Navigate scripts"),
-        Line::from("  l                - Load selected script"),
-        Line::from("  r                - Rescan directory"),
-        Line::from(""),
-        Line::from(Span::styled(
-            "Function Tester:",
-            Style::default().fg(Color::Yellow),
-        )),
-        Line::from("  ↑ / ↓            - Select function"),
-        Line::from("  a                - Add argument"),
-        Line::from("  c                - Clear arguments"),
-        Line::from("  Enter            - Execute function"),
-        Line::from("  p                - Add to pipeline"),
-        Line::from(""),
-        Line::from(Span::styled(
-            "Pipeline Builder:",
-            Style::default().fg(Color::Yellow),
-        )),
-        Line::from("  ↑ / ↓            - Select step"),
-        Line::from("  x                - Execute pipeline"),
-        Line::from("  d                - Delete selected step"),
-        Line::from("  c                - Clear all steps"),
-        Line::from(""),
-        Line::from(Span::styled(
-            "Export View:",
-            Style::default().fg(Color::Yellow),
-        )),
-        Line::from("  s                - Save pipeline to file"),
-        Line::from(""),
-        Line::from(Span::styled(
-            "Press any key to close",
-            Style::default().fg(Color::DarkGray),
-        )),
     ];
 
+    for scope in [Scope::Global, Scope::of(&app.current_view)] {
+        help_text.push(Line::from(Span::styled(
+            format!("{}:", scope.label()),
+            Style::default().fg(theme.highlight),
+        )));
+        for binding in app.keymap.bindings(scope) {
+            let keys: Vec<String> = binding.keys.iter().map(|key| key.label()).collect();
+            let (keys, style) = if keys.is_empty() {
+                ("unbound".to_string(), Style::default().fg(theme.subtle))
+            } else {
+                (
+                    theme.icons(&keys.join(" / ")).into_owned(),
+                    Style::default(),
+                )
+            };
+            help_text.push(Line::from(Span::styled(
+                format!("  {:<16} - {}", keys, binding.description),
+                style,
+            )));
+        }
+        if scope == Scope::Tester {
+            help_text.push(Line::from("  In the argument editor:"));
+            help_text.push(Line::from(
+                "  Ctrl+S / Alt+Enter - Apply the edited arguments",
+            ));
+            help_text.push(Line::from(
+                theme.icons("  Ctrl+↑ / Ctrl+↓  - Recall argument lists used before"),
+            ));
+            help_text.push(Line::from("  Esc              - Discard the changes"));
+        }
+        help_text.push(Line::from(""));
+    }
+
+    let source = match (app.keymap.preset, app.keymap.customized) {
+        (keymap::Preset::Vim, false) => "Vim keys".to_string(),
+        (keymap::Preset::Default, false) => "Default keys".to_string(),
+        (preset, true) => format!(
+            "{} keys from {}/keymap.json",
+            if preset == keymap::Preset::Vim {
+                "Vim"
+            } else {
+                "Default"
+            },
+            config::DATA_DIR
+        ),
+    };
+    help_text.push(Line::from(Span::styled(
+        format!("{} | Press any key to close", source),
+        Style::default().fg(theme.subtle),
+    )));
+
     let paragraph = Paragraph::new(help_text)
         .block(
             Block::default()
-                .title("❓ Help")
+                .title(theme.icons("❓ Help"))
                 .borders(Borders::ALL)
-                .border_style(Style::default().fg(Color::Cyan))
+                .border_style(Style::default().fg(theme.primary))
                 .padding(Padding::uniform(2)),
         )
         .alignment(Alignment::Left);
 
+    f.render_widget(Clear, area);
     f.render_widget(paragraph, area);
 }
 
+/// Key hints such as `↑/↓: Navigate | e: Export`, from the first key of
+/// each action. Hints whose actions are all unbound are left out.
+fn key_hints(app: &App, scope: Scope, hints: &[(&[Action], &str)]) -> String {
+    let keymap = &app.keymap;
+    hints
+        .iter()
+        .filter_map(|(actions, label)| {
+            let keys: Vec<String> = actions
+                .iter()
+                .filter_map(|&action| {
+                    let global = keymap.keys(Scope::Global, action);
+                    keymap
+                        .keys(scope, action)
+                        .first()
+                        .or(global.first())
+                        .map(|key| app.theme.icons(&key.label()).into_owned())
+                })
+                .collect();
+            (!keys.is_empty()).then(|| format!("{}: {}", keys.join("/"), label))
+        })
+        .collect::<Vec<_>>()
+        .join(" | ")
+}
+
+fn render_command_palette(f: &mut Frame, app: &App, palette: &palette::Palette) {
+    let theme = &app.theme;
+    let area = centered_rect(60, 60, f.area());
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Length(3), Constraint::Min(0)])
+        .split(area);
+
+    f.render_widget(Clear, area);
+    f.render_widget(
+        Paragraph::new(Line::from(vec![
+            Span::styled("> ", Style::default().fg(theme.muted)),
+            Span::styled(
+                format!("{}{}", palette.query, theme.icons("█")),
+                Style::default().fg(theme.highlight),
+            ),
+        ]))
+        .block(
+            Block::default()
+                .title(theme.icons("🔍 Functions and Actions"))
+                .borders(Borders::ALL)
+                .border_style(Style::default().fg(theme.highlight)),
+        ),
+        chunks[0],
+    );
+
+    let matches = palette.matches();
+    let items: Vec<ListItem> = matches
+        .iter()
+        .map(|m| {
+            let mut spans: Vec<Span> = m
+                .entry
+                .label
+                .chars()
+                .enumerate()
+                .map(|(i, c)| {
+                    if m.positions.contains(&i) {
+                        Span::styled(
+                            c.to_string(),
+                            Style::default()
+                                .fg(theme.highlight)
+                                .add_modifier(Modifier::BOLD),
+                        )
+                    } else {
+                        Span::styled(c.to_string(), Style::default().fg(theme.text))
+                    }
+                })
+                .collect();
+            if !m.entry.detail.is_empty() {
+                spans.push(Span::styled(
+                    format!("  {}", m.entry.detail),
+                    Style::default().fg(theme.subtle),
+                ));
+            }
+            ListItem::new(Line::from(spans))
+        })
+        .collect();
+    let empty = matches.is_empty();
+    let mut state = ListState::default().with_selected((!empty).then_some(palette.selected));
+    f.render_stateful_widget(
+        List::new(items)
+            .block(
+                Block::default()
+                    .title(format!("{} of {}", matches.len(), palette.len()))
+                    .title_bottom(Line::from(
+                        theme.icons(" ↑↓: Select | Enter: Open or run | Esc: Close "),
+                    ))
+                    .borders(Borders::ALL)
+                    .border_style(Style::default().fg(theme.highlight)),
+            )
+            .highlight_style(Style::default().bg(theme.selection)),
+        chunks[1],
+        &mut state,
+    );
+}
+
+fn render_quit_confirmation(f: &mut Frame, app: &App) {
+    let theme = &app.theme;
+    let area = centered_rect(40, 20, f.area());
+    f.render_widget(Clear, area);
+    f.render_widget(
+        Paragraph::new(vec![
+            Line::from("Quit MetaCall Playground?"),
+            Line::from(""),
+            Line::from(Span::styled(
+                "y/Enter: Quit | any other key: Cancel",
+                Style::default().fg(theme.subtle),
+            )),
+        ])
+        .block(
+            Block::default()
+                .title(theme.icons("⚠ Quit?"))
+                .borders(Borders::ALL)
+                .border_style(Style::default().fg(theme.highlight))
+                .padding(Padding::uniform(1)),
+        )
+        .wrap(Wrap { trim: false }),
+        area,
+    );
+}
+
 fn centered_rect(percent_x: u16, percent_y: u16, r: Rect) -> Rect {
     let popup_layout = Layout::default()
         .direction(Direction::Vertical)
@@ -1278,15 +4844,200 @@ fn centered_rect(percent_x: u16, percent_y: u16, r: Rect) -> Rect {
 }
 
 fn handle_input(app: &mut App, key: KeyCode, modifiers: KeyModifiers) -> io::Result<bool> {
+    if app.output_viewer.is_some() {
+        handle_output_viewer_input(app, key, modifiers);
+        return Ok(false);
+    }
+
     match app.input_mode {
+        InputMode::ConfirmQuit => {
+            let scope = Scope::of(&app.current_view);
+            match key {
+                KeyCode::Char('y') | KeyCode::Char('Y') | KeyCode::Enter => return Ok(true),
+                _ if app.keymap.action(scope, key, modifiers) == Some(Action::Quit) => {
+                    return Ok(true);
+                }
+                _ => app.input_mode = InputMode::Normal,
+            }
+            Ok(false)
+        }
         InputMode::EditingArgs => {
+            handle_arg_editor_input(app, key, modifiers);
+            Ok(false)
+        }
+        InputMode::ExportName => {
+            match key {
+                KeyCode::Enter => {
+                    app.input_mode = InputMode::Normal;
+                    if !app.input_buffer.is_empty() {
+                        let name = app.input_buffer.clone();
+                        app.export_files(&name);
+                        app.input_buffer.clear();
+                    }
+                }
+                KeyCode::Esc => {
+                    app.input_buffer.clear();
+                    app.input_mode = InputMode::Normal;
+                }
+                KeyCode::Backspace => {
+                    app.input_buffer.pop();
+                }
+                KeyCode::Char(c) => {
+                    app.input_buffer.push(c);
+                }
+                _ => {}
+            }
+            Ok(false)
+        }
+        InputMode::ExportDir => {
+            match key {
+                KeyCode::Enter => {
+                    let dir = app.input_buffer.clone();
+                    match app.set_export_dir(&dir) {
+                        Ok(()) => {
+                            app.input_buffer.clear();
+                            app.input_mode = InputMode::Normal;
+                        }
+                        Err(e) => app.add_log(LogLevel::Error, e),
+                    }
+                }
+                KeyCode::Esc => {
+                    app.input_buffer.clear();
+                    app.input_mode = InputMode::Normal;
+                }
+                KeyCode::Backspace => {
+                    app.input_buffer.pop();
+                }
+                KeyCode::Char(c) => {
+                    app.input_buffer.push(c);
+                }
+                _ => {}
+            }
+            Ok(false)
+        }
+        InputMode::ExportResults => {
+            match key {
+                KeyCode::Enter => {
+                    let name = app.input_buffer.trim().to_string();
+                    if name.is_empty() {
+                        app.add_log(LogLevel::Error, "File name cannot be empty".to_string());
+                    } else {
+                        app.input_buffer.clear();
+                        app.input_mode = InputMode::Normal;
+                        app.export_results(&name);
+                    }
+                }
+                KeyCode::Esc => {
+                    app.input_buffer.clear();
+                    app.input_mode = InputMode::Normal;
+                }
+                KeyCode::Up => app.results_export_format = app.results_export_format.prev(),
+                KeyCode::Down => app.results_export_format = app.results_export_format.next(),
+                KeyCode::Tab => app.results_export_all = !app.results_export_all,
+                KeyCode::Backspace => {
+                    app.input_buffer.pop();
+                }
+                KeyCode::Char(c) => {
+                    app.input_buffer.push(c);
+                }
+                _ => {}
+            }
+            Ok(false)
+        }
+        InputMode::ConfirmOverwrite => {
+            match key {
+                KeyCode::Char('y') | KeyCode::Char('Y') => {
+                    let files = std::mem::take(&mut app.pending_export);
+                    app.write_export(files, app.pending_export_label);
+                    app.input_mode = InputMode::Normal;
+                }
+                KeyCode::Char('n') | KeyCode::Char('N') | KeyCode::Esc => {
+                    app.pending_export.clear();
+                    app.add_log(LogLevel::Info, "Export cancelled".to_string());
+                    app.input_mode = InputMode::Normal;
+                }
+                _ => {}
+            }
+            Ok(false)
+        }
+        InputMode::SearchResults => {
+            match key {
+                KeyCode::Enter => app.input_mode = InputMode::Normal,
+                KeyCode::Esc => {
+                    app.results_filter.search.clear();
+                    app.input_mode = InputMode::Normal;
+                }
+                KeyCode::Backspace => {
+                    app.results_filter.search.pop();
+                }
+                KeyCode::Char(c) => app.results_filter.search.push(c),
+                _ => {}
+            }
+            app.selected_result = 0;
+            Ok(false)
+        }
+        InputMode::CommandPalette => Ok(handle_palette_input(app, key, modifiers)),
+        InputMode::SearchLogs => {
+            match key {
+                KeyCode::Enter => app.input_mode = InputMode::Normal,
+                KeyCode::Esc => {
+                    app.log_filter.search.clear();
+                    app.input_mode = InputMode::Normal;
+                }
+                KeyCode::Backspace => {
+                    app.log_filter.search.pop();
+                }
+                KeyCode::Char(c) => app.log_filter.search.push(c),
+                _ => {}
+            }
+            app.selected_log = None;
+            Ok(false)
+        }
+        InputMode::ConfirmImport => {
+            match key {
+                KeyCode::Char('y') | KeyCode::Char('Y') => {
+                    if let Some((path, file)) = app.pending_import.take() {
+                        app.apply_import(path, file);
+                    }
+                    app.input_mode = InputMode::Normal;
+                }
+                KeyCode::Char('n') | KeyCode::Char('N') | KeyCode::Esc => {
+                    app.pending_import = None;
+                    app.add_log(LogLevel::Info, "Import cancelled".to_string());
+                    app.input_mode = InputMode::Normal;
+                }
+                _ => {}
+            }
+            Ok(false)
+        }
+        InputMode::ConfirmClearHistory => {
+            match key {
+                KeyCode::Char('y') | KeyCode::Char('Y') => {
+                    app.clear_history();
+                    app.input_mode = InputMode::Normal;
+                }
+                KeyCode::Char('n') | KeyCode::Char('N') | KeyCode::Esc => {
+                    app.input_mode = InputMode::Normal;
+                }
+                _ => {}
+            }
+            Ok(false)
+        }
+        InputMode::AddingStep | InputMode::EditingStep => {
+            handle_step_editor_input(app, key);
+            Ok(false)
+        }
+        InputMode::EditingParams => {
             match key {
                 KeyCode::Enter => {
-                    if !app.input_buffer.is_empty() {
-                        app.function_input.args.push(app.input_buffer.clone());
-                        app.input_buffer.clear();
+                    let declarations = app.input_buffer.clone();
+                    match app.set_params(&declarations) {
+                        Ok(()) => {
+                            app.input_buffer.clear();
+                            app.input_mode = InputMode::Normal;
+                        }
+                        Err(e) => app.add_log(LogLevel::Error, e),
                     }
-                    app.input_mode = InputMode::Normal;
                 }
                 KeyCode::Esc => {
                     app.input_buffer.clear();
@@ -1302,23 +5053,23 @@ fn handle_input(app: &mut App, key: KeyCode, modifiers: KeyModifiers) -> io::Res
             }
             Ok(false)
         }
-        InputMode::ExportName => {
+        InputMode::PipelineInputs => {
+            handle_pipeline_inputs_input(app, key);
+            Ok(false)
+        }
+        InputMode::ImportPath => {
             match key {
                 KeyCode::Enter => {
-                    if !app.input_buffer.is_empty() {
-                        let filename = format!("{}.rs", app.input_buffer);
-                        let content = app.export_pipeline();
-                        match fs::write(&filename, content) {
-                            Ok(_) => {
-                                app.add_log(LogLevel::Success, format!("Exported to {}", filename));
-                            }
-                            Err(e) => {
-                                app.add_log(LogLevel::Error, format!("Failed to export: {}", e));
+                    let path = app.input_buffer.clone();
+                    match app.import_pipeline(&path) {
+                        Ok(()) => {
+                            app.input_buffer.clear();
+                            if app.input_mode == InputMode::ImportPath {
+                                app.input_mode = InputMode::Normal;
                             }
                         }
-                        app.input_buffer.clear();
+                        Err(e) => app.add_log(LogLevel::Error, e),
                     }
-                    app.input_mode = InputMode::Normal;
                 }
                 KeyCode::Esc => {
                     app.input_buffer.clear();
@@ -1340,68 +5091,169 @@ fn handle_input(app: &mut App, key: KeyCode, modifiers: KeyModifiers) -> io::Res
                 return Ok(false);
             }
 
+            let scope = Scope::of(&app.current_view);
+            match app.keymap.action(scope, key, modifiers) {
+                Some(action) => Ok(handle_action(app, action)),
+                None => Ok(false),
+            }
+        }
+    }
+}
+
+/// Runs a global action or an action of the current view. Returns true to
+/// quit.
+fn handle_action(app: &mut App, action: Action) -> bool {
+    match action {
+        Action::Quit if app.keymap.confirm_quit => app.input_mode = InputMode::ConfirmQuit,
+        Action::Quit => return true,
+        Action::Help => app.show_help = true,
+        Action::NextView => app.next_view(),
+        Action::PrevView => app.prev_view(),
+        Action::CommandPalette => app.open_palette(),
+        action => handle_view_action(app, action),
+    }
+    false
+}
+
+fn handle_palette_input(app: &mut App, key: KeyCode, modifiers: KeyModifiers) -> bool {
+    let Some(palette) = app.palette.as_mut() else {
+        app.input_mode = InputMode::Normal;
+        return false;
+    };
+    let ctrl = modifiers.contains(KeyModifiers::CONTROL);
+    let command = match key {
+        KeyCode::Enter => palette.command(),
+        KeyCode::Esc => None,
+        _ => {
             match key {
-                KeyCode::Char('q') if modifiers.contains(KeyModifiers::CONTROL) => {
-                    return Ok(true);
-                }
-                KeyCode::Char('q') => return Ok(true),
-                KeyCode::Char('?') => {
-                    app.show_help = true;
-                }
-                KeyCode::Tab => {
-                    app.next_view();
-                }
-                KeyCode::BackTab => {
-                    app.prev_view();
-                }
-                _ => match app.current_view {
-                    View::ScriptBrowser => handle_script_browser_input(app, key),
-                    View::FunctionTester => handle_function_tester_input(app, key),
-                    View::PipelineBuilder => handle_pipeline_builder_input(app, key),
-                    View::ResultsExplorer => handle_results_input(app, key),
-                    View::Export => handle_export_input(app, key),
-                },
+                KeyCode::Up => palette.select(-1),
+                KeyCode::Char('p') if ctrl => palette.select(-1),
+                KeyCode::Down => palette.select(1),
+                KeyCode::Char('n') if ctrl => palette.select(1),
+                KeyCode::PageUp => palette.select(-10),
+                KeyCode::PageDown => palette.select(10),
+                KeyCode::Backspace => palette.pop(),
+                KeyCode::Char(c) if !ctrl => palette.push(c),
+                _ => {}
             }
-            Ok(false)
+            return false;
+        }
+    };
+
+    app.palette = None;
+    app.input_mode = InputMode::Normal;
+    match command {
+        Some(palette::Command::Function { script, function }) => {
+            app.open_function(script, function)
+        }
+        Some(palette::Command::View(view)) => app.current_view = view,
+        Some(palette::Command::Action(scope, action)) => {
+            if let Some(view) = scope.view() {
+                app.current_view = view;
+            }
+            return handle_action(app, action);
         }
-        _ => Ok(false),
+        None => {}
     }
+    false
 }
 
-fn handle_script_browser_input(app: &mut App, key: KeyCode) {
-    match key {
-        KeyCode::Up => {
+fn handle_view_action(app: &mut App, action: Action) {
+    match app.current_view {
+        View::ScriptBrowser => handle_script_browser_input(app, action),
+        View::FunctionTester => handle_function_tester_input(app, action),
+        View::PipelineBuilder => handle_pipeline_builder_input(app, action),
+        View::RunHistory => handle_run_history_input(app, action),
+        View::ResultsExplorer => handle_results_input(app, action),
+        View::Statistics => handle_statistics_input(app, action),
+        View::Export => handle_export_input(app, action),
+        View::Logs => handle_logs_input(app, action),
+    }
+}
+
+/// Clicks select a tab or list item, and the wheel moves the selection or
+/// scrolls the output viewer. Prompts and the argument editor ignore the
+/// mouse.
+fn handle_mouse(app: &mut App, mouse: MouseEvent) {
+    let up = match mouse.kind {
+        MouseEventKind::ScrollUp => true,
+        MouseEventKind::ScrollDown => false,
+        MouseEventKind::Down(MouseButton::Left) => {
+            return handle_click(app, mouse.column, mouse.row);
+        }
+        _ => return,
+    };
+    if let Some(viewer) = app.output_viewer.as_mut() {
+        viewer.scroll_by(if up { -3 } else { 3 });
+    } else if app.input_mode == InputMode::Normal && !app.show_help {
+        handle_view_action(app, if up { Action::Up } else { Action::Down });
+    }
+}
+
+fn handle_click(app: &mut App, column: u16, row: u16) {
+    if app.show_help {
+        app.show_help = false;
+        return;
+    }
+    if app.output_viewer.is_some() || app.input_mode != InputMode::Normal {
+        return;
+    }
+    let target = app.regions.borrow().target(column, row);
+    match target {
+        Some(mouse::Target::Tab(view)) => app.current_view = view,
+        Some(mouse::Target::Item(list, index)) => match list {
+            mouse::List::Scripts => app.selected_script = index,
+            mouse::List::Functions => app.function_input.selected_function = index,
+            mouse::List::Steps => app.selected_pipeline_step = index,
+            mouse::List::Runs => {
+                app.selected_run = index;
+                app.selected_run_step = 0;
+            }
+            mouse::List::Results => app.selected_result = index,
+            mouse::List::Stats => app.selected_stat = index,
+            mouse::List::Logs => {
+                let last = app.filtered_logs().len().saturating_sub(1);
+                app.selected_log = (index < last).then_some(index);
+            }
+        },
+        None => {}
+    }
+}
+
+fn handle_script_browser_input(app: &mut App, action: Action) {
+    match action {
+        Action::Up => {
             if app.selected_script > 0 {
                 app.selected_script -= 1;
             }
         }
-        KeyCode::Down => {
+        Action::Down => {
             if app.selected_script < app.scripts.len().saturating_sub(1) {
                 app.selected_script += 1;
             }
         }
-        KeyCode::Char('l') => {
+        Action::LoadScript => {
             if !app.scripts.is_empty() {
                 let _ = app.load_script(app.selected_script);
             }
         }
-        KeyCode::Char('r') => {
+        Action::Rescan => {
             app.scan_scripts();
         }
         _ => {}
     }
 }
 
-fn handle_function_tester_input(app: &mut App, key: KeyCode) {
-    match key {
-        KeyCode::Up => {
+fn handle_function_tester_input(app: &mut App, action: Action) {
+    match action {
+        Action::Up => {
             if !app.scripts.is_empty() && app.selected_script < app.scripts.len() {
                 if app.function_input.selected_function > 0 {
                     app.function_input.selected_function -= 1;
                 }
             }
         }
-        KeyCode::Down => {
+        Action::Down => {
             if !app.scripts.is_empty() && app.selected_script < app.scripts.len() {
                 let script = &app.scripts[app.selected_script];
                 if app.function_input.selected_function < script.functions.len().saturating_sub(1) {
@@ -1409,42 +5261,131 @@ fn handle_function_tester_input(app: &mut App, key: KeyCode) {
                 }
             }
         }
-        KeyCode::Char('a') => {
-            app.input_mode = InputMode::EditingArgs;
-            app.input_buffer.clear();
-        }
-        KeyCode::Char('c') => {
+        Action::EditArgs => app.open_arg_editor(),
+        Action::ClearArgs => {
             app.function_input.args.clear();
             app.add_log(LogLevel::Info, "Arguments cleared".to_string());
         }
-        KeyCode::Enter => {
+        Action::Execute => {
             let _ = app.execute_function();
         }
-        KeyCode::Char('p') => {
+        Action::AddToPipeline => {
             app.add_to_pipeline();
         }
         _ => {}
     }
 }
 
-fn handle_pipeline_builder_input(app: &mut App, key: KeyCode) {
+fn handle_arg_editor_input(app: &mut App, key: KeyCode, modifiers: KeyModifiers) {
+    let Some(editor) = app.arg_editor.as_mut() else {
+        app.input_mode = InputMode::Normal;
+        return;
+    };
+    let ctrl = modifiers.contains(KeyModifiers::CONTROL);
+    let alt = modifiers.contains(KeyModifiers::ALT);
     match key {
-        KeyCode::Up => {
+        KeyCode::Esc => app.close_arg_editor(false),
+        KeyCode::Char('s') if ctrl => app.close_arg_editor(true),
+        KeyCode::Enter if alt => app.close_arg_editor(true),
+        KeyCode::Up if ctrl => {
+            editor.recall(true);
+        }
+        KeyCode::Down if ctrl => {
+            editor.recall(false);
+        }
+        _ => {
+            let key = match key {
+                KeyCode::Char(c) => Key::Char(c),
+                KeyCode::Backspace => Key::Backspace,
+                KeyCode::Enter => Key::Enter,
+                KeyCode::Left => Key::Left,
+                KeyCode::Right => Key::Right,
+                KeyCode::Up => Key::Up,
+                KeyCode::Down => Key::Down,
+                KeyCode::Tab => Key::Tab,
+                KeyCode::Delete => Key::Delete,
+                KeyCode::Home => Key::Home,
+                KeyCode::End => Key::End,
+                KeyCode::PageUp => Key::PageUp,
+                KeyCode::PageDown => Key::PageDown,
+                KeyCode::F(n) => Key::F(n),
+                _ => return,
+            };
+            editor.textarea_mut().input(Input {
+                key,
+                ctrl,
+                alt,
+                shift: modifiers.contains(KeyModifiers::SHIFT),
+            });
+        }
+    }
+}
+
+/// Handles text pasted into the terminal. The argument editor takes it as
+/// is; other prompts get it as typed keys, so newlines confirm them as
+/// before bracketed paste was enabled.
+fn handle_paste(app: &mut App, text: &str) -> io::Result<bool> {
+    if let Some(editor) = app.arg_editor.as_mut() {
+        editor.textarea_mut().insert_str(text);
+        return Ok(false);
+    }
+    if app.input_mode == InputMode::Normal {
+        return Ok(false);
+    }
+    for c in text.chars().filter(|&c| c != '\r') {
+        let key = match c {
+            '\n' => KeyCode::Enter,
+            c => KeyCode::Char(c),
+        };
+        if handle_input(app, key, KeyModifiers::NONE)? {
+            return Ok(true);
+        }
+    }
+    Ok(false)
+}
+
+fn handle_pipeline_builder_input(app: &mut App, action: Action) {
+    match action {
+        Action::Up => {
             if app.selected_pipeline_step > 0 {
                 app.selected_pipeline_step -= 1;
             }
         }
-        KeyCode::Down => {
+        Action::Down => {
             if app.selected_pipeline_step < app.pipeline.len().saturating_sub(1) {
                 app.selected_pipeline_step += 1;
             }
         }
-        KeyCode::Char('x') => {
-            let _ = app.execute_pipeline();
+        Action::RunPipeline => {
+            app.begin_pipeline_inputs();
+        }
+        Action::EditStep => {
+            app.begin_step_edit();
+        }
+        Action::InsertStep => {
+            app.begin_step_insert();
+        }
+        Action::EditParams => {
+            app.input_buffer = params::format_declarations(&app.params);
+            app.input_mode = InputMode::EditingParams;
         }
-        KeyCode::Char('d') => {
+        Action::ImportPipeline => {
+            app.input_buffer.clear();
+            app.input_mode = InputMode::ImportPath;
+        }
+        Action::DuplicateStep => {
+            app.duplicate_step();
+        }
+        Action::MoveStepUp => {
+            app.move_step(true);
+        }
+        Action::MoveStepDown => {
+            app.move_step(false);
+        }
+        Action::DeleteStep => {
             if !app.pipeline.is_empty() && app.selected_pipeline_step < app.pipeline.len() {
                 app.pipeline.remove(app.selected_pipeline_step);
+                app.pipeline_changed();
                 app.add_log(LogLevel::Success, "Step deleted".to_string());
                 if app.selected_pipeline_step >= app.pipeline.len()
                     && app.selected_pipeline_step > 0
@@ -1453,8 +5394,10 @@ fn handle_pipeline_builder_input(app: &mut App, key: KeyCode) {
                 }
             }
         }
-        KeyCode::Char('c') => {
+        Action::ClearPipeline => {
             app.pipeline.clear();
+            app.import_issues.clear();
+            app.pipeline_modified = false;
             app.selected_pipeline_step = 0;
             app.add_log(LogLevel::Success, "Pipeline cleared".to_string());
         }
@@ -1462,34 +5405,367 @@ fn handle_pipeline_builder_input(app: &mut App, key: KeyCode) {
     }
 }
 
-fn handle_results_input(app: &mut App, key: KeyCode) {
+fn handle_step_editor_input(app: &mut App, key: KeyCode) {
+    match key {
+        KeyCode::Esc => {
+            app.input_mode = InputMode::Normal;
+        }
+        KeyCode::Enter => match app.commit_step_editor() {
+            Ok(()) => app.input_mode = InputMode::Normal,
+            Err(e) => app.add_log(LogLevel::Error, e),
+        },
+        KeyCode::Down | KeyCode::Tab => {
+            app.step_editor.field = app.step_editor.field.next();
+        }
+        KeyCode::Up | KeyCode::BackTab => {
+            app.step_editor.field = app.step_editor.field.prev();
+        }
+        KeyCode::Left | KeyCode::Right => {
+            let forward = key == KeyCode::Right;
+            match app.step_editor.field {
+                StepField::Script => app.cycle_editor_script(forward),
+                StepField::Function => app.cycle_editor_function(forward),
+                _ => {}
+            }
+        }
+        KeyCode::Backspace => {
+            if let Some(text) = step_editor_text(&mut app.step_editor) {
+                text.pop();
+            }
+        }
+        KeyCode::Char(c) => {
+            if let Some(text) = step_editor_text(&mut app.step_editor) {
+                text.push(c);
+            }
+        }
+        _ => {}
+    }
+}
+
+fn handle_pipeline_inputs_input(app: &mut App, key: KeyCode) {
+    let count = app.param_inputs.len();
     match key {
-        KeyCode::Up => {
+        KeyCode::Esc => {
+            app.input_mode = InputMode::Normal;
+        }
+        KeyCode::Enter => {
+            if let Err(e) = app.submit_pipeline_inputs() {
+                app.add_log(LogLevel::Error, e);
+            }
+        }
+        KeyCode::Down | KeyCode::Tab if count > 0 => {
+            app.selected_param = (app.selected_param + 1) % count;
+        }
+        KeyCode::Up | KeyCode::BackTab if count > 0 => {
+            app.selected_param = (app.selected_param + count - 1) % count;
+        }
+        KeyCode::Backspace => {
+            if let Some(input) = app.param_inputs.get_mut(app.selected_param) {
+                input.pop();
+            }
+        }
+        KeyCode::Char(c) => {
+            if let Some(input) = app.param_inputs.get_mut(app.selected_param) {
+                input.push(c);
+            }
+        }
+        _ => {}
+    }
+}
+
+fn step_editor_text(editor: &mut StepEditor) -> Option<&mut String> {
+    match editor.field {
+        StepField::Id => Some(&mut editor.id),
+        StepField::Args => Some(&mut editor.args),
+        StepField::MapOver => Some(&mut editor.map_over),
+        StepField::Expect => Some(&mut editor.expect),
+        StepField::Description => Some(&mut editor.description),
+        StepField::Script | StepField::Function => None,
+    }
+}
+
+fn handle_run_history_input(app: &mut App, action: Action) {
+    match action {
+        Action::Up if app.selected_run > 0 => {
+            app.selected_run -= 1;
+            app.selected_run_step = 0;
+        }
+        Action::Down if app.selected_run < app.runs.len().saturating_sub(1) => {
+            app.selected_run += 1;
+            app.selected_run_step = 0;
+        }
+        Action::Left => {
+            app.selected_run_step = app.selected_run_step.saturating_sub(1);
+        }
+        Action::Right => {
+            let total = app.run_at(app.selected_run).map_or(0, |r| r.total_steps);
+            if app.selected_run_step < total.saturating_sub(1) {
+                app.selected_run_step += 1;
+            }
+        }
+        Action::Rerun if !app.runs.is_empty() => {
+            let _ = app.rerun_pipeline(app.selected_run, app.selected_run_step);
+        }
+        Action::RerunFailed => {
+            let failed = app
+                .run_at(app.selected_run)
+                .map(|r| r.first_failure().unwrap_or(r.steps.len()));
+            if let Some(from) = failed {
+                let _ = app.rerun_pipeline(app.selected_run, from);
+            }
+        }
+        Action::Compare => {
+            let id = app.run_at(app.selected_run).map(|r| r.id);
+            app.compare_run = if app.compare_run == id { None } else { id };
+        }
+        Action::ClearCompare => {
+            app.compare_run = None;
+        }
+        _ => {}
+    }
+}
+
+fn handle_results_input(app: &mut App, action: Action) {
+    match action {
+        Action::Up => {
             if app.selected_result > 0 {
                 app.selected_result -= 1;
             }
         }
-        KeyCode::Down => {
-            if app.selected_result < app.results.len().saturating_sub(1) {
+        Action::Down => {
+            if app.selected_result < app.filtered_results().len().saturating_sub(1) {
                 app.selected_result += 1;
             }
         }
+        Action::FilterStatus => {
+            app.results_filter.status = app.results_filter.status.next();
+            app.selected_result = 0;
+        }
+        Action::FilterScript => app.cycle_results_filter(ResultsFilterField::Script),
+        Action::FilterFunction => app.cycle_results_filter(ResultsFilterField::Function),
+        Action::FilterLanguage => app.cycle_results_filter(ResultsFilterField::Language),
+        Action::Search => app.input_mode = InputMode::SearchResults,
+        Action::Sort => {
+            app.results_sort = app.results_sort.next();
+            app.selected_result = 0;
+        }
+        Action::ResetFilters => {
+            app.results_filter = ResultsFilter::default();
+            app.selected_result = 0;
+        }
+        Action::Mark => {
+            let index = app.selected_result_index();
+            app.compare_result = if app.compare_result == index {
+                None
+            } else {
+                index
+            };
+        }
+        Action::Baseline => app.toggle_baseline(),
+        Action::Replay => {
+            if let Err(e) = app.replay_result() {
+                app.add_log(LogLevel::Error, e);
+            }
+        }
+        Action::ViewOutput => {
+            app.output_viewer = app
+                .filtered_results()
+                .get(app.selected_result)
+                .map(|r| viewer::OutputViewer::new(r));
+        }
+        Action::SendToTester => {
+            if let Err(e) = app.send_to_tester() {
+                app.add_log(LogLevel::Error, e);
+            }
+        }
+        Action::Diff => app.results_diff = !app.results_diff,
+        Action::ExportResults => {
+            app.input_mode = InputMode::ExportResults;
+            app.input_buffer = "results".to_string();
+        }
+        Action::ClearHistory if !app.results.is_empty() => {
+            app.input_mode = InputMode::ConfirmClearHistory;
+        }
         _ => {}
     }
 }
 
-fn handle_export_input(app: &mut App, key: KeyCode) {
-    match key {
-        KeyCode::Char('s') => {
+fn handle_statistics_input(app: &mut App, action: Action) {
+    let functions = stats::by_function(&app.results).len();
+    match action {
+        Action::Up if app.selected_stat > 0 => app.selected_stat -= 1,
+        Action::Down if app.selected_stat + 1 < functions => app.selected_stat += 1,
+        _ => {}
+    }
+}
+
+fn handle_logs_input(app: &mut App, action: Action) {
+    let last = app.filtered_logs().len().saturating_sub(1);
+    let current = app.selected_log.unwrap_or(last).min(last);
+    let select = |index: usize| (index < last).then_some(index);
+    let selected = match action {
+        Action::Up => Some(current.saturating_sub(1)),
+        Action::Down => select(current + 1),
+        Action::PageUp => Some(current.saturating_sub(10)),
+        Action::PageDown => select(current + 10),
+        Action::Top => Some(0),
+        Action::Bottom => None,
+        Action::ToggleInfo => return toggle_log_level(app, LogLevel::Info),
+        Action::ToggleSuccess => return toggle_log_level(app, LogLevel::Success),
+        Action::ToggleWarning => return toggle_log_level(app, LogLevel::Warning),
+        Action::ToggleError => return toggle_log_level(app, LogLevel::Error),
+        Action::Search => {
+            app.input_mode = InputMode::SearchLogs;
+            return;
+        }
+        Action::ResetFilters => {
+            app.log_filter = logs::LogFilter::default();
+            None
+        }
+        Action::ClearLogs => {
+            app.logs.clear();
+            None
+        }
+        _ => return,
+    };
+    app.selected_log = selected;
+}
+
+fn toggle_log_level(app: &mut App, level: LogLevel) {
+    app.log_filter.toggle(level);
+    app.selected_log = None;
+}
+
+fn handle_output_viewer_input(app: &mut App, key: KeyCode, modifiers: KeyModifiers) {
+    let Some(viewer) = app.output_viewer.as_mut() else {
+        return;
+    };
+
+    if viewer.searching {
+        match key {
+            KeyCode::Enter => {
+                viewer.searching = false;
+                viewer.jump(true);
+            }
+            KeyCode::Esc => {
+                viewer.searching = false;
+                viewer.search.clear();
+            }
+            KeyCode::Backspace => {
+                viewer.search.pop();
+            }
+            KeyCode::Char(c) => viewer.search.push(c),
+            _ => {}
+        }
+        return;
+    }
+
+    let Some(action) = app.keymap.action(Scope::Viewer, key, modifiers) else {
+        return;
+    };
+    match action {
+        Action::Close => app.output_viewer = None,
+        Action::Up => viewer.scroll_by(-1),
+        Action::Down => viewer.scroll_by(1),
+        Action::PageUp => viewer.scroll_by(-10),
+        Action::PageDown => viewer.scroll_by(10),
+        Action::Top => viewer.scroll = 0,
+        Action::Bottom => viewer.scroll_by(i32::from(u16::MAX)),
+        Action::Left if !viewer.wrap => viewer.hscroll = viewer.hscroll.saturating_sub(8),
+        Action::Right if !viewer.wrap => viewer.hscroll = viewer.hscroll.saturating_add(8),
+        Action::ToggleWrap => {
+            let line = viewer.top_line();
+            viewer.wrap = !viewer.wrap;
+            viewer.hscroll = 0;
+            viewer.scroll_to_line(line);
+        }
+        Action::TogglePretty if viewer.is_json() => {
+            viewer.pretty = !viewer.pretty;
+            viewer.scroll = 0;
+        }
+        Action::Search => {
+            viewer.searching = true;
+            viewer.search.clear();
+        }
+        Action::NextMatch => {
+            viewer.jump(true);
+        }
+        Action::PrevMatch => {
+            viewer.jump(false);
+        }
+        Action::Copy => {
+            let text = viewer.text();
+            match clipboard::copy(&text) {
+                Ok(()) => app.add_log(
+                    LogLevel::Success,
+                    format!(
+                        "Copied {} characters to the clipboard",
+                        text.chars().count()
+                    ),
+                ),
+                Err(e) => app.add_log(LogLevel::Error, format!("Copy failed: {}", e)),
+            }
+        }
+        _ => {}
+    }
+}
+
+fn handle_export_input(app: &mut App, action: Action) {
+    match action {
+        Action::SaveExport => {
             app.input_mode = InputMode::ExportName;
             app.input_buffer = "pipeline".to_string();
         }
+        Action::Down => {
+            app.export_format = app.export_format.next();
+            app.export_scroll = 0;
+        }
+        Action::Up => {
+            app.export_format = app.export_format.prev();
+            app.export_scroll = 0;
+        }
+        Action::PageDown => {
+            app.export_scroll = app.export_scroll.saturating_add(10);
+        }
+        Action::PageUp => {
+            app.export_scroll = app.export_scroll.saturating_sub(10);
+        }
+        Action::OutputDir => {
+            app.input_buffer = app.export_output_dir.display().to_string();
+            app.input_mode = InputMode::ExportDir;
+        }
+        Action::TogglePaths => {
+            app.export_relative_paths = !app.export_relative_paths;
+            app.add_log(
+                LogLevel::Info,
+                format!(
+                    "Exported projects load scripts by {} path",
+                    if app.export_relative_paths {
+                        "relative"
+                    } else {
+                        "absolute"
+                    }
+                ),
+            );
+        }
         _ => {}
     }
 }
 
+fn init_metacall() -> io::Result<impl Drop> {
+    initialize().map_err(|e| io::Error::other(format!("Failed to initialize MetaCall: {:?}", e)))
+}
+
 fn main() -> io::Result<()> {
     let args: Vec<String> = std::env::args().collect();
+
+    if args.get(1).is_some_and(|a| a == "run") {
+        let metacall = init_metacall()?;
+        let code = headless::run(&args[2..])?;
+        drop(metacall);
+        std::process::exit(code);
+    }
+
     let root_dir = if args.len() > 1 {
         PathBuf::from(&args[1])
     } else {
@@ -1501,20 +5777,21 @@ fn main() -> io::Result<()> {
         std::process::exit(1);
     }
 
-    let _metacall = initialize().map_err(|e| {
-        io::Error::new(
-            io::ErrorKind::Other,
-            format!("Failed to initialize MetaCall: {:?}", e),
-        )
-    })?;
+    let _metacall = init_metacall()?;
 
     enable_raw_mode()?;
     let mut stdout = io::stdout();
-    execute!(stdout, EnterAlternateScreen)?;
+    execute!(
+        stdout,
+        EnterAlternateScreen,
+        EnableBracketedPaste,
+        EnableMouseCapture
+    )?;
     let backend = CrosstermBackend::new(stdout);
     let mut terminal = Terminal::new(backend)?;
 
     let mut app = App::new(root_dir);
+    app.load_config();
     let mut last_tick = Instant::now();
     let tick_rate = Duration::from_millis(250);
 
@@ -1526,12 +5803,19 @@ fn main() -> io::Result<()> {
             .unwrap_or_else(|| Duration::from_secs(0));
 
         if event::poll(timeout)? {
-            if let Event::Key(key) = event::read()? {
-                if key.kind == KeyEventKind::Press {
-                    if handle_input(&mut app, key.code, key.modifiers)? {
-                        break;
-                    }
+            let quit = match event::read()? {
+                Event::Key(key) if key.kind == KeyEventKind::Press => {
+                    handle_input(&mut app, key.code, key.modifiers)?
                 }
+                Event::Paste(text) => handle_paste(&mut app, &text)?,
+                Event::Mouse(mouse) => {
+                    handle_mouse(&mut app, mouse);
+                    false
+                }
+                _ => false,
+            };
+            if quit {
+                break;
             }
         }
 
@@ -1541,8 +5825,275 @@ fn main() -> io::Result<()> {
     }
 
     disable_raw_mode()?;
-    execute!(terminal.backend_mut(), LeaveAlternateScreen)?;
+    execute!(
+        terminal.backend_mut(),
+        LeaveAlternateScreen,
+        DisableBracketedPaste,
+        DisableMouseCapture
+    )?;
     terminal.show_cursor()?;
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn step(id: &str, function: &str, args: &[&str]) -> PipelineStep {
+        PipelineStep {
+            id: id.to_string(),
+            script: "calc.py".to_string(),
+            function: function.to_string(),
+            args: args.iter().map(|a| a.to_string()).collect(),
+            description: String::new(),
+            kind: StepKind::Call,
+            expect: Vec::new(),
+        }
+    }
+
+    fn step_run(step: PipelineStep, output: Value, success: bool) -> StepRun {
+        StepRun {
+            result: ExecutionResult {
+                function: format!("{}::{}", step.script, step.function),
+                args: step.args.clone(),
+                output: values::display(&output),
+                value: Some(output),
+                duration_ms: 1,
+                success,
+                timestamp: time::now(),
+                run_id: Some(1),
+                changed: false,
+                mapped: false,
+                replay_of: None,
+                assertions: Vec::new(),
+            },
+            step,
+            cached: false,
+            assertions: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn resolves_references_in_args() {
+        let outputs = HashMap::from([
+            ("step_1".to_string(), json!([1, 2])),
+            ("params.name".to_string(), json!("Ada")),
+        ]);
+        assert_eq!(
+            resolve_args(
+                &[
+                    "${step_1}".to_string(),
+                    " ${params.name} ".to_string(),
+                    "3".to_string(),
+                ],
+                &outputs
+            )
+            .unwrap(),
+            [json!([1, 2]), json!("Ada"), json!(3)]
+        );
+        assert_eq!(
+            resolve_arg("{\"xs\": ${step_1}}", &outputs).unwrap(),
+            json!({"xs": [1, 2]})
+        );
+        assert_eq!(
+            resolve_arg("Hello ${params.name}!", &outputs).unwrap(),
+            json!("Hello Ada!")
+        );
+        assert!(
+            resolve_arg("${step_2}", &outputs)
+                .unwrap_err()
+                .contains("step_2")
+        );
+        assert!(resolve_arg("${step_1", &outputs).is_err());
+    }
+
+    #[test]
+    fn maps_a_function_over_items() {
+        let mut calls = Vec::new();
+        let output = map_items(&[json!(1), json!(2), json!(3)], &[json!(10)], |args| {
+            calls.push(args.to_vec());
+            Ok(json!(args[0].as_i64().unwrap() * args[1].as_i64().unwrap()))
+        });
+        assert_eq!(output.unwrap(), json!([10, 20, 30]));
+        assert_eq!(calls[2], [json!(3), json!(10)]);
+
+        let output = map_items(&[json!(1), json!(0), json!(2)], &[], |args| {
+            match args[0].as_i64() {
+                Some(0) => Err("division by zero".to_string()),
+                _ => Ok(json!(1)),
+            }
+        });
+        assert_eq!(output.unwrap_err(), "Item 1: division by zero");
+        assert_eq!(map_items(&[], &[], |_| Ok(json!(1))).unwrap(), json!([]));
+    }
+
+    #[test]
+    fn reuses_only_unchanged_steps_on_rerun() {
+        let first = step_run(step("step_1", "add", &["1", "2"]), json!(3), true);
+        let second = step_run(step("step_2", "square", &["${step_1}"]), json!(9), true);
+        let recorded = HashMap::from([
+            ("step_1".to_string(), json!(3)),
+            ("step_2".to_string(), json!(9)),
+        ]);
+
+        let outputs = HashMap::new();
+        assert!(reusable(&first.step, Some(&first), &outputs, &recorded).is_ok());
+        assert!(reusable(&first.step, None, &outputs, &recorded).is_err());
+        let failed = step_run(first.step.clone(), json!("boom"), false);
+        assert!(reusable(&first.step, Some(&failed), &outputs, &recorded).is_err());
+
+        let edited = step("step_1", "add", &["1", "5"]);
+        assert!(reusable(&edited, Some(&first), &outputs, &recorded).is_err());
+        let renamed = step("step_1", "subtract", &["1", "2"]);
+        assert!(reusable(&renamed, Some(&first), &outputs, &recorded).is_err());
+        let mut mapped = first.step.clone();
+        mapped.kind = StepKind::Map {
+            over: "[1]".to_string(),
+        };
+        assert!(reusable(&mapped, Some(&first), &outputs, &recorded).is_err());
+
+        // A step depending on one that ran again is reused only when that
+        // step's output did not change.
+        let same = HashMap::from([("step_1".to_string(), json!(3))]);
+        assert!(reusable(&second.step, Some(&second), &same, &recorded).is_ok());
+        let changed = HashMap::from([("step_1".to_string(), json!(4))]);
+        assert!(reusable(&second.step, Some(&second), &changed, &recorded).is_err());
+    }
+
+    #[test]
+    fn rechecks_references_and_confirms_replacing_modified_steps() {
+        let dir = std::env::temp_dir().join(format!("polyglot-import-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join("calc.py"), "def add(a, b):\n    return a + b\n").unwrap();
+        let file = PipelineFile {
+            params: Vec::new(),
+            steps: vec![
+                step("step_1", "add", &["1", "2"]),
+                PipelineStep {
+                    script: "util.py".to_string(),
+                    ..step("step_2", "double", &["${step_1}"])
+                },
+            ],
+        };
+        fs::write(
+            dir.join("pipeline.json"),
+            serde_json::to_string(&file).unwrap(),
+        )
+        .unwrap();
+
+        let mut app = App::new(dir.clone());
+        app.export_output_dir = dir.clone();
+        app.import_pipeline("pipeline.json").unwrap();
+        assert_eq!(app.pipeline.len(), 2);
+        assert_eq!(app.import_issues.len(), 1);
+
+        fs::write(dir.join("util.py"), "def double(x):\n    return 2 * x\n").unwrap();
+        app.scan_scripts();
+        assert!(app.import_issues.is_empty());
+
+        app.selected_pipeline_step = 1;
+        app.duplicate_step();
+        app.import_pipeline("pipeline.json").unwrap();
+        assert_eq!(app.input_mode, InputMode::ConfirmImport);
+        assert_eq!(app.pipeline.len(), 3);
+
+        let (path, file) = app.pending_import.take().unwrap();
+        app.apply_import(path, file);
+        assert_eq!(app.pipeline.len(), 2);
+        assert!(!app.pipeline_modified);
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn parses_and_formats_arg_lists() {
+        assert_eq!(parse_arg_list(""), Vec::<String>::new());
+        assert_eq!(parse_arg_list("  "), Vec::<String>::new());
+        assert_eq!(parse_arg_list("1, a\\, b ,"), ["1", "a, b", ""]);
+        assert_eq!(parse_arg_list("x,,y"), ["x", "", "y"]);
+
+        let args = ["a,b".to_string(), String::new(), "${step_1}".to_string()];
+        assert_eq!(format_arg_list(&args), "a\\,b, , ${step_1}");
+        assert_eq!(parse_arg_list(&format_arg_list(&args)), args);
+    }
+
+    #[test]
+    fn keeps_json_args_whole() {
+        let args = parse_arg_list(r#"[1,2,3], {"a":1,"b":[2,3]}, "x, y", plain"#);
+        assert_eq!(
+            args,
+            ["[1,2,3]", r#"{"a":1,"b":[2,3]}"#, r#""x, y""#, "plain"]
+        );
+        assert_eq!(values::parse_arg(&args[1]), json!({"a": 1, "b": [2, 3]}));
+        assert_eq!(
+            format_arg_list(&args),
+            r#"[1,2,3], {"a":1,"b":[2,3]}, "x, y", plain"#
+        );
+        assert_eq!(parse_arg_list(&format_arg_list(&args)), args);
+    }
+
+    #[test]
+    fn keeps_a_missing_function_and_regenerates_default_descriptions() {
+        let dir = std::env::temp_dir().join(format!("polyglot-step-edit-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join("calc.py"), "def add(a, b):\n    return a + b\n").unwrap();
+
+        let mut app = App::new(dir.clone());
+        app.scripts[0].loaded = true;
+        app.scripts[0].functions = vec!["add".to_string()];
+        let mut gone = step("step_1", "subtract", &["1", "2"]);
+        gone.description = default_description(&gone);
+        app.pipeline = vec![gone];
+
+        app.begin_step_edit();
+        assert_eq!(
+            app.step_editor.missing_function.as_deref(),
+            Some("subtract")
+        );
+        assert_eq!(app.step_editor.description, "");
+
+        app.step_editor.args = "3, 4".to_string();
+        app.commit_step_editor().unwrap();
+        assert_eq!(app.pipeline[0].function, "subtract");
+        assert_eq!(app.pipeline[0].description, "subtract(3, 4)");
+
+        app.begin_step_edit();
+        app.cycle_editor_function(true);
+        app.commit_step_editor().unwrap();
+        assert_eq!(app.pipeline[0].function, "add");
+        assert_eq!(app.pipeline[0].description, "add(3, 4)");
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn keeps_the_selected_result_when_history_is_trimmed() {
+        let dir = std::env::temp_dir().join(format!("polyglot-trim-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+
+        let mut app = App::new(dir.clone());
+        app.history = Some(history::History::new(&dir, 3));
+        let record = |app: &mut App, name: &str| {
+            app.record_result(name.to_string(), Vec::new(), Ok(json!(1)), 1, None, false);
+        };
+        let selected = |app: &App| app.filtered_results()[app.selected_result].function.clone();
+
+        for name in ["a", "b", "c"] {
+            record(&mut app, name);
+        }
+        app.selected_result = 1;
+        assert_eq!(selected(&app), "b");
+
+        record(&mut app, "d");
+        assert_eq!(app.results.len(), 3);
+        assert_eq!(selected(&app), "b");
+
+        record(&mut app, "e");
+        assert_eq!(app.selected_result, 2);
+        assert_eq!(selected(&app), "c");
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
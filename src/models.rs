@@ -1,5 +1,6 @@
+use crate::time::{self, Timestamp};
 use serde::{Deserialize, Serialize};
-use std::path::PathBuf;
+use std::{collections::BTreeMap, path::PathBuf};
 
 #[derive(Debug, Clone)]
 pub struct Script {
@@ -17,9 +18,33 @@ pub struct ExecutionResult {
     pub function: String,
     pub args: Vec<String>,
     pub output: String,
+    #[serde(default)]
+    pub value: Option<serde_json::Value>,
     pub duration_ms: u64,
     pub success: bool,
-    pub timestamp: String,
+    #[serde(with = "time::serde_rfc3339")]
+    pub timestamp: Timestamp,
+    #[serde(default)]
+    pub run_id: Option<u64>,
+    /// Set when the call returned something else than its baseline, or than
+    /// the previous result with the same args.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub changed: bool,
+    /// Set for the results of `Map` steps, whose first arg is the array the
+    /// function was mapped over.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub mapped: bool,
+    /// The time of the result this one replays.
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        with = "time::serde_rfc3339_option"
+    )]
+    pub replay_of: Option<Timestamp>,
+    /// The expectations of the pipeline step that made the call, checked
+    /// against it.
+    #[serde(default)]
+    pub assertions: Vec<AssertionResult>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -29,6 +54,211 @@ pub struct PipelineStep {
     pub function: String,
     pub args: Vec<String>,
     pub description: String,
+    #[serde(default)]
+    pub kind: StepKind,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub expect: Vec<Expectation>,
+}
+
+/// A named input of the pipeline, referenced from step args as `${params.name}`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PipelineParam {
+    pub name: String,
+    #[serde(rename = "type", default)]
+    pub kind: ParamType,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub default: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ParamType {
+    #[default]
+    String,
+    Int,
+    Float,
+    Bool,
+    Json,
+}
+
+impl ParamType {
+    pub fn name(self) -> &'static str {
+        match self {
+            ParamType::String => "string",
+            ParamType::Int => "int",
+            ParamType::Float => "float",
+            ParamType::Bool => "bool",
+            ParamType::Json => "json",
+        }
+    }
+}
+
+/// On-disk pipeline format. Older files that are a bare array of steps are
+/// still accepted.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(from = "PipelineFileRepr")]
+pub struct PipelineFile {
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub params: Vec<PipelineParam>,
+    pub steps: Vec<PipelineStep>,
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum PipelineFileRepr {
+    Steps(Vec<PipelineStep>),
+    File {
+        #[serde(default)]
+        params: Vec<PipelineParam>,
+        steps: Vec<PipelineStep>,
+    },
+}
+
+impl From<PipelineFileRepr> for PipelineFile {
+    fn from(repr: PipelineFileRepr) -> Self {
+        match repr {
+            PipelineFileRepr::Steps(steps) => PipelineFile {
+                params: Vec::new(),
+                steps,
+            },
+            PipelineFileRepr::File { params, steps } => PipelineFile { params, steps },
+        }
+    }
+}
+
+/// A check applied to a step's result once it has run.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum Expectation {
+    Equals {
+        value: serde_json::Value,
+    },
+    Contains {
+        value: String,
+    },
+    Regex {
+        pattern: String,
+    },
+    JsonPath {
+        path: String,
+        value: serde_json::Value,
+    },
+    MaxDuration {
+        ms: u64,
+    },
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AssertionResult {
+    pub expectation: String,
+    pub passed: bool,
+    pub message: String,
+}
+
+impl ExecutionResult {
+    /// The typed return value, falling back to the textual output for results
+    /// recorded without one.
+    pub fn output_value(&self) -> serde_json::Value {
+        self.value
+            .clone()
+            .unwrap_or_else(|| serde_json::Value::String(self.output.clone()))
+    }
+
+    /// Whether one of the step's expectations failed for this call.
+    pub fn failed_assertions(&self) -> bool {
+        self.assertions.iter().any(|a| !a.passed)
+    }
+
+    /// Whether the call succeeded and met all of its step's expectations.
+    pub fn passed(&self) -> bool {
+        self.success && !self.failed_assertions()
+    }
+
+    /// The script part of `function`, which is recorded as `script::function`.
+    pub fn script(&self) -> &str {
+        self.function
+            .split_once("::")
+            .map_or("", |(script, _)| script)
+    }
+
+    pub fn function_name(&self) -> &str {
+        self.function
+            .split_once("::")
+            .map_or(self.function.as_str(), |(_, name)| name)
+    }
+}
+
+/// A `Map` step calls its function once per element of the array that `over`
+/// resolves to, passing the element as the first argument followed by `args`.
+/// The results are collected into an array in the original order. Items are
+/// called one at a time, since MetaCall calls are not thread-safe; the
+/// `concurrency` of older pipeline files is ignored.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum StepKind {
+    #[default]
+    Call,
+    Map {
+        over: String,
+    },
+}
+
+/// One execution of the pipeline. Steps after a failure are not run, so
+/// `steps` may be shorter than `total_steps`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PipelineRun {
+    pub id: u64,
+    #[serde(with = "time::serde_rfc3339")]
+    pub started_at: Timestamp,
+    #[serde(with = "time::serde_rfc3339")]
+    pub finished_at: Timestamp,
+    pub duration_ms: u64,
+    pub total_steps: usize,
+    pub resumed_from: Option<u64>,
+    #[serde(default)]
+    pub params: BTreeMap<String, serde_json::Value>,
+    /// The pipeline as it was when the run started; a rerun runs it again.
+    #[serde(default)]
+    pub pipeline: Vec<PipelineStep>,
+    pub steps: Vec<StepRun>,
+}
+
+impl PipelineRun {
+    pub fn success(&self) -> bool {
+        self.steps.len() == self.total_steps
+            && self
+                .steps
+                .iter()
+                .all(|s| s.result.success && s.assertions.iter().all(|a| a.passed))
+    }
+
+    /// Number of passed and failed assertions across all steps.
+    pub fn assertion_counts(&self) -> (usize, usize) {
+        let assertions = self.steps.iter().flat_map(|s| &s.assertions);
+        assertions.fold((0, 0), |(passed, failed), a| {
+            if a.passed {
+                (passed + 1, failed)
+            } else {
+                (passed, failed + 1)
+            }
+        })
+    }
+
+    /// The first step that failed to run or failed one of its assertions.
+    pub fn first_failure(&self) -> Option<usize> {
+        self.steps
+            .iter()
+            .position(|s| !s.result.success || s.assertions.iter().any(|a| !a.passed))
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StepRun {
+    pub step: PipelineStep,
+    pub result: ExecutionResult,
+    pub cached: bool,
+    #[serde(default)]
+    pub assertions: Vec<AssertionResult>,
 }
 
 #[derive(Debug, Clone, PartialEq)]
@@ -36,8 +266,25 @@ pub enum View {
     ScriptBrowser,
     FunctionTester,
     PipelineBuilder,
+    RunHistory,
     ResultsExplorer,
+    Statistics,
     Export,
+    Logs,
+}
+
+impl View {
+    /// The views in tab order.
+    pub const ALL: [View; 8] = [
+        View::ScriptBrowser,
+        View::FunctionTester,
+        View::PipelineBuilder,
+        View::RunHistory,
+        View::ResultsExplorer,
+        View::Statistics,
+        View::Export,
+        View::Logs,
+    ];
 }
 
 #[derive(Debug, Clone, PartialEq)]
@@ -45,27 +292,449 @@ pub enum InputMode {
     Normal,
     EditingArgs,
     AddingStep,
+    EditingStep,
+    EditingParams,
+    PipelineInputs,
     ExportName,
+    ExportDir,
+    ConfirmOverwrite,
+    ExportResults,
+    ImportPath,
+    ConfirmClearHistory,
+    ConfirmImport,
+    SearchResults,
+    SearchLogs,
+    ConfirmQuit,
+    CommandPalette,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum StepField {
+    Id,
+    Script,
+    Function,
+    Args,
+    MapOver,
+    Expect,
+    Description,
+}
+
+impl StepField {
+    pub const ALL: [StepField; 7] = [
+        StepField::Id,
+        StepField::Script,
+        StepField::Function,
+        StepField::Args,
+        StepField::MapOver,
+        StepField::Expect,
+        StepField::Description,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            StepField::Id => "Id",
+            StepField::Script => "Script",
+            StepField::Function => "Function",
+            StepField::Args => "Args",
+            StepField::MapOver => "Map over",
+            StepField::Expect => "Expect",
+            StepField::Description => "Description",
+        }
+    }
+
+    pub fn next(self) -> Self {
+        let i = Self::ALL.iter().position(|f| *f == self).unwrap_or(0);
+        Self::ALL[(i + 1) % Self::ALL.len()]
+    }
+
+    pub fn prev(self) -> Self {
+        let i = Self::ALL.iter().position(|f| *f == self).unwrap_or(0);
+        Self::ALL[(i + Self::ALL.len() - 1) % Self::ALL.len()]
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ExportFormat {
+    Rust,
+    RustTests,
+    Python,
+    Node,
+    Json,
+    Yaml,
+    Shell,
+    Markdown,
+    Mermaid,
+}
+
+impl ExportFormat {
+    pub const ALL: [ExportFormat; 9] = [
+        ExportFormat::Rust,
+        ExportFormat::RustTests,
+        ExportFormat::Python,
+        ExportFormat::Node,
+        ExportFormat::Json,
+        ExportFormat::Yaml,
+        ExportFormat::Shell,
+        ExportFormat::Markdown,
+        ExportFormat::Mermaid,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            ExportFormat::Rust => "Rust (Cargo project)",
+            ExportFormat::RustTests => "Rust integration tests",
+            ExportFormat::Python => "Python (metacall on PyPI)",
+            ExportFormat::Node => "Node.js (metacall on npm)",
+            ExportFormat::Json => "Pipeline JSON",
+            ExportFormat::Yaml => "Pipeline YAML",
+            ExportFormat::Shell => "metacallcli script",
+            ExportFormat::Markdown => "Markdown",
+            ExportFormat::Mermaid => "Mermaid diagram",
+        }
+    }
+
+    /// The file extension of single file formats; the others export a
+    /// project directory.
+    pub fn extension(self) -> Option<&'static str> {
+        match self {
+            ExportFormat::Rust
+            | ExportFormat::RustTests
+            | ExportFormat::Python
+            | ExportFormat::Node => None,
+            ExportFormat::Json => Some("json"),
+            ExportFormat::Yaml => Some("yaml"),
+            ExportFormat::Shell => Some("sh"),
+            ExportFormat::Markdown => Some("md"),
+            ExportFormat::Mermaid => Some("mmd"),
+        }
+    }
+
+    pub fn next(self) -> Self {
+        let i = Self::ALL.iter().position(|f| *f == self).unwrap_or(0);
+        Self::ALL[(i + 1) % Self::ALL.len()]
+    }
+
+    pub fn prev(self) -> Self {
+        let i = Self::ALL.iter().position(|f| *f == self).unwrap_or(0);
+        Self::ALL[(i + Self::ALL.len() - 1) % Self::ALL.len()]
+    }
+}
+
+/// Which results the Results Explorer shows.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ResultsFilter {
+    pub status: StatusFilter,
+    pub script: Option<String>,
+    pub function: Option<String>,
+    pub language: Option<String>,
+    /// Case-insensitive text looked up in the args and the output.
+    pub search: String,
+}
+
+impl ResultsFilter {
+    /// Whether `result`, whose script is written in `language`, is shown.
+    pub fn matches(&self, result: &ExecutionResult, language: Option<&str>) -> bool {
+        self.status.matches(result)
+            && self
+                .script
+                .as_ref()
+                .is_none_or(|script| result.script() == script)
+            && self
+                .function
+                .as_ref()
+                .is_none_or(|function| result.function_name() == function)
+            && self
+                .language
+                .as_ref()
+                .is_none_or(|wanted| language == Some(wanted.as_str()))
+            && self.matches_search(result)
+    }
+
+    fn matches_search(&self, result: &ExecutionResult) -> bool {
+        let needle = self.search.trim().to_lowercase();
+        needle.is_empty()
+            || result.output.to_lowercase().contains(&needle)
+            || result
+                .args
+                .iter()
+                .any(|arg| arg.to_lowercase().contains(&needle))
+    }
+
+    pub fn is_active(&self) -> bool {
+        *self != Self::default()
+    }
+
+    /// A short description of the active filters, e.g. `Failed, calc.py, "42"`.
+    pub fn summary(&self) -> String {
+        let mut parts = Vec::new();
+        if self.status != StatusFilter::All {
+            parts.push(self.status.label().to_string());
+        }
+        parts.extend(self.script.clone());
+        parts.extend(self.function.clone());
+        parts.extend(self.language.clone());
+        if !self.search.trim().is_empty() {
+            parts.push(format!("\"{}\"", self.search.trim()));
+        }
+        if parts.is_empty() {
+            "All".to_string()
+        } else {
+            parts.join(", ")
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub enum StatusFilter {
+    #[default]
+    All,
+    Passed,
+    Failed,
+}
+
+impl StatusFilter {
+    pub fn label(self) -> &'static str {
+        match self {
+            StatusFilter::All => "All",
+            StatusFilter::Passed => "Passed",
+            StatusFilter::Failed => "Failed",
+        }
+    }
+
+    pub fn matches(self, result: &ExecutionResult) -> bool {
+        match self {
+            StatusFilter::All => true,
+            StatusFilter::Passed => result.success,
+            StatusFilter::Failed => !result.success,
+        }
+    }
+
+    pub fn next(self) -> Self {
+        match self {
+            StatusFilter::All => StatusFilter::Passed,
+            StatusFilter::Passed => StatusFilter::Failed,
+            StatusFilter::Failed => StatusFilter::All,
+        }
+    }
+}
+
+/// The results filters that pick one of the values seen in the results.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ResultsFilterField {
+    Script,
+    Function,
+    Language,
+}
+
+/// The order of the Results Explorer list.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ResultsSort {
+    Newest,
+    Oldest,
+    Slowest,
+    Fastest,
+}
+
+impl ResultsSort {
+    pub fn label(self) -> &'static str {
+        match self {
+            ResultsSort::Newest => "Newest first",
+            ResultsSort::Oldest => "Oldest first",
+            ResultsSort::Slowest => "Slowest first",
+            ResultsSort::Fastest => "Fastest first",
+        }
+    }
+
+    pub fn next(self) -> Self {
+        match self {
+            ResultsSort::Newest => ResultsSort::Oldest,
+            ResultsSort::Oldest => ResultsSort::Slowest,
+            ResultsSort::Slowest => ResultsSort::Fastest,
+            ResultsSort::Fastest => ResultsSort::Newest,
+        }
+    }
+
+    /// Orders results given in recording order. Ties keep the latest
+    /// recorded first, except when sorting oldest first.
+    pub fn apply(self, results: &mut Vec<&ExecutionResult>) {
+        if self != ResultsSort::Oldest {
+            results.reverse();
+        }
+        match self {
+            ResultsSort::Newest => results.sort_by_key(|r| std::cmp::Reverse(r.timestamp)),
+            ResultsSort::Oldest => results.sort_by_key(|r| r.timestamp),
+            ResultsSort::Slowest => results.sort_by_key(|r| std::cmp::Reverse(r.duration_ms)),
+            ResultsSort::Fastest => results.sort_by_key(|r| r.duration_ms),
+        }
+    }
+}
+
+/// File formats execution results can be exported to.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ResultsFormat {
+    JsonLines,
+    Csv,
+    JUnit,
+}
+
+impl ResultsFormat {
+    pub const ALL: [ResultsFormat; 3] = [
+        ResultsFormat::JsonLines,
+        ResultsFormat::Csv,
+        ResultsFormat::JUnit,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            ResultsFormat::JsonLines => "JSON Lines",
+            ResultsFormat::Csv => "CSV",
+            ResultsFormat::JUnit => "JUnit XML",
+        }
+    }
+
+    pub fn extension(self) -> &'static str {
+        match self {
+            ResultsFormat::JsonLines => "jsonl",
+            ResultsFormat::Csv => "csv",
+            ResultsFormat::JUnit => "xml",
+        }
+    }
+
+    pub fn next(self) -> Self {
+        let i = Self::ALL.iter().position(|f| *f == self).unwrap_or(0);
+        Self::ALL[(i + 1) % Self::ALL.len()]
+    }
+
+    pub fn prev(self) -> Self {
+        let i = Self::ALL.iter().position(|f| *f == self).unwrap_or(0);
+        Self::ALL[(i + Self::ALL.len() - 1) % Self::ALL.len()]
+    }
+}
+
+/// Form state used by the Pipeline Builder while inserting or editing a step.
+/// `position` is the index being edited, or the insertion index when adding.
+#[derive(Debug, Clone)]
+pub struct StepEditor {
+    pub position: usize,
+    pub field: StepField,
+    pub id: String,
+    pub script: usize,
+    pub function: usize,
+    pub args: String,
+    pub map_over: String,
+    pub expect: String,
+    pub description: String,
+    /// The edited step's function when the script does not define it. It is
+    /// kept until another function or script is picked.
+    pub missing_function: Option<String>,
 }
 
 #[derive(Debug, Clone)]
 pub struct FunctionInput {
     pub selected_function: usize,
     pub args: Vec<String>,
-    pub current_arg: String,
 }
 
 #[derive(Debug, Clone)]
 pub struct LogEntry {
-    pub timestamp: String,
+    pub timestamp: Timestamp,
     pub level: LogLevel,
     pub message: String,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum LogLevel {
     Info,
     Success,
     Error,
     Warning,
 }
+
+impl LogLevel {
+    pub const ALL: [LogLevel; 4] = [
+        LogLevel::Info,
+        LogLevel::Success,
+        LogLevel::Warning,
+        LogLevel::Error,
+    ];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            LogLevel::Info => "info",
+            LogLevel::Success => "success",
+            LogLevel::Warning => "warning",
+            LogLevel::Error => "error",
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn result(function: &str, args: &[&str], duration_ms: u64, success: bool) -> ExecutionResult {
+        ExecutionResult {
+            function: function.to_string(),
+            args: args.iter().map(|a| a.to_string()).collect(),
+            output: format!("{} done", function),
+            value: None,
+            duration_ms,
+            success,
+            timestamp: time::parse("2024-01-01T12:00:00Z").unwrap(),
+            run_id: None,
+            changed: false,
+            mapped: false,
+            replay_of: None,
+            assertions: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn results_filter_and_sort() {
+        let results = [
+            result("calc.py::add", &["1", "2"], 5, true),
+            result("calc.py::div", &["1", "0"], 9, false),
+            result("text.js::upper", &["Hello"], 5, true),
+        ];
+
+        let filter = ResultsFilter {
+            script: Some("calc.py".to_string()),
+            ..Default::default()
+        };
+        assert!(filter.matches(&results[1], Some("Python")));
+        assert!(!filter.matches(&results[2], Some("JavaScript")));
+
+        let filter = ResultsFilter {
+            status: StatusFilter::Passed,
+            language: Some("JavaScript".to_string()),
+            search: " hello ".to_string(),
+            ..Default::default()
+        };
+        assert!(filter.matches(&results[2], Some("JavaScript")));
+        assert!(!filter.matches(&results[0], Some("Python")));
+        assert_eq!(filter.summary(), "Passed, JavaScript, \"hello\"");
+
+        let order = |sort: ResultsSort| {
+            let mut shown: Vec<&ExecutionResult> = results.iter().collect();
+            sort.apply(&mut shown);
+            shown.iter().map(|r| r.function_name()).collect::<Vec<_>>()
+        };
+        assert_eq!(order(ResultsSort::Newest), ["upper", "div", "add"]);
+        assert_eq!(order(ResultsSort::Oldest), ["add", "div", "upper"]);
+        assert_eq!(order(ResultsSort::Slowest), ["div", "upper", "add"]);
+        assert_eq!(order(ResultsSort::Fastest), ["upper", "add", "div"]);
+    }
+
+    #[test]
+    fn loads_map_steps_saved_with_a_concurrency() {
+        let kind: StepKind =
+            serde_json::from_str(r#"{"type": "map", "over": "${nums}", "concurrency": 4}"#)
+                .unwrap();
+        assert_eq!(
+            kind,
+            StepKind::Map {
+                over: "${nums}".to_string()
+            }
+        );
+    }
+}
@@ -0,0 +1,130 @@
+use crate::models::View;
+use ratatui::{
+    layout::{Margin, Position, Rect},
+    text::Line,
+};
+
+/// A list that can be clicked to select an item.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum List {
+    Scripts,
+    Functions,
+    Steps,
+    Runs,
+    Results,
+    Stats,
+    Logs,
+}
+
+/// What a click landed on.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Target {
+    Tab(View),
+    Item(List, usize),
+}
+
+struct ListArea {
+    list: List,
+    rows: Rect,
+    offset: usize,
+    len: usize,
+}
+
+/// The clickable parts of the last frame, recorded while rendering it.
+#[derive(Default)]
+pub struct Regions {
+    tabs: Vec<(Rect, View)>,
+    lists: Vec<ListArea>,
+}
+
+impl Regions {
+    pub fn clear(&mut self) {
+        self.tabs.clear();
+        self.lists.clear();
+    }
+
+    /// Records the titles of a bordered `Tabs` widget drawn in `area`. Each
+    /// title takes its width plus the one-column padding on both sides, and
+    /// titles are separated by a one-column divider.
+    pub fn tabs(&mut self, area: Rect, titles: &[(&str, View)]) {
+        let inner = area.inner(Margin::new(1, 1));
+        let mut x = inner.x;
+        for (title, view) in titles {
+            let width = Line::from(*title).width() as u16 + 2;
+            let tab = Rect::new(x, inner.y, width, 1).intersection(inner);
+            if tab.is_empty() {
+                break;
+            }
+            self.tabs.push((tab, view.clone()));
+            x = x.saturating_add(width + 1);
+        }
+    }
+
+    /// Records a bordered list drawn in `area`, one item per row below
+    /// `header` rows, starting with the item at `offset`.
+    pub fn list(&mut self, list: List, area: Rect, header: u16, offset: usize, len: usize) {
+        let inner = area.inner(Margin::new(1, 1));
+        let rows = Rect {
+            y: inner.y.saturating_add(header),
+            height: inner.height.saturating_sub(header),
+            ..inner
+        };
+        self.lists.push(ListArea {
+            list,
+            rows,
+            offset,
+            len,
+        });
+    }
+
+    /// The tab or list item at a terminal cell, if any.
+    pub fn target(&self, column: u16, row: u16) -> Option<Target> {
+        let position = Position::new(column, row);
+        if let Some((_, view)) = self.tabs.iter().find(|(area, _)| area.contains(position)) {
+            return Some(Target::Tab(view.clone()));
+        }
+        self.lists
+            .iter()
+            .filter(|list| list.rows.contains(position))
+            .find_map(|list| {
+                let index = list.offset + (row - list.rows.y) as usize;
+                (index < list.len).then_some(Target::Item(list.list, index))
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn finds_the_tab_or_item_under_the_cursor() {
+        let mut regions = Regions::default();
+        regions.tabs(
+            Rect::new(0, 0, 40, 3),
+            &[
+                ("Scripts", View::ScriptBrowser),
+                ("Tester", View::FunctionTester),
+            ],
+        );
+        // │ Scripts │ Tester
+        assert_eq!(regions.target(1, 1), Some(Target::Tab(View::ScriptBrowser)));
+        assert_eq!(regions.target(9, 1), Some(Target::Tab(View::ScriptBrowser)));
+        assert_eq!(regions.target(10, 1), None);
+        assert_eq!(
+            regions.target(11, 1),
+            Some(Target::Tab(View::FunctionTester))
+        );
+        assert_eq!(regions.target(19, 1), None);
+
+        regions.list(List::Steps, Rect::new(0, 3, 20, 6), 1, 5, 7);
+        assert_eq!(regions.target(1, 4), None);
+        assert_eq!(regions.target(1, 5), Some(Target::Item(List::Steps, 5)));
+        assert_eq!(regions.target(18, 6), Some(Target::Item(List::Steps, 6)));
+        assert_eq!(regions.target(1, 7), None);
+        assert_eq!(regions.target(0, 5), None);
+
+        regions.clear();
+        assert_eq!(regions.target(1, 1), None);
+    }
+}
@@ -0,0 +1,222 @@
+use crate::keymap::{Action, Scope};
+use crate::models::View;
+
+/// What choosing an entry of the command palette does.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Command {
+    /// Opens a function of a loaded script in the Function Tester.
+    Function {
+        script: usize,
+        function: usize,
+    },
+    View(View),
+    /// Runs an action in the view of its scope.
+    Action(Scope, Action),
+}
+
+#[derive(Debug, Clone)]
+pub struct Entry {
+    /// The text searched, e.g. `calc.py::add` or `Pipeline Builder: Execute
+    /// pipeline`.
+    pub label: String,
+    /// Shown next to the label: the language of a function or the keys of
+    /// an action.
+    pub detail: String,
+    /// Also searched when the label does not match, e.g. `run pipeline`.
+    pub keywords: String,
+    pub command: Command,
+}
+
+/// An entry matching the query, with the positions of the matched
+/// characters in its label.
+pub struct Match<'a> {
+    pub entry: &'a Entry,
+    pub positions: Vec<usize>,
+}
+
+/// The Ctrl+P palette: a query and the entries it fuzzy-matches.
+pub struct Palette {
+    entries: Vec<Entry>,
+    pub query: String,
+    /// Position of the selected entry among the matches.
+    pub selected: usize,
+}
+
+impl Palette {
+    pub fn new(entries: Vec<Entry>) -> Self {
+        Self {
+            entries,
+            query: String::new(),
+            selected: 0,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// The entries matching the query, best first. Equal scores keep the
+    /// shorter label first, then the order of the entries.
+    pub fn matches(&self) -> Vec<Match<'_>> {
+        let mut matches: Vec<(i32, Match)> = self
+            .entries
+            .iter()
+            .filter_map(|entry| {
+                let (score, positions) = fuzzy_match(&self.query, &entry.label).or_else(|| {
+                    fuzzy_match(&self.query, &entry.keywords).map(|(score, _)| (score, Vec::new()))
+                })?;
+                Some((score, Match { entry, positions }))
+            })
+            .collect();
+        matches
+            .sort_by(|(a, x), (b, y)| b.cmp(a).then(x.entry.label.len().cmp(&y.entry.label.len())));
+        matches.into_iter().map(|(_, m)| m).collect()
+    }
+
+    pub fn push(&mut self, c: char) {
+        self.query.push(c);
+        self.selected = 0;
+    }
+
+    pub fn pop(&mut self) {
+        self.query.pop();
+        self.selected = 0;
+    }
+
+    /// Moves the selection by `delta` matches, stopping at either end.
+    pub fn select(&mut self, delta: isize) {
+        let last = self.matches().len().saturating_sub(1);
+        self.selected = self.selected.saturating_add_signed(delta).min(last);
+    }
+
+    /// The command of the selected match.
+    pub fn command(&self) -> Option<Command> {
+        self.matches()
+            .get(self.selected)
+            .map(|m| m.entry.command.clone())
+    }
+}
+
+/// Matches the characters of `query`, ignoring case and spaces, in order
+/// anywhere in `text`. Returns the best score, higher for matches at the
+/// start of words and for consecutive characters, and the matched char
+/// positions.
+pub fn fuzzy_match(query: &str, text: &str) -> Option<(i32, Vec<usize>)> {
+    let text: Vec<char> = text.chars().collect();
+    let query: Vec<char> = query.chars().filter(|c| !c.is_whitespace()).collect();
+    if query.is_empty() {
+        return Some((0, Vec::new()));
+    }
+
+    // best[k][j] is the best score of the first k + 1 query characters with
+    // the last one at position j, reached from position from[k][j].
+    let n = text.len();
+    let mut best: Vec<Vec<Option<i32>>> = vec![vec![None; n]; query.len()];
+    let mut from = vec![vec![0; n]; query.len()];
+    for (k, &q) in query.iter().enumerate() {
+        for j in 0..n {
+            if !text[j].to_lowercase().eq(q.to_lowercase()) {
+                continue;
+            }
+            let bonus = if is_word_start(&text, j) { 7 } else { 1 };
+            if k == 0 {
+                best[k][j] = Some(bonus - j.min(3) as i32);
+                continue;
+            }
+            let mut top: Option<(i32, usize)> = None;
+            for (p, score) in best[k - 1][..j].iter().enumerate() {
+                let Some(score) = *score else {
+                    continue;
+                };
+                let score = if p + 1 == j {
+                    score + 4
+                } else {
+                    score - (j - p - 1).min(3) as i32
+                };
+                if top.is_none_or(|(s, _)| score > s) {
+                    top = Some((score, p));
+                }
+            }
+            if let Some((score, p)) = top {
+                best[k][j] = Some(score + bonus);
+                from[k][j] = p;
+            }
+        }
+    }
+
+    let last = query.len() - 1;
+    let (mut j, score) = (0..n)
+        .filter_map(|j| best[last][j].map(|score| (j, score)))
+        .fold(None, |top: Option<(usize, i32)>, (j, score)| match top {
+            Some((_, s)) if s >= score => top,
+            _ => Some((j, score)),
+        })?;
+    let mut positions = vec![j; query.len()];
+    for k in (1..query.len()).rev() {
+        j = from[k][j];
+        positions[k - 1] = j;
+    }
+    Some((score, positions))
+}
+
+fn is_word_start(text: &[char], i: usize) -> bool {
+    i == 0
+        || !text[i - 1].is_alphanumeric()
+        || (text[i - 1].is_lowercase() && text[i].is_uppercase())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(label: &str, keywords: &str) -> Entry {
+        Entry {
+            label: label.to_string(),
+            detail: String::new(),
+            keywords: keywords.to_string(),
+            command: Command::View(View::Logs),
+        }
+    }
+
+    #[test]
+    fn ranks_word_starts_and_runs_first() {
+        assert_eq!(fuzzy_match("ca", "calc.py::add").unwrap().1, [0, 1]);
+        assert_eq!(fuzzy_match("ad", "calc.py::add").unwrap().1, [9, 10]);
+        assert_eq!(fuzzy_match("cpa", "calc.py::add").unwrap().1, [0, 5, 9]);
+        // Word starts are preferred only while the rest still matches.
+        assert_eq!(fuzzy_match("ab", "xab a").unwrap().1, [1, 2]);
+        assert!(fuzzy_match("ddd", "calc.py::add").is_none());
+        assert_eq!(fuzzy_match("", "anything").unwrap(), (0, vec![]));
+
+        let mut palette = Palette::new(vec![
+            entry("Pipeline Builder: Clear all steps", "clear pipeline"),
+            entry("calc.py::multiply", ""),
+            entry("Pipeline Builder: Execute pipeline", "run pipeline"),
+            entry("math.js::mul", ""),
+        ]);
+        palette.query = "run pipe".to_string();
+        let matches = palette.matches();
+        assert_eq!(matches.len(), 1);
+        assert_eq!(matches[0].entry.label, "Pipeline Builder: Execute pipeline");
+        assert!(matches[0].positions.is_empty());
+        palette.query = "exe pi".to_string();
+        let labels: Vec<&str> = palette
+            .matches()
+            .iter()
+            .map(|m| m.entry.label.as_str())
+            .collect();
+        assert_eq!(labels, ["Pipeline Builder: Execute pipeline"]);
+        palette.query = "mul".to_string();
+        let labels: Vec<&str> = palette
+            .matches()
+            .iter()
+            .map(|m| m.entry.label.as_str())
+            .collect();
+        assert_eq!(labels, ["math.js::mul", "calc.py::multiply"]);
+        palette.select(5);
+        assert_eq!(palette.selected, 1);
+        palette.push('t');
+        assert_eq!(palette.selected, 0);
+        assert_eq!(palette.matches().len(), 1);
+    }
+}
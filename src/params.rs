@@ -0,0 +1,244 @@
+use crate::models::{ParamType, PipelineParam};
+use crate::values;
+use serde_json::Value;
+use std::collections::{BTreeMap, HashMap};
+
+/// Parses parameter declarations written as `name[:type][=default]`,
+/// separated by commas, e.g. `name:string=World, n:int=5, tags:json=[1,2]`.
+pub fn parse_declarations(input: &str) -> Result<Vec<PipelineParam>, String> {
+    let mut params: Vec<PipelineParam> = Vec::new();
+
+    for declaration in split_declarations(input) {
+        let (signature, default) = match declaration.split_once('=') {
+            Some((signature, default)) => (signature.trim(), Some(default.trim().to_string())),
+            None => (declaration.trim(), None),
+        };
+        let (name, kind) = match signature.split_once(':') {
+            Some((name, kind)) => (name.trim(), parse_type(kind.trim())?),
+            None => (signature, ParamType::String),
+        };
+
+        if name.is_empty() || !name.chars().all(|c| c.is_alphanumeric() || c == '_') {
+            return Err(format!("Invalid parameter name '{}'", name));
+        }
+        if params.iter().any(|p| p.name == name) {
+            return Err(format!("Parameter {} is declared twice", name));
+        }
+
+        let param = PipelineParam {
+            name: name.to_string(),
+            kind,
+            default,
+        };
+        if let Some(default) = &param.default {
+            coerce(&param, default)?;
+        }
+        params.push(param);
+    }
+
+    Ok(params)
+}
+
+fn split_declarations(input: &str) -> Vec<String> {
+    values::split_list(input)
+        .into_iter()
+        .map(|d| d.trim().to_string())
+        .filter(|d| !d.is_empty())
+        .collect()
+}
+
+pub fn format_declarations(params: &[PipelineParam]) -> String {
+    params
+        .iter()
+        .map(|p| {
+            let mut declaration = format!("{}:{}", p.name, p.kind.name());
+            if let Some(default) = &p.default {
+                declaration.push('=');
+                declaration.push_str(&default.replace(',', "\\,"));
+            }
+            declaration
+        })
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+fn parse_type(name: &str) -> Result<ParamType, String> {
+    match name {
+        "string" | "str" => Ok(ParamType::String),
+        "int" | "integer" => Ok(ParamType::Int),
+        "float" | "number" => Ok(ParamType::Float),
+        "bool" | "boolean" => Ok(ParamType::Bool),
+        "json" => Ok(ParamType::Json),
+        other => Err(format!("Unknown parameter type '{}'", other)),
+    }
+}
+
+/// Converts a raw input to the parameter's declared type. Accepts what the
+/// exported programs accept, so a pipeline runs with the same inputs there.
+pub fn coerce(param: &PipelineParam, raw: &str) -> Result<Value, String> {
+    let invalid = || {
+        format!(
+            "{} expects {}, got '{}'",
+            param.name,
+            param.kind.name(),
+            raw
+        )
+    };
+    let trimmed = raw.trim();
+
+    match param.kind {
+        ParamType::String => Ok(Value::String(raw.to_string())),
+        ParamType::Int => trimmed
+            .parse::<i64>()
+            .map(Value::from)
+            .map_err(|_| invalid()),
+        ParamType::Float => trimmed
+            .parse::<f64>()
+            .ok()
+            .and_then(serde_json::Number::from_f64)
+            .map(Value::Number)
+            .ok_or_else(invalid),
+        ParamType::Bool => match trimmed {
+            "true" => Ok(Value::Bool(true)),
+            "false" => Ok(Value::Bool(false)),
+            _ => Err(invalid()),
+        },
+        ParamType::Json => serde_json::from_str(trimmed).map_err(|_| invalid()),
+    }
+}
+
+/// Resolves the value of every declared parameter from the provided inputs,
+/// falling back to defaults. Empty inputs count as not provided.
+pub fn resolve_inputs(
+    params: &[PipelineParam],
+    inputs: &HashMap<String, String>,
+) -> Result<BTreeMap<String, Value>, String> {
+    if let Some(unknown) = inputs
+        .keys()
+        .find(|k| !params.iter().any(|p| &p.name == *k))
+    {
+        return Err(format!("Unknown parameter {}", unknown));
+    }
+
+    params
+        .iter()
+        .map(|param| {
+            let raw = inputs
+                .get(&param.name)
+                .filter(|v| !v.is_empty())
+                .or(param.default.as_ref())
+                .ok_or_else(|| format!("Missing value for parameter {}", param.name))?;
+            Ok((param.name.clone(), coerce(param, raw)?))
+        })
+        .collect()
+}
+
+/// Looks up `env.NAME` references, parsing the value like a typed argument.
+pub fn env_value(name: &str) -> Option<Result<Value, String>> {
+    let var = name.strip_prefix("env.")?;
+    Some(
+        std::env::var(var)
+            .map(|v| values::parse_arg(&v))
+            .map_err(|_| format!("Environment variable {} is not set", var)),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn param(name: &str, kind: ParamType, default: Option<&str>) -> PipelineParam {
+        PipelineParam {
+            name: name.to_string(),
+            kind,
+            default: default.map(str::to_string),
+        }
+    }
+
+    #[test]
+    fn parses_declarations_with_commas_inside_defaults() {
+        let params = parse_declarations(
+            r#"tags:json=[1,2], opts:json={"a":[1,2],"b":"x,y"}, name=Wo"rld, note=a\,b, n:int"#,
+        )
+        .unwrap();
+
+        let declared: Vec<_> = params
+            .iter()
+            .map(|p| (p.name.as_str(), p.kind.name(), p.default.as_deref()))
+            .collect();
+        assert_eq!(
+            declared,
+            vec![
+                ("tags", "json", Some("[1,2]")),
+                ("opts", "json", Some(r#"{"a":[1,2],"b":"x,y"}"#)),
+                ("name", "string", Some(r#"Wo"rld"#)),
+                ("note", "string", Some("a,b")),
+                ("n", "int", None),
+            ]
+        );
+        assert_eq!(
+            parse_declarations(&format_declarations(&params)),
+            Ok(params)
+        );
+    }
+
+    #[test]
+    fn rejects_invalid_declarations() {
+        assert!(parse_declarations("bad-name").is_err());
+        assert!(parse_declarations("n:int, n:float").is_err());
+        assert!(parse_declarations("n:date").is_err());
+        assert!(parse_declarations("n:int=five").is_err());
+        assert_eq!(parse_declarations(" , "), Ok(Vec::new()));
+    }
+
+    #[test]
+    fn coerces_inputs_like_the_exported_programs() {
+        let cases = [
+            (ParamType::String, " a b ", Some(json!(" a b "))),
+            (ParamType::Int, " 42 ", Some(json!(42))),
+            (ParamType::Int, "4.2", None),
+            (ParamType::Float, "1.5", Some(json!(1.5))),
+            (ParamType::Float, "NaN", None),
+            (ParamType::Bool, "true", Some(json!(true))),
+            (ParamType::Bool, " false ", Some(json!(false))),
+            (ParamType::Bool, "yes", None),
+            (ParamType::Bool, "1", None),
+            (ParamType::Json, r#"{"a":[1]}"#, Some(json!({"a": [1]}))),
+            (ParamType::Json, "{", None),
+        ];
+
+        for (kind, raw, expected) in cases {
+            let p = param("p", kind, None);
+            assert_eq!(
+                coerce(&p, raw).ok(),
+                expected,
+                "{} {:?}",
+                p.kind.name(),
+                raw
+            );
+        }
+    }
+
+    #[test]
+    fn resolves_inputs_with_defaults() {
+        let params = [
+            param("name", ParamType::String, Some("World")),
+            param("n", ParamType::Int, None),
+        ];
+        let inputs = |pairs: &[(&str, &str)]| -> HashMap<String, String> {
+            pairs
+                .iter()
+                .map(|(k, v)| (k.to_string(), v.to_string()))
+                .collect()
+        };
+
+        let resolved = resolve_inputs(&params, &inputs(&[("n", "3"), ("name", "")])).unwrap();
+        assert_eq!(resolved.get("name"), Some(&json!("World")));
+        assert_eq!(resolved.get("n"), Some(&json!(3)));
+
+        assert!(resolve_inputs(&params, &inputs(&[])).is_err());
+        assert!(resolve_inputs(&params, &inputs(&[("n", "x")])).is_err());
+        assert!(resolve_inputs(&params, &inputs(&[("n", "3"), ("other", "1")])).is_err());
+    }
+}
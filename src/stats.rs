@@ -0,0 +1,159 @@
+use crate::models::ExecutionResult;
+
+/// Call counts and latency of a group of results: one function, one
+/// language, or all of them.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct CallStats {
+    pub name: String,
+    pub calls: usize,
+    pub passed: usize,
+    pub total_ms: u64,
+    pub max_ms: u64,
+}
+
+impl CallStats {
+    fn new(name: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            ..Default::default()
+        }
+    }
+
+    fn add(&mut self, result: &ExecutionResult) {
+        self.calls += 1;
+        self.passed += usize::from(result.passed());
+        self.total_ms += result.duration_ms;
+        self.max_ms = self.max_ms.max(result.duration_ms);
+    }
+
+    /// Share of calls that succeeded and met their expectations, from 0 to
+    /// 100.
+    pub fn success_rate(&self) -> f64 {
+        if self.calls == 0 {
+            0.0
+        } else {
+            self.passed as f64 * 100.0 / self.calls as f64
+        }
+    }
+
+    pub fn avg_ms(&self) -> u64 {
+        self.total_ms / self.calls.max(1) as u64
+    }
+}
+
+/// Totals over every result.
+pub fn overall<'a>(results: impl IntoIterator<Item = &'a ExecutionResult>) -> CallStats {
+    let mut stats = CallStats::new("All");
+    results.into_iter().for_each(|r| stats.add(r));
+    stats
+}
+
+/// Per-function stats, most called first.
+pub fn by_function<'a>(results: impl IntoIterator<Item = &'a ExecutionResult>) -> Vec<CallStats> {
+    group(results, |r| r.function.clone())
+}
+
+/// Per-language stats, most called first. Results whose language is
+/// unknown are counted as "Other".
+pub fn by_language<'a>(
+    results: impl IntoIterator<Item = &'a ExecutionResult>,
+    language: impl Fn(&ExecutionResult) -> Option<&'static str>,
+) -> Vec<CallStats> {
+    group(results, |r| language(r).unwrap_or("Other").to_string())
+}
+
+/// The `n` slowest results, slowest first.
+pub fn slowest(results: &[ExecutionResult], n: usize) -> Vec<&ExecutionResult> {
+    let mut sorted: Vec<&ExecutionResult> = results.iter().collect();
+    sorted.sort_by_key(|r| std::cmp::Reverse(r.duration_ms));
+    sorted.truncate(n);
+    sorted
+}
+
+fn group<'a>(
+    results: impl IntoIterator<Item = &'a ExecutionResult>,
+    key: impl Fn(&ExecutionResult) -> String,
+) -> Vec<CallStats> {
+    let mut groups: Vec<CallStats> = Vec::new();
+    for result in results {
+        let name = key(result);
+        match groups.iter_mut().find(|g| g.name == name) {
+            Some(stats) => stats.add(result),
+            None => {
+                let mut stats = CallStats::new(&name);
+                stats.add(result);
+                groups.push(stats);
+            }
+        }
+    }
+    groups.sort_by(|a, b| b.calls.cmp(&a.calls).then_with(|| a.name.cmp(&b.name)));
+    groups
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::AssertionResult;
+
+    fn result(function: &str, duration_ms: u64, success: bool) -> ExecutionResult {
+        ExecutionResult {
+            function: function.to_string(),
+            args: Vec::new(),
+            output: String::new(),
+            value: None,
+            duration_ms,
+            success,
+            timestamp: crate::time::now(),
+            run_id: None,
+            changed: false,
+            mapped: false,
+            replay_of: None,
+            assertions: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn groups_calls_by_function_and_language() {
+        let results = vec![
+            result("calc.py::add", 4, true),
+            result("text.js::upper", 30, true),
+            result("calc.py::add", 10, false),
+            result("calc.py::div", 1, true),
+        ];
+
+        let functions = by_function(&results);
+        assert_eq!(functions[0].name, "calc.py::add");
+        assert_eq!(functions[0].calls, 2);
+        assert_eq!(functions[0].success_rate(), 50.0);
+        assert_eq!(functions[0].avg_ms(), 7);
+        assert_eq!(functions[0].max_ms, 10);
+        assert_eq!(functions[1].name, "calc.py::div");
+
+        let languages = by_language(&results, |r| {
+            r.function.contains(".py::").then_some("Python")
+        });
+        let names: Vec<&str> = languages.iter().map(|l| l.name.as_str()).collect();
+        assert_eq!(names, ["Python", "Other"]);
+        assert_eq!(languages[0].calls, 3);
+
+        let slow: Vec<u64> = slowest(&results, 2).iter().map(|r| r.duration_ms).collect();
+        assert_eq!(slow, [30, 10]);
+        assert_eq!(overall(&results).passed, 3);
+        assert_eq!(overall(&[]).success_rate(), 0.0);
+    }
+
+    #[test]
+    fn counts_failed_assertions_as_failed_calls() {
+        let mut checked = result("calc.py::add", 4, true);
+        checked.assertions = vec![AssertionResult {
+            expectation: "= 5".to_string(),
+            passed: false,
+            message: "got 4".to_string(),
+        }];
+        let results = [checked, result("calc.py::add", 4, true)];
+
+        let stats = overall(&results);
+        assert_eq!(stats.passed, 1);
+        assert_eq!(stats.success_rate(), 50.0);
+    }
+}
@@ -0,0 +1,322 @@
+use crate::config::ThemeConfig;
+use ratatui::{
+    style::Color,
+    symbols::{Marker, bar},
+};
+use std::{borrow::Cow, str::FromStr};
+
+/// The colours of the interface by role, and whether icons are drawn as
+/// ASCII.
+#[derive(Debug, Clone)]
+pub struct Theme {
+    /// Borders, tab bar and headings.
+    pub primary: Color,
+    /// The selected item, focused fields and typed input.
+    pub highlight: Color,
+    pub text: Color,
+    /// Labels and secondary text.
+    pub muted: Color,
+    /// Hints, placeholders and inactive items.
+    pub subtle: Color,
+    /// Background of the selected row.
+    pub selection: Color,
+    pub success: Color,
+    pub error: Color,
+    pub info: Color,
+    /// Draw icons and arrows as ASCII instead of emojis and symbols.
+    pub ascii: bool,
+}
+
+const THEMES: [&str; 3] = ["dark", "light", "high-contrast"];
+
+const COLORS: [&str; 9] = [
+    "primary",
+    "highlight",
+    "text",
+    "muted",
+    "subtle",
+    "selection",
+    "success",
+    "error",
+    "info",
+];
+
+/// ASCII stand-ins for the icons, longest first. Emojis that only decorate a
+/// title are dropped together with the spaces after them.
+/// Sparkline bars from low to high, drawn with ASCII characters.
+const ASCII_BARS: bar::Set = bar::Set {
+    full: "#",
+    seven_eighths: "#",
+    three_quarters: "=",
+    five_eighths: "=",
+    half: "-",
+    three_eighths: "-",
+    one_quarter: "_",
+    one_eighth: "_",
+    empty: " ",
+};
+
+const ASCII_ICONS: &[(&str, &str)] = &[
+    ("↑↓", "Up/Down"),
+    ("←→", "Left/Right"),
+    ("✓", "+"),
+    ("✗", "x"),
+    ("⚠", "!"),
+    ("○", "-"),
+    ("⇄", "<>"),
+    ("↻", "@"),
+    ("Δ", "~"),
+    ("▶", ">"),
+    ("◀", "<"),
+    ("→", "->"),
+    ("↑", "Up"),
+    ("↓", "Down"),
+    ("←", "Left"),
+    ("■", "[x]"),
+    ("□", "[ ]"),
+    ("█", "_"),
+    ("—", "-"),
+    ("•", "*"),
+    ("×", "x"),
+    ("⏱", ""),
+    ("🐍", ""),
+    ("📜", ""),
+    ("📘", ""),
+    ("💎", ""),
+    ("📄", ""),
+    ("📂", ""),
+    ("📁", ""),
+    ("🗂", ""),
+    ("🔧", ""),
+    ("🔍", ""),
+    ("🔎", ""),
+    ("🚀", ""),
+    ("📊", ""),
+    ("📤", ""),
+    ("📝", ""),
+    ("📋", ""),
+    ("📉", ""),
+    ("📈", ""),
+    ("⚙", ""),
+    ("ℹ", ""),
+    ("🧪", ""),
+    ("🕘", ""),
+    ("🔗", ""),
+    ("🌐", ""),
+    ("🐢", ""),
+    ("➕", ""),
+    ("❓", ""),
+    ("✏", ""),
+    ("⌨", ""),
+];
+
+/// Makes the preceding character draw as an emoji; dropped with it.
+const EMOJI_PRESENTATION: char = '\u{fe0f}';
+
+impl Default for Theme {
+    fn default() -> Self {
+        Self::dark()
+    }
+}
+
+impl Theme {
+    pub fn dark() -> Self {
+        Self {
+            primary: Color::Cyan,
+            highlight: Color::Yellow,
+            text: Color::White,
+            muted: Color::Gray,
+            subtle: Color::DarkGray,
+            selection: Color::DarkGray,
+            success: Color::Green,
+            error: Color::Red,
+            info: Color::Blue,
+            ascii: false,
+        }
+    }
+
+    /// For terminals with a light background.
+    pub fn light() -> Self {
+        Self {
+            primary: Color::Blue,
+            highlight: Color::Magenta,
+            text: Color::Black,
+            muted: Color::DarkGray,
+            subtle: Color::Gray,
+            selection: Color::Gray,
+            success: Color::Green,
+            error: Color::Red,
+            info: Color::Cyan,
+            ascii: false,
+        }
+    }
+
+    /// Bright colours only, and a blue selection that text stands out on.
+    pub fn high_contrast() -> Self {
+        Self {
+            primary: Color::White,
+            highlight: Color::LightYellow,
+            text: Color::White,
+            muted: Color::White,
+            subtle: Color::Gray,
+            selection: Color::Blue,
+            success: Color::LightGreen,
+            error: Color::LightRed,
+            info: Color::LightCyan,
+            ascii: false,
+        }
+    }
+
+    pub fn named(name: &str) -> Option<Self> {
+        match name {
+            "dark" => Some(Self::dark()),
+            "light" => Some(Self::light()),
+            "high-contrast" => Some(Self::high_contrast()),
+            _ => None,
+        }
+    }
+
+    /// The named theme with the colours set in `config` replacing its own.
+    /// Colours are names such as `lightblue`, indexes such as `208`, or
+    /// `#rrggbb`.
+    pub fn from_config(config: &ThemeConfig) -> Result<Self, String> {
+        let mut theme = Self::named(&config.name).ok_or_else(|| {
+            format!(
+                "Unknown theme '{}', expected one of {}",
+                config.name,
+                THEMES.join(", ")
+            )
+        })?;
+        for (role, value) in &config.colors {
+            let color = Color::from_str(value)
+                .map_err(|_| format!("Invalid color '{}' for '{}'", value, role))?;
+            *theme.color_mut(role).ok_or_else(|| {
+                format!(
+                    "Unknown theme color '{}', expected one of {}",
+                    role,
+                    COLORS.join(", ")
+                )
+            })? = color;
+        }
+        theme.ascii = config.ascii;
+        Ok(theme)
+    }
+
+    fn color_mut(&mut self, role: &str) -> Option<&mut Color> {
+        Some(match role {
+            "primary" => &mut self.primary,
+            "highlight" => &mut self.highlight,
+            "text" => &mut self.text,
+            "muted" => &mut self.muted,
+            "subtle" => &mut self.subtle,
+            "selection" => &mut self.selection,
+            "success" => &mut self.success,
+            "error" => &mut self.error,
+            "info" => &mut self.info,
+            _ => return None,
+        })
+    }
+
+    /// Replaces the icons in `text` with ASCII in ASCII-only mode.
+    pub fn icons<'a>(&self, text: &'a str) -> Cow<'a, str> {
+        if !self.ascii || text.is_ascii() {
+            return Cow::Borrowed(text);
+        }
+        let mut out = String::with_capacity(text.len());
+        let mut rest = text;
+        while let Some(c) = rest.chars().next() {
+            match ASCII_ICONS.iter().find(|(icon, _)| rest.starts_with(icon)) {
+                Some((icon, ascii)) => {
+                    rest = rest[icon.len()..].trim_start_matches(EMOJI_PRESENTATION);
+                    if ascii.is_empty() {
+                        rest = rest.trim_start_matches(' ');
+                    }
+                    out.push_str(ascii);
+                }
+                None => {
+                    if c != EMOJI_PRESENTATION {
+                        out.push(c);
+                    }
+                    rest = &rest[c.len_utf8()..];
+                }
+            }
+        }
+        Cow::Owned(out)
+    }
+
+    /// The marker of chart lines: Braille dots, or plain dots in ASCII-only
+    /// mode.
+    pub fn marker(&self) -> Marker {
+        if self.ascii {
+            Marker::Dot
+        } else {
+            Marker::Braille
+        }
+    }
+
+    /// The bars of sparklines: block elements, or ASCII in ASCII-only mode.
+    pub fn bar_set(&self) -> bar::Set {
+        if self.ascii {
+            ASCII_BARS
+        } else {
+            bar::NINE_LEVELS
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::BTreeMap;
+
+    #[test]
+    fn applies_user_colors_to_a_named_theme() {
+        let config = ThemeConfig {
+            name: "light".to_string(),
+            ascii: true,
+            colors: BTreeMap::from([
+                ("highlight".to_string(), "#ff8800".to_string()),
+                ("error".to_string(), "lightred".to_string()),
+            ]),
+        };
+        let theme = Theme::from_config(&config).unwrap();
+        assert_eq!(theme.highlight, Color::Rgb(0xff, 0x88, 0x00));
+        assert_eq!(theme.error, Color::LightRed);
+        assert_eq!(theme.primary, Theme::light().primary);
+        assert!(theme.ascii);
+
+        let mut unknown = config.clone();
+        unknown
+            .colors
+            .insert("border".to_string(), "red".to_string());
+        assert!(
+            Theme::from_config(&unknown)
+                .unwrap_err()
+                .contains("'border'")
+        );
+        unknown.name = "solarized".to_string();
+        assert!(Theme::from_config(&unknown).is_err());
+    }
+
+    #[test]
+    fn replaces_icons_in_ascii_mode() {
+        let mut theme = Theme::dark();
+        assert_eq!(theme.icons("🔧 Functions"), "🔧 Functions");
+        theme.ascii = true;
+        assert_eq!(theme.icons("🔧 Functions"), "Functions");
+        assert_eq!(theme.icons("⚙️  Arguments"), "Arguments");
+        assert_eq!(theme.icons("✓ 🐍 calc.py [Python]"), "+ calc.py [Python]");
+        assert_eq!(
+            theme.icons("↑↓ select, ← back"),
+            "Up/Down select, Left back"
+        );
+        assert_eq!(theme.icons("~ $.a: 3 → 4 (café)"), "~ $.a: 3 -> 4 (café)");
+        assert_eq!(theme.marker(), Marker::Dot);
+        let bars = theme.bar_set();
+        assert!(
+            [bars.full, bars.half, bars.one_eighth, bars.empty]
+                .iter()
+                .all(|b| b.is_ascii())
+        );
+    }
+}
@@ -0,0 +1,155 @@
+use chrono::format::{Item, StrftimeItems};
+use chrono::{DateTime, FixedOffset, Local, NaiveTime, SecondsFormat, TimeDelta, Utc};
+use serde::{Deserialize, Deserializer, Serializer};
+
+/// A point in time with the UTC offset it was recorded in.
+pub type Timestamp = DateTime<FixedOffset>;
+
+/// The current local time, to the millisecond so that it survives a round
+/// trip through RFC 3339.
+pub fn now() -> Timestamp {
+    let now = Local::now().fixed_offset();
+    now - TimeDelta::nanoseconds(i64::from(now.timestamp_subsec_nanos() % 1_000_000))
+}
+
+/// RFC 3339 with milliseconds, e.g. `2024-01-01T12:00:00.000+01:00`.
+pub fn rfc3339(timestamp: &Timestamp) -> String {
+    timestamp.to_rfc3339_opts(SecondsFormat::Millis, true)
+}
+
+/// Checks a display format: one of the presets of [`format`] or a strftime
+/// pattern.
+pub fn validate_format(format: &str) -> Result<(), String> {
+    match format {
+        "time" | "datetime" | "rfc3339" | "relative" => Ok(()),
+        pattern if StrftimeItems::new(pattern).any(|item| item == Item::Error) => {
+            Err(format!("Invalid timestamp format '{}'", pattern))
+        }
+        _ => Ok(()),
+    }
+}
+
+/// Renders a timestamp for display. `format` is `time`, `datetime`,
+/// `rfc3339`, `relative` (`5m ago`) or a strftime pattern. Times are shown in
+/// the local timezone, or in UTC when `utc` is set.
+pub fn format(timestamp: &Timestamp, format: &str, utc: bool) -> String {
+    let pattern = match format {
+        "time" => "%H:%M:%S",
+        "datetime" => "%Y-%m-%d %H:%M:%S",
+        "rfc3339" if utc => return rfc3339(&timestamp.with_timezone(&Utc).fixed_offset()),
+        "rfc3339" => return rfc3339(&timestamp.with_timezone(&Local).fixed_offset()),
+        "relative" => return relative(*timestamp, Utc::now().fixed_offset()),
+        pattern => pattern,
+    };
+    if utc {
+        timestamp.with_timezone(&Utc).format(pattern).to_string()
+    } else {
+        timestamp.with_timezone(&Local).format(pattern).to_string()
+    }
+}
+
+fn relative(timestamp: Timestamp, now: Timestamp) -> String {
+    let seconds = (now - timestamp).num_seconds();
+    match seconds {
+        s if s < 0 => "in the future".to_string(),
+        s if s < 60 => format!("{}s ago", s),
+        s if s < 3600 => format!("{}m ago", s / 60),
+        s if s < 86400 => format!("{}h ago", s / 3600),
+        s => format!("{}d ago", s / 86400),
+    }
+}
+
+/// Serde support for timestamps as RFC 3339 with milliseconds. Times saved by
+/// older versions as a bare UTC `HH:MM:SS` are read as that time on
+/// 1970-01-01.
+pub mod serde_rfc3339 {
+    use super::*;
+
+    pub fn serialize<S: Serializer>(
+        timestamp: &Timestamp,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&rfc3339(timestamp))
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Timestamp, D::Error> {
+        let text = String::deserialize(deserializer)?;
+        parse(&text)
+            .ok_or_else(|| serde::de::Error::custom(format!("invalid timestamp '{}'", text)))
+    }
+}
+
+/// [`serde_rfc3339`] for optional timestamps.
+pub mod serde_rfc3339_option {
+    use super::*;
+
+    pub fn serialize<S: Serializer>(
+        timestamp: &Option<Timestamp>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        match timestamp {
+            Some(timestamp) => serde_rfc3339::serialize(timestamp, serializer),
+            None => serializer.serialize_none(),
+        }
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Option<Timestamp>, D::Error> {
+        match Option::<String>::deserialize(deserializer)? {
+            Some(text) => parse(&text)
+                .map(Some)
+                .ok_or_else(|| serde::de::Error::custom(format!("invalid timestamp '{}'", text))),
+            None => Ok(None),
+        }
+    }
+}
+
+pub fn parse(text: &str) -> Option<Timestamp> {
+    DateTime::parse_from_rfc3339(text).ok().or_else(|| {
+        let time = NaiveTime::parse_from_str(text, "%H:%M:%S").ok()?;
+        Some(
+            DateTime::UNIX_EPOCH
+                .date_naive()
+                .and_time(time)
+                .and_utc()
+                .fixed_offset(),
+        )
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trips_with_milliseconds() {
+        let timestamp = now();
+        assert_eq!(timestamp.timestamp_subsec_nanos() % 1_000_000, 0);
+        assert_eq!(parse(&rfc3339(&timestamp)), Some(timestamp));
+
+        let fixed = parse("2024-01-01T12:00:00.250+02:00").unwrap();
+        assert_eq!(rfc3339(&fixed), "2024-01-01T12:00:00.250+02:00");
+        assert_eq!(format(&fixed, "%H:%M:%S%.3f", true), "10:00:00.250");
+        assert_eq!(format(&fixed, "rfc3339", true), "2024-01-01T10:00:00.250Z");
+        assert_eq!(
+            rfc3339(&parse("13:27:41").unwrap()),
+            "1970-01-01T13:27:41.000Z"
+        );
+        assert!(parse("yesterday").is_none());
+    }
+
+    #[test]
+    fn formats_relative_times_and_checks_patterns() {
+        let then = parse("2024-01-01T12:00:00Z").unwrap();
+        let later = |seconds| then + TimeDelta::seconds(seconds);
+        assert_eq!(relative(then, later(42)), "42s ago");
+        assert_eq!(relative(then, later(600)), "10m ago");
+        assert_eq!(relative(then, later(7200)), "2h ago");
+        assert_eq!(relative(then, later(3 * 86400)), "3d ago");
+
+        assert!(validate_format("relative").is_ok());
+        assert!(validate_format("%d/%m %H:%M").is_ok());
+        assert!(validate_format("%Q").is_err());
+    }
+}
@@ -0,0 +1,260 @@
+use metacall::{
+    MetaCallException, MetaCallValue,
+    bindings::{
+        metacall_function, metacall_throwable_value, metacall_value_count,
+        metacall_value_create_array, metacall_value_create_bool, metacall_value_create_double,
+        metacall_value_create_long, metacall_value_create_map, metacall_value_create_null,
+        metacall_value_create_string, metacall_value_destroy, metacall_value_id,
+        metacall_value_to_array, metacall_value_to_bool, metacall_value_to_char,
+        metacall_value_to_double, metacall_value_to_float, metacall_value_to_int,
+        metacall_value_to_long, metacall_value_to_map, metacall_value_to_short,
+        metacall_value_to_string, metacall_value_to_throwable, metacallfv_s,
+    },
+};
+use serde_json::{Map, Number, Value};
+use std::ffi::{CStr, CString, c_void};
+
+// The typed `metacall::<T>` API cannot describe values whose shape is only known
+// after the call (e.g. a JS array of numbers), so calls go through the raw
+// bindings and every value is converted to and from JSON.
+
+/// Calls a loaded function with JSON arguments and returns its result as JSON.
+pub fn call(func_name: &str, args: &[Value]) -> Result<Value, String> {
+    let c_name = CString::new(func_name).map_err(|e| e.to_string())?;
+    let func = unsafe { metacall_function(c_name.as_ptr()) };
+    if func.is_null() {
+        return Err(format!("FunctionNotFound: {}", func_name));
+    }
+
+    let mut raw_args: Vec<*mut c_void> = args.iter().map(to_raw).collect();
+    let ret = unsafe { metacallfv_s(func, raw_args.as_mut_ptr(), raw_args.len()) };
+
+    for arg in raw_args {
+        unsafe { metacall_value_destroy(arg) };
+    }
+
+    if ret.is_null() {
+        return Ok(Value::Null);
+    }
+
+    let value = from_raw(ret);
+    unsafe { metacall_value_destroy(ret) };
+    value
+}
+
+/// Interprets a user typed argument: numbers, booleans, `null` and JSON
+/// arrays/objects keep their type, a quoted JSON string forces a string and
+/// anything else is passed as plain text.
+pub fn parse_arg(arg: &str) -> Value {
+    let trimmed = arg.trim();
+
+    if let Ok(n) = trimmed.parse::<i64>() {
+        return Value::from(n);
+    }
+    if let Ok(n) = trimmed.parse::<f64>()
+        && n.is_finite()
+    {
+        return Value::from(n);
+    }
+
+    match trimmed {
+        "true" => Value::Bool(true),
+        "false" => Value::Bool(false),
+        "null" => Value::Null,
+        _ if trimmed.starts_with(['[', '{', '"']) => {
+            serde_json::from_str(trimmed).unwrap_or_else(|_| Value::String(arg.to_string()))
+        }
+        _ => Value::String(arg.to_string()),
+    }
+}
+
+/// Splits a comma separated list on the commas outside brackets and quoted
+/// values, so JSON entries keep theirs. `\,` is a literal comma anywhere. A
+/// quote opens a quoted value at the start of an entry or after `=`.
+pub fn split_list(input: &str) -> Vec<String> {
+    let mut entries = Vec::new();
+    let mut current = String::new();
+    let mut depth = 0usize;
+    let mut quoted = false;
+    let mut chars = input.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' if chars.peek() == Some(&',') => {
+                current.push(',');
+                chars.next();
+            }
+            '\\' if quoted => {
+                current.push(c);
+                current.extend(chars.next());
+            }
+            '"' if quoted
+                || depth > 0
+                || current.trim().is_empty()
+                || current.trim_end().ends_with('=') =>
+            {
+                quoted = !quoted;
+                current.push(c);
+            }
+            '[' | '{' if !quoted => {
+                depth += 1;
+                current.push(c);
+            }
+            ']' | '}' if !quoted => {
+                depth = depth.saturating_sub(1);
+                current.push(c);
+            }
+            ',' if !quoted && depth == 0 => entries.push(std::mem::take(&mut current)),
+            _ => current.push(c),
+        }
+    }
+    entries.push(current);
+    entries
+}
+
+/// Renders a value the way it is shown in results: strings verbatim, anything
+/// else as compact JSON.
+pub fn display(value: &Value) -> String {
+    match value {
+        Value::String(s) => s.clone(),
+        other => other.to_string(),
+    }
+}
+
+/// Renders an argument so that [`parse_arg`] reads it back as the same value:
+/// like [`display`], but strings that would parse as something else are
+/// quoted.
+pub fn display_arg(value: &Value) -> String {
+    match value {
+        Value::String(s) if parse_arg(s) != *value => Value::String(s.clone()).to_string(),
+        other => display(other),
+    }
+}
+
+fn to_raw(value: &Value) -> *mut c_void {
+    unsafe {
+        match value {
+            Value::Null => metacall_value_create_null(),
+            Value::Bool(b) => metacall_value_create_bool(*b as u8),
+            Value::Number(n) => match n.as_i64() {
+                Some(i) => metacall_value_create_long(i as _),
+                None => metacall_value_create_double(n.as_f64().unwrap_or_default()),
+            },
+            Value::String(s) => raw_string(s),
+            Value::Array(items) => {
+                let mut raw: Vec<*const c_void> =
+                    items.iter().map(|v| to_raw(v).cast_const()).collect();
+                metacall_value_create_array(raw.as_mut_ptr(), raw.len())
+            }
+            Value::Object(map) => {
+                let mut tuples: Vec<*const c_void> = map
+                    .iter()
+                    .map(|(k, v)| {
+                        let mut pair = [raw_string(k).cast_const(), to_raw(v).cast_const()];
+                        metacall_value_create_array(pair.as_mut_ptr(), 2).cast_const()
+                    })
+                    .collect();
+                metacall_value_create_map(tuples.as_mut_ptr(), tuples.len())
+            }
+        }
+    }
+}
+
+fn raw_string(s: &str) -> *mut c_void {
+    let c_str = CString::new(s.replace('\0', "")).unwrap_or_default();
+    let len = c_str.as_bytes().len();
+    unsafe { metacall_value_create_string(c_str.as_ptr(), len) }
+}
+
+fn from_raw(v: *mut c_void) -> Result<Value, String> {
+    use metacall::bindings::metacall_value_id as Id;
+
+    if v.is_null() {
+        return Ok(Value::Null);
+    }
+
+    let value = unsafe {
+        match metacall_value_id(v) {
+            Id::METACALL_BOOL => Value::Bool(metacall_value_to_bool(v) != 0),
+            Id::METACALL_CHAR => Value::String((metacall_value_to_char(v) as u8 as char).into()),
+            Id::METACALL_SHORT => Value::from(metacall_value_to_short(v)),
+            Id::METACALL_INT => Value::from(metacall_value_to_int(v)),
+            Id::METACALL_LONG => Value::from(metacall_value_to_long(v)),
+            Id::METACALL_FLOAT => float(metacall_value_to_float(v) as f64),
+            Id::METACALL_DOUBLE => float(metacall_value_to_double(v)),
+            Id::METACALL_STRING => Value::String(
+                CStr::from_ptr(metacall_value_to_string(v))
+                    .to_string_lossy()
+                    .into_owned(),
+            ),
+            Id::METACALL_ARRAY => {
+                let items =
+                    std::slice::from_raw_parts(metacall_value_to_array(v), metacall_value_count(v));
+                Value::Array(
+                    items
+                        .iter()
+                        .map(|item| from_raw(*item))
+                        .collect::<Result<_, _>>()?,
+                )
+            }
+            Id::METACALL_MAP => {
+                let tuples =
+                    std::slice::from_raw_parts(metacall_value_to_map(v), metacall_value_count(v));
+                let mut map = Map::new();
+                for tuple in tuples {
+                    let pair = std::slice::from_raw_parts(metacall_value_to_array(*tuple), 2);
+                    let key = display(&from_raw(pair[0])?);
+                    map.insert(key, from_raw(pair[1])?);
+                }
+                Value::Object(map)
+            }
+            Id::METACALL_EXCEPTION => {
+                let message = MetaCallException::from_metacall_raw_leak(v)
+                    .map(|e| e.to_string())
+                    .unwrap_or_else(|e| format!("{:?}", e));
+                return Err(message);
+            }
+            Id::METACALL_THROWABLE => {
+                let inner = metacall_throwable_value(metacall_value_to_throwable(v));
+                let message = match from_raw(inner) {
+                    Ok(value) => format!("[Throwable]: {}", display(&value)),
+                    Err(message) => message,
+                };
+                return Err(message);
+            }
+            Id::METACALL_NULL => Value::Null,
+            other => return Err(format!("Unsupported return type {:?}", other)),
+        }
+    };
+
+    Ok(value)
+}
+
+fn float(n: f64) -> Value {
+    Number::from_f64(n).map_or(Value::Null, Value::Number)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn displayed_args_parse_back_to_the_same_value() {
+        for value in [
+            json!("plain text"),
+            json!("42"),
+            json!(" true"),
+            json!("null"),
+            json!("\"quoted\""),
+            json!("[not json"),
+            json!(42),
+            json!(1.5),
+            json!([1, "2", {"a": null}]),
+        ] {
+            assert_eq!(parse_arg(&display_arg(&value)), value, "{}", value);
+        }
+        assert_eq!(display_arg(&json!("42")), "\"42\"");
+        assert_eq!(display_arg(&json!("plain text")), "plain text");
+    }
+}
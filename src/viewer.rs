@@ -0,0 +1,244 @@
+use crate::models::ExecutionResult;
+use ratatui::text::Span;
+use serde_json::Value;
+use std::{cell::Cell, ops::Range};
+
+/// State of the full-screen viewer for one result's output.
+#[derive(Debug, Clone)]
+pub struct OutputViewer {
+    pub title: String,
+    output: String,
+    value: Option<Value>,
+    /// Show JSON values pretty-printed instead of as compact JSON.
+    pub pretty: bool,
+    pub wrap: bool,
+    /// The top row shown, counting wrapped rows when wrapping.
+    pub scroll: u16,
+    pub hscroll: u16,
+    pub search: String,
+    /// Whether the search text is being typed.
+    pub searching: bool,
+    /// Width of the text area in the last frame, which lines wrap at.
+    pub width: Cell<u16>,
+}
+
+impl OutputViewer {
+    pub fn new(result: &ExecutionResult) -> Self {
+        Self {
+            title: format!("{}({})", result.function, result.args.join(", ")),
+            output: result.output.clone(),
+            // Strings holding JSON are pretty-printed too.
+            value: result
+                .value
+                .clone()
+                .filter(|v| !v.is_string())
+                .or_else(|| serde_json::from_str(&result.output).ok())
+                .filter(|v: &Value| v.is_array() || v.is_object()),
+            pretty: true,
+            wrap: true,
+            scroll: 0,
+            hscroll: 0,
+            search: String::new(),
+            searching: false,
+            width: Cell::new(0),
+        }
+    }
+
+    /// Whether the output is JSON that can be pretty-printed.
+    pub fn is_json(&self) -> bool {
+        self.value.is_some()
+    }
+
+    /// The text as currently shown.
+    pub fn text(&self) -> String {
+        match &self.value {
+            Some(value) if self.pretty => {
+                serde_json::to_string_pretty(value).unwrap_or_else(|_| self.output.clone())
+            }
+            _ => self.output.clone(),
+        }
+    }
+
+    /// The rows the text takes on screen, as the index of the line each row
+    /// shows and its byte range in that line. When wrapping, lines are cut
+    /// where they reach the width of the last frame.
+    pub fn rows(&self) -> Vec<(usize, Range<usize>)> {
+        let width = if self.wrap { self.width.get() } else { 0 };
+        let mut rows = Vec::new();
+        for (i, line) in self.text().lines().enumerate() {
+            let mut start = 0;
+            let mut used = 0;
+            for (at, c) in line.char_indices() {
+                let mut buf = [0; 4];
+                let w = Span::raw(&*c.encode_utf8(&mut buf)).width() as u16;
+                if width > 0 && used + w > width && at > start {
+                    rows.push((i, start..at));
+                    start = at;
+                    used = 0;
+                }
+                used += w;
+            }
+            rows.push((i, start..line.len()));
+        }
+        rows
+    }
+
+    pub fn row_count(&self) -> u16 {
+        self.rows().len().min(u16::MAX as usize) as u16
+    }
+
+    /// The line shown in the top row.
+    pub fn top_line(&self) -> usize {
+        self.rows()
+            .get(self.scroll as usize)
+            .map_or(0, |(line, _)| *line)
+    }
+
+    /// Scrolls to the first row of `line`.
+    pub fn scroll_to_line(&mut self, line: usize) {
+        let row = self.rows().iter().position(|(l, _)| *l >= line);
+        self.scroll = row.unwrap_or(0).min(u16::MAX as usize) as u16;
+    }
+
+    /// Rows where the search text starts, ignoring case.
+    pub fn matches(&self) -> Vec<u16> {
+        let text = self.text();
+        let lines: Vec<&str> = text.lines().collect();
+        self.rows()
+            .iter()
+            .enumerate()
+            .filter(|(_, (line, range))| {
+                match_ranges(lines[*line], &self.search)
+                    .iter()
+                    .any(|m| range.contains(&m.start))
+            })
+            .map(|(row, _)| row.min(u16::MAX as usize) as u16)
+            .collect()
+    }
+
+    /// Scrolls to the next match after the top line, or the previous one
+    /// before it, wrapping around. Returns false when nothing matches.
+    pub fn jump(&mut self, forward: bool) -> bool {
+        let matches = self.matches();
+        let next = if forward {
+            matches
+                .iter()
+                .find(|&&line| line > self.scroll)
+                .or(matches.first())
+        } else {
+            matches
+                .iter()
+                .rev()
+                .find(|&&line| line < self.scroll)
+                .or(matches.last())
+        };
+        match next {
+            Some(&line) => {
+                self.scroll = line;
+                true
+            }
+            None => false,
+        }
+    }
+
+    pub fn scroll_by(&mut self, rows: i32) {
+        let max = self.row_count().saturating_sub(1) as i32;
+        self.scroll = (self.scroll as i32 + rows).clamp(0, max.max(0)) as u16;
+    }
+}
+
+/// The byte ranges of `line` holding `needle`, ignoring case. Lowercasing
+/// can change byte offsets outside ASCII, so such lines match as a whole.
+pub fn match_ranges(line: &str, needle: &str) -> Vec<Range<usize>> {
+    let needle = needle.to_lowercase();
+    let lower = line.to_lowercase();
+    if needle.is_empty() || !lower.contains(&needle) {
+        return Vec::new();
+    }
+    if lower.len() != line.len() {
+        return std::iter::once(0..line.len()).collect();
+    }
+    lower
+        .match_indices(&needle)
+        .map(|(start, _)| start..start + needle.len())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn result(output: String, value: Option<Value>) -> ExecutionResult {
+        ExecutionResult {
+            function: "people.py::list".to_string(),
+            args: vec!["2".to_string()],
+            output,
+            value,
+            duration_ms: 1,
+            success: true,
+            timestamp: crate::time::now(),
+            run_id: None,
+            changed: false,
+            mapped: false,
+            replay_of: None,
+            assertions: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn pretty_prints_and_searches_json_output() {
+        let value = json!({"items": [{"name": "Ada"}, {"name": "Grace"}], "total": 2});
+        let mut viewer = OutputViewer::new(&result(value.to_string(), Some(value)));
+        assert_eq!(viewer.title, "people.py::list(2)");
+        assert!(viewer.is_json());
+        assert!(viewer.row_count() > 1);
+
+        viewer.search = "NAME".to_string();
+        let matches = viewer.matches();
+        assert_eq!(matches.len(), 2);
+        assert!(viewer.jump(true));
+        assert_eq!(viewer.scroll, matches[0]);
+        assert!(viewer.jump(true));
+        assert_eq!(viewer.scroll, matches[1]);
+        assert!(viewer.jump(true));
+        assert_eq!(viewer.scroll, matches[0]);
+        assert!(viewer.jump(false));
+        assert_eq!(viewer.scroll, matches[1]);
+
+        viewer.pretty = false;
+        assert_eq!(viewer.row_count(), 1);
+        viewer.scroll_by(10);
+        assert_eq!(viewer.scroll, 0);
+        viewer.search = "nobody".to_string();
+        assert!(!viewer.jump(true));
+    }
+
+    #[test]
+    fn scrolls_and_searches_wrapped_rows_of_a_long_line() {
+        let output = format!("{}needle{}", "a".repeat(100), "b".repeat(94));
+        let mut viewer = OutputViewer::new(&result(output, None));
+        assert!(!viewer.is_json());
+        viewer.width.set(40);
+        assert_eq!(viewer.row_count(), 5);
+        assert_eq!(viewer.rows()[2], (0, 80..120));
+
+        viewer.scroll_by(3);
+        assert_eq!(viewer.scroll, 3);
+        viewer.scroll_by(10);
+        assert_eq!(viewer.scroll, 4);
+        assert_eq!(viewer.top_line(), 0);
+
+        viewer.search = "NEEDLE".to_string();
+        assert_eq!(viewer.matches(), [2]);
+        viewer.scroll = 0;
+        assert!(viewer.jump(true));
+        assert_eq!(viewer.scroll, 2);
+        let ranges = match_ranges(&viewer.text(), "NEEDLE");
+        assert_eq!((ranges.len(), &ranges[0]), (1, &(100..106)));
+
+        viewer.wrap = false;
+        assert_eq!(viewer.row_count(), 1);
+        assert_eq!(viewer.matches(), [0]);
+    }
+}